@@ -0,0 +1,37 @@
+//! Helpers shared by the benchmarks.
+#![allow(dead_code)]
+
+use kolor::{Float, Vec3};
+use std::time::{Duration, Instant};
+
+/// The number of steps per axis of the grid of benchmarked colors.
+pub const STEPS: usize = 64;
+/// How often each benchmark runs over all colors.
+pub const ROUNDS: usize = 20;
+
+/// Returns a `STEPS`³ grid over the unit cube, with the first component
+/// changing fastest.
+pub fn unit_cube() -> Vec<Vec3> {
+    (0..STEPS * STEPS * STEPS)
+        .map(|i| {
+            let [r, g, b] = [i % STEPS, i / STEPS % STEPS, i / (STEPS * STEPS)]
+                .map(|c| c as Float / (STEPS - 1) as Float);
+            Vec3::new(r, g, b)
+        })
+        .collect()
+}
+
+/// Runs `round` `ROUNDS` times, prints the time per color for `colors`
+/// colors per round under `name` and returns the total time.
+pub fn time(name: &str, colors: usize, mut round: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        round();
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{name}: {:.1} ns per color",
+        elapsed.as_nanos() as f64 / (ROUNDS * colors) as f64
+    );
+    elapsed
+}
@@ -0,0 +1,37 @@
+//! Compares the speed of `ColorConversion::convert_slice()` with converting
+//! each color with `ColorConversion::convert()` on the same buffer, for a
+//! conversion between linear spaces and one with transform functions.
+//!
+//! Run with `cargo bench --bench convert_slice`.
+mod common;
+
+use common::time;
+use kolor::{ColorConversion, ColorSpace, spaces};
+use std::hint::black_box;
+
+fn main() {
+    let colors = common::unit_cube();
+
+    let compare = |name: &str, src: ColorSpace, dst: ColorSpace| {
+        let conversion = ColorConversion::new(src, dst);
+        let mut buffer = colors.clone();
+        let per_color = time(&format!("{name} per color"), colors.len(), || {
+            buffer.copy_from_slice(&colors);
+            for value in buffer.iter_mut() {
+                *value = conversion.convert(*value);
+            }
+            black_box(&mut buffer);
+        });
+        let slice = time(&format!("{name} slice"), colors.len(), || {
+            buffer.copy_from_slice(&colors);
+            conversion.convert_slice(&mut buffer);
+            black_box(&mut buffer);
+        });
+        println!(
+            "{name} speedup: {:.2}x",
+            per_color.as_secs_f64() / slice.as_secs_f64()
+        );
+    };
+    compare("linear", spaces::LINEAR_SRGB, spaces::ACES_CG);
+    compare("encoded", spaces::ENCODED_SRGB, spaces::ENCODED_DISPLAY_P3);
+}
@@ -0,0 +1,36 @@
+//! Compares the speed of the approximate Oklab conversion of
+//! `Color::to_oklab_fast()` with the exact one, on CIE XYZ values of the sRGB
+//! gamut.
+//!
+//! Run with `cargo bench --bench oklab_fast`.
+mod common;
+
+use common::time;
+use kolor::{
+    Color, Vec3,
+    details::{
+        color::WhitePoint,
+        transform::{xyz_to_ok_lab, xyz_to_ok_lab_fast},
+    },
+    spaces,
+};
+use std::hint::black_box;
+
+fn main() {
+    let colors: Vec<Vec3> = common::unit_cube()
+        .into_iter()
+        .map(|rgb| Color::srgb(rgb.x, rgb.y, rgb.z).to(spaces::CIE_XYZ).value)
+        .collect();
+
+    let run = |convert: fn(Vec3, WhitePoint) -> Vec3| {
+        let colors = &colors;
+        move || {
+            for color in colors {
+                black_box(convert(black_box(*color), WhitePoint::D65));
+            }
+        }
+    };
+    let exact = time("exact", colors.len(), run(xyz_to_ok_lab));
+    let fast = time("fast", colors.len(), run(xyz_to_ok_lab_fast));
+    println!("speedup: {:.2}x", exact.as_secs_f64() / fast.as_secs_f64());
+}
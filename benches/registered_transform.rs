@@ -0,0 +1,42 @@
+//! Compares the speed of a conversion using a transform function registered at
+//! runtime with the same conversion using the built-in transform function, to
+//! measure the cost of dispatching to registered functions.
+//!
+//! Run with `cargo bench --bench registered_transform`.
+mod common;
+
+use common::time;
+use kolor::{
+    ColorConversion,
+    details::{
+        color::TransformFn,
+        registry::register_transform,
+        transform::{srgb_eotf, srgb_oetf},
+    },
+    spaces,
+};
+use std::hint::black_box;
+
+fn main() {
+    let colors = common::unit_cube();
+
+    let run = |transform_fn: TransformFn| {
+        let conversion = ColorConversion::new(
+            spaces::LINEAR_SRGB.with_transform(transform_fn),
+            spaces::ACES_CG,
+        );
+        let colors = &colors;
+        move || {
+            for color in colors {
+                black_box(conversion.convert(black_box(*color)));
+            }
+        }
+    };
+    let registered = register_transform("benchmark srgb", srgb_oetf, srgb_eotf);
+    let built_in = time("built-in", colors.len(), run(TransformFn::Srgb));
+    let registered = time("registered", colors.len(), run(registered));
+    println!(
+        "overhead: {:.2}x",
+        registered.as_secs_f64() / built_in.as_secs_f64()
+    );
+}
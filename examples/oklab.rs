@@ -9,4 +9,10 @@ pub fn main() {
     let modified_srgb = oklab.to(srgb.space);
 
     println!(" {srgb:?} -> {modified_srgb:?}");
+
+    // shift the hue in Oklch, the polar form of Oklab
+    let rotated_srgb = srgb.rotate_hue(90.0, 0.002);
+    let lch = rotated_srgb.to(spaces::OK_LCH);
+
+    println!(" {srgb:?} -> {rotated_srgb:?} ({lch:?})");
 }
@@ -19,9 +19,7 @@ fn main() {
         }
     }
     let mut out_str = String::with_capacity(conversions.len() * 256);
-    out_str += "use super::{
-    color::{RgbPrimaries, WhitePoint},
-};
+    out_str += "use super::color::{RgbPrimaries, WhitePoint};
 use crate::Mat3;\n\n";
     let mut const_matches = String::with_capacity(conversions.len() * 128);
     for conversion in conversions {
@@ -67,13 +65,16 @@ pub const {}_TO_{}: Mat3 = Mat3::from_cols_array(&[
 
     out_str += &format!(
         r#"
-pub fn const_conversion_matrix(
+/// Returns the bundled matrix converting from the source to the destination
+/// primaries and white point, if there is one. Identical primaries and white
+/// points give the identity.
+pub const fn const_conversion_matrix(
     src_primaries: RgbPrimaries,
     src_wp: WhitePoint,
     dst_primaries: RgbPrimaries,
     dst_wp: WhitePoint,
 ) -> Option<Mat3> {{
-    if src_primaries == dst_primaries && src_wp == dst_wp {{
+    if src_primaries.const_eq(&dst_primaries) && src_wp.const_eq(&dst_wp) {{
         return Some(Mat3::IDENTITY);
     }}
     match (src_primaries, src_wp, dst_primaries, dst_wp) {{
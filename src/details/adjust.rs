@@ -0,0 +1,507 @@
+//! Perceptual color adjustments, mostly implemented in Oklch.
+use super::{
+    color::{Color, ColorSpace, RgbPrimaries, TransformFn, color_spaces},
+    transform::xyz_to_cie_1960_ucs,
+};
+use crate::{Float, PI, TAU, Vec3};
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+use num_traits::Float as _;
+
+/// Oklch chroma at which [`Color::vibrance()`] stops boosting, roughly the
+/// most saturated colors in sRGB.
+const VIBRANCE_FULL_CHROMA: Float = 0.32;
+
+/// Stops above and below the pivot over which
+/// [`Color::apply_contrast_curve()`] rolls off.
+const CONTRAST_CURVE_STOPS: Float = 8.0;
+
+impl Color {
+    /// Boosts (or, for negative `amount`, reduces) the saturation of the
+    /// color, affecting less saturated colors more than already saturated
+    /// ones.
+    ///
+    /// Unlike a uniform saturation change, which scales Oklch chroma by the
+    /// same factor for every color, the factor here is
+    /// `1 + amount * (1 - chroma / 0.32)²`: pale colors get close to the full
+    /// `amount`, while the boost falls off quadratically towards the most
+    /// vivid colors, which are left as they are. This protects skin tones and
+    /// already vivid colors from oversaturating. Oklch lightness and hue are
+    /// kept.
+    ///
+    /// The result is not mapped into any gamut. The returned color is in the
+    /// same space as `self`.
+    pub fn vibrance(&self, amount: Float) -> Color {
+        let lch = self.to(color_spaces::OK_LCH).value;
+        let falloff = (1.0 - lch.y / VIBRANCE_FULL_CHROMA).max(0.0);
+        let chroma = (lch.y * (1.0 + amount * falloff * falloff)).max(0.0);
+        Color::new(lch.x, chroma, lch.z, color_spaces::OK_LCH).to(self.space)
+    }
+
+    /// Rotates the Oklch hue of the color by `degrees`, keeping Oklch
+    /// lightness and chroma.
+    ///
+    /// Colors with an Oklch chroma of at most `achromatic_lock` are returned
+    /// unchanged, see [`is_achromatic()`](Self::is_achromatic). Rounding makes
+    /// the hue of near-grays arbitrary, so rotating them would give them a
+    /// faint tint; locking them keeps grays gray when shifting the hue of a
+    /// whole image. Use 0 to rotate every color with a hue.
+    ///
+    /// The rotated hue wraps around to stay in the [-π, π] range of Oklch
+    /// hues, like those of [`OK_LCH`](color_spaces::OK_LCH) colors. The result
+    /// is not mapped into any gamut. The returned color is in the same space as
+    /// `self`.
+    pub fn rotate_hue(&self, degrees: Float, achromatic_lock: Float) -> Color {
+        let lch = self.to(color_spaces::OK_LCH).value;
+        if lch.y <= achromatic_lock {
+            return *self;
+        }
+        let hue = lch.z + degrees * (PI / 180.0);
+        let hue = hue - TAU * ((hue + PI) / TAU).floor();
+        Color::new(lch.x, lch.y, hue, color_spaces::OK_LCH).to(self.space)
+    }
+
+    /// Returns `true` if the color is neutral, i.e. a gray, white or black,
+    /// within `epsilon`.
+    ///
+    /// The color is neutral when its Oklch chroma is at most `epsilon`. Oklch
+    /// chroma is roughly perceptually uniform, so the same `epsilon` works for
+    /// dark and bright colors; around 0.002 is barely noticeable. The hue of
+    /// colors for which this is `true` is unreliable.
+    pub fn is_achromatic(&self, epsilon: Float) -> bool {
+        self.to(color_spaces::OK_LCH).value.y <= epsilon
+    }
+
+    /// Brings the color into the [0, 1] range of its linear space by
+    /// desaturating it towards white, instead of clamping each channel.
+    ///
+    /// Clamping channels separately shifts the hue of bright saturated colors,
+    /// e.g. towards cyan or magenta for a bright blue, as one channel clips
+    /// before the others. Instead, when a linear channel exceeds 1, the color is
+    /// mixed with the gray of the same luminance just enough to bring the
+    /// largest channel to 1, which keeps the hue and the luminance. Colors with
+    /// a luminance of 1 or more clip to white, like highlights on film.
+    /// Channels below 0 are left as they are. The returned color is in the same
+    /// space as `self`.
+    ///
+    /// In spaces with [XYZ primaries](RgbPrimaries::CieXyz), white and the
+    /// grays are the XYZ of the white point scaled by their luminance, and
+    /// each channel is limited to the one of the white point instead of 1.
+    pub fn clip_to_white_preserve_hue(&self) -> Color {
+        let linear = self.to_linear();
+        let white = if linear.space.primaries() == RgbPrimaries::CieXyz {
+            linear.space.white_point().xyz()
+        } else {
+            Vec3::splat(1.0)
+        };
+        // Relative to white, so the white is 1 in every channel.
+        let rgb = linear.value;
+        let rgb = Vec3::new(rgb.x / white.x, rgb.y / white.y, rgb.z / white.z);
+        let max = rgb.x.max(rgb.y).max(rgb.z);
+        if max <= 1.0 {
+            return *self;
+        }
+        let luminance = linear.relative_luminance();
+        let relative = if luminance >= 1.0 {
+            Vec3::splat(1.0)
+        } else {
+            let saturation = (1.0 - luminance) / (max - luminance);
+            Vec3::splat(luminance) + (rgb - Vec3::splat(luminance)) * saturation
+        };
+        let value = relative * white;
+        Color { value, ..linear }.to(self.space)
+    }
+
+    /// Inverts the color in `space`, returning it in the same space as `self`.
+    ///
+    /// RGB spaces invert each channel, `1 - value`, so inverting in encoded
+    /// sRGB, linear sRGB or any other space gives visibly different results.
+    /// Lab-like and cylindrical spaces invert only the lightness and keep hue
+    /// and chroma: Oklab/Oklch lightness is inverted in [0, 1], CIE LAB/Lch/Luv
+    /// and CAM16-UCS lightness in [0, 100], HSL/HSV/HSI the last component, and
+    /// HWB swaps whiteness and blackness. CIE xyY, CIE 1960 UCS and its uvV
+    /// form invert `Y` and keep the chromaticity, and CIE 1964 UVW inverts W*
+    /// in [-17, 100]. Y'CbCr and ICtCp invert the luma or intensity and negate
+    /// the chroma components, like inverting the encoded channels.
+    pub fn invert(&self, space: ColorSpace) -> Color {
+        let mut value = self.to(space).value;
+        match space.transform_function() {
+            Some(TransformFn::OkLab | TransformFn::OkLch) => value.x = 1.0 - value.x,
+            Some(
+                TransformFn::CieLab
+                | TransformFn::CieLch
+                | TransformFn::Cie1976Luv
+                | TransformFn::Cam16Ucs(_),
+            ) => value.x = 100.0 - value.x,
+            Some(
+                TransformFn::Hsl
+                | TransformFn::Hsv
+                | TransformFn::Hsi
+                | TransformFn::CieXyY
+                | TransformFn::Cie1960UcsUvV,
+            ) => value.z = 1.0 - value.z,
+            Some(TransformFn::Cie1960Ucs) => {
+                // UVW is linear in XYZ, so scaling it keeps the chromaticity.
+                // Black has none and inverts to the white point.
+                value = if value.y > 0.0 {
+                    value * ((1.0 - value.y) / value.y)
+                } else {
+                    let white_point = space.white_point();
+                    xyz_to_cie_1960_ucs(white_point.xyz(), white_point)
+                }
+            }
+            Some(TransformFn::Cie1964Uvw) => value.z = 83.0 - value.z,
+            Some(TransformFn::YCbCr(_) | TransformFn::IctCpPq | TransformFn::IctCpHlg) => {
+                value = Vec3::new(1.0 - value.x, -value.y, -value.z)
+            }
+            Some(TransformFn::Hwb) => value = Vec3::new(value.x, value.z, value.y),
+            _ => value = Vec3::splat(1.0) - value,
+        }
+        Color::new(value.x, value.y, value.z, space).to(self.space)
+    }
+
+    /// Changes the contrast of the color with an S-curve around the luminance
+    /// `pivot`, typically middle gray at `0.18`, returning it in the same space
+    /// as `self`.
+    ///
+    /// The curve acts on the relative luminance in stops from the pivot, `x =
+    /// log2(Y / pivot)`, as `x' = 8 * tanh(slope * atanh(x / 8))`. Its slope
+    /// at the pivot is `slope` and it flattens towards 8 stops above and below
+    /// the pivot, so shadows and highlights roll off instead of clipping. The
+    /// linear channels are scaled by the ratio of the luminances, which keeps
+    /// the chromaticity.
+    ///
+    /// A `slope` of 1 keeps the color, larger slopes push luminances away from
+    /// the pivot and smaller ones pull them towards it. Applying `1 / slope`
+    /// reverses the curve. Colors without a positive luminance or 8 or more
+    /// stops away from the pivot are returned as they are.
+    pub fn apply_contrast_curve(&self, slope: Float, pivot: Float) -> Color {
+        let linear = self.to_linear();
+        let luminance = linear.relative_luminance();
+        if luminance <= 0.0 {
+            return *self;
+        }
+        let stops = (luminance / pivot).log2() / CONTRAST_CURVE_STOPS;
+        if stops.abs() >= 1.0 {
+            return *self;
+        }
+        let curved = (slope * stops.atanh()).tanh() * CONTRAST_CURVE_STOPS;
+        let scale = pivot * curved.exp2() / luminance;
+        Color {
+            value: linear.value * scale,
+            ..linear
+        }
+        .to(self.space)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::details::color::WhitePoint;
+
+    fn chroma(color: Color) -> Float {
+        color.to(color_spaces::OK_LCH).value.y
+    }
+
+    #[test]
+    fn hue_rotation_locks_near_grays() {
+        let mut pixels = [
+            Color::srgb(0.5, 0.5, 0.5),
+            Color::srgb(0.5, 0.5, 0.502),
+            Color::srgb(0.9, 0.2, 0.1),
+            Color::srgb(0.1, 0.6, 0.3),
+        ];
+        let original = pixels;
+        for pixel in &mut pixels {
+            *pixel = pixel.rotate_hue(120.0, 0.002);
+        }
+        for (rotated, original) in pixels[..2].iter().zip(&original) {
+            assert_eq!(rotated.value, original.value);
+        }
+        for (rotated, original) in pixels[2..].iter().zip(&original[2..]) {
+            let before = original.to(color_spaces::OK_LCH).value;
+            let after = rotated.to(color_spaces::OK_LCH).value;
+            assert!((before.y - after.y).abs() < 1e-3, "{before:?} {after:?}");
+            let difference = after.z - before.z;
+            let rotation = 2.0 * PI / 3.0;
+            assert!(
+                (difference.cos() - rotation.cos()).abs() < 1e-3
+                    && (difference.sin() - rotation.sin()).abs() < 1e-3,
+                "{difference}"
+            );
+        }
+
+        let tinted = original[1].rotate_hue(120.0, 0.0);
+        assert_ne!(tinted.value, original[1].value);
+    }
+
+    #[test]
+    fn hue_rotation_wraps_around() {
+        let lch = Color::new(0.7, 0.1, 3.0, color_spaces::OK_LCH);
+        for degrees in [90.0, 360.0, -720.0, 1000.0] {
+            let hue = lch.rotate_hue(degrees, 0.0).value.z;
+            assert!((-PI..=PI).contains(&hue), "{degrees}: {hue}");
+            let expected = 3.0 + degrees * (PI / 180.0);
+            assert!(
+                (hue.sin() - expected.sin()).abs() < 1e-4
+                    && (hue.cos() - expected.cos()).abs() < 1e-4,
+                "{degrees}: {hue}"
+            );
+        }
+        let full_turn = Color::srgb(0.9, 0.2, 0.1).rotate_hue(360.0, 0.0);
+        assert!(
+            full_turn
+                .value
+                .abs_diff_eq(Color::srgb(0.9, 0.2, 0.1).value, 1e-4)
+        );
+    }
+
+    #[test]
+    fn vibrance_favors_pale_colors() {
+        let pale = Color::srgb(0.7, 0.6, 0.55);
+        let saturated = Color::srgb(0.9, 0.2, 0.1);
+        let pale_gain = chroma(pale.vibrance(0.5)) / chroma(pale);
+        let saturated_gain = chroma(saturated.vibrance(0.5)) / chroma(saturated);
+        assert!(
+            pale_gain > saturated_gain,
+            "{pale_gain} <= {saturated_gain}"
+        );
+        assert!(saturated_gain > 1.0);
+
+        let lch = pale.to(color_spaces::OK_LCH).value;
+        let boosted = pale.vibrance(0.5).to(color_spaces::OK_LCH).value;
+        assert!((lch.x - boosted.x).abs() < 1e-5 && (lch.z - boosted.z).abs() < 1e-4);
+    }
+
+    #[test]
+    fn negative_vibrance_desaturates() {
+        let pale = Color::srgb(0.7, 0.6, 0.55);
+        assert!(chroma(pale.vibrance(-0.5)) < chroma(pale));
+        let gray = Color::srgb(0.5, 0.5, 0.5).vibrance(1.0).value;
+        assert!(
+            (gray.x - 0.5).abs() < 1e-4 && (gray.z - 0.5).abs() < 1e-4,
+            "{gray:?}"
+        );
+    }
+
+    #[test]
+    fn bright_blue_clips_towards_white() {
+        let blue = Color::new(0.2, 0.2, 3.0, color_spaces::LINEAR_SRGB);
+        let clipped = blue.clip_to_white_preserve_hue();
+        let rgb = clipped.value;
+        assert!((rgb.z - 1.0).abs() < 1e-5, "{rgb:?}");
+        // Red and green stay equal, so the hue doesn't shift towards cyan or
+        // magenta, and both rise towards white.
+        assert!((rgb.x - rgb.y).abs() < 1e-5 && rgb.x > 0.2, "{rgb:?}");
+        let luminance = clipped.relative_luminance();
+        assert!((luminance - blue.relative_luminance()).abs() < 1e-5);
+
+        let srgb = Color::srgb(0.5, 0.2, 0.9);
+        assert_eq!(srgb.clip_to_white_preserve_hue().value, srgb.value);
+        let white = Color::new(4.0, 3.0, 2.0, color_spaces::LINEAR_SRGB);
+        let white = white.clip_to_white_preserve_hue().value;
+        assert_eq!(white, Vec3::splat(1.0));
+
+        // XYZ grays have the chromaticity of the white point.
+        let xyz = ColorSpace::linear(RgbPrimaries::CieXyz, WhitePoint::D50);
+        let d50 = WhitePoint::D50.xyz();
+        let white = Color::new(3.0, 2.0, 1.0, xyz).clip_to_white_preserve_hue();
+        assert!(white.value.abs_diff_eq(d50, 1e-6), "{white:?}");
+        let bright = Color::new(0.2, 0.5, 1.5, xyz).clip_to_white_preserve_hue();
+        let relative = Vec3::new(
+            bright.value.x / d50.x,
+            bright.value.y / d50.y,
+            bright.value.z / d50.z,
+        );
+        assert!((relative.z - 1.0).abs() < 1e-5, "{relative:?}");
+        assert!((bright.value.y - 0.5).abs() < 1e-5, "{bright:?}");
+    }
+
+    #[test]
+    fn achromatic_colors() {
+        assert!(Color::srgb(0.5, 0.5, 0.5).is_achromatic(1e-4));
+        assert!(Color::srgb(1.0, 1.0, 1.0).is_achromatic(1e-4));
+        assert!(Color::srgb(0.0, 0.0, 0.0).is_achromatic(1e-4));
+        assert!(Color::new(0.3, 0.3, 0.3, color_spaces::ACES_CG).is_achromatic(1e-4));
+        let faint = Color::srgb(0.5, 0.5, 0.52);
+        assert!(!faint.is_achromatic(1e-3));
+        assert!(faint.is_achromatic(0.1));
+    }
+
+    #[test]
+    fn invert_in_space() {
+        let white = Color::srgb(1.0, 1.0, 1.0);
+        let black = white.invert(color_spaces::LINEAR_SRGB);
+        assert!(
+            black.value.abs_diff_eq(Vec3::ZERO, 1e-5),
+            "{:?}",
+            black.value
+        );
+
+        let color = Color::srgb(0.8, 0.3, 0.2);
+        let lab = color.to(color_spaces::OK_LAB).value;
+        let inverted = color.invert(color_spaces::OK_LAB);
+        assert!(inverted.space == color.space);
+        let inverted_lab = inverted.to(color_spaces::OK_LAB).value;
+        assert!(
+            (inverted_lab.x - (1.0 - lab.x)).abs() < 1e-4
+                && (inverted_lab.y - lab.y).abs() < 1e-4
+                && (inverted_lab.z - lab.z).abs() < 1e-4,
+            "{lab:?} {inverted_lab:?}"
+        );
+        // Mid gray in Oklab lightness stays (almost) where it is.
+        let gray = Color::new(0.5, 0.0, 0.0, color_spaces::OK_LAB);
+        let gray_inverted = gray.invert(color_spaces::OK_LCH).value;
+        assert!(
+            gray_inverted.abs_diff_eq(gray.value, 1e-4),
+            "{gray_inverted:?}"
+        );
+
+        for space in [
+            color_spaces::ENCODED_SRGB,
+            color_spaces::LINEAR_SRGB,
+            color_spaces::OK_LCH,
+            color_spaces::LINEAR_SRGB.to_cie_lab(),
+            ColorSpace::new(RgbPrimaries::Bt709, WhitePoint::D65, Some(TransformFn::Hsl)),
+            color_spaces::HWB,
+        ] {
+            let twice = color.invert(space).invert(space);
+            assert!(
+                twice.value.abs_diff_eq(color.value, 1e-3),
+                "{space:?}: {:?} != {:?}",
+                twice.value,
+                color.value
+            );
+        }
+
+        let encoded = color.invert(color_spaces::ENCODED_SRGB).value;
+        let linear = color.invert(color_spaces::LINEAR_SRGB).value;
+        assert!(!encoded.abs_diff_eq(linear, 1e-2), "{encoded:?} {linear:?}");
+        // HWB keeps the hue, unlike inverting the RGB channels.
+        let hwb = color.to(color_spaces::HWB).value;
+        let inverted_hwb = color.invert(color_spaces::HWB).to(color_spaces::HWB).value;
+        let swapped = Vec3::new(hwb.x, hwb.z, hwb.y);
+        assert!(
+            inverted_hwb.abs_diff_eq(swapped, 1e-4),
+            "{inverted_hwb:?} != {swapped:?}"
+        );
+    }
+
+    #[test]
+    fn invert_chromaticity_and_opponent_spaces() {
+        let color = Color::srgb(0.8, 0.3, 0.2);
+        let chromaticity = |value: Vec3| {
+            let sum = value.x + value.y + value.z;
+            Vec3::new(value.x / sum, value.y / sum, 0.0)
+        };
+        let luminance = color.relative_luminance();
+
+        let ucs = color_spaces::CIE_XYZ.with_transform(TransformFn::Cie1960Ucs);
+        let inverted = color.invert(ucs).to(ucs).value;
+        let original = color.to(ucs).value;
+        assert!(
+            (inverted.y - (1.0 - luminance)).abs() < 1e-4,
+            "{inverted:?}"
+        );
+        assert!(
+            chromaticity(inverted).abs_diff_eq(chromaticity(original), 1e-4),
+            "{inverted:?} {original:?}"
+        );
+        let black = Color::srgb(0.0, 0.0, 0.0)
+            .invert(ucs)
+            .to(color_spaces::LINEAR_SRGB);
+        assert!(black.value.abs_diff_eq(Vec3::splat(1.0), 1e-4), "{black:?}");
+
+        let uvv = color_spaces::CIE_XYZ.with_transform(TransformFn::Cie1960UcsUvV);
+        let inverted = color.invert(uvv).to(uvv).value;
+        let original = color.to(uvv).value;
+        assert!(
+            (inverted.z - (1.0 - luminance)).abs() < 1e-4,
+            "{inverted:?}"
+        );
+        assert!(
+            (inverted.x - original.x).abs() < 1e-4 && (inverted.y - original.y).abs() < 1e-4,
+            "{inverted:?} {original:?}"
+        );
+
+        for space in [color_spaces::ICT_CP_PQ, color_spaces::ICT_CP_HLG] {
+            let original = color.to(space).value;
+            let inverted = color.invert(space).to(space).value;
+            let expected = Vec3::new(1.0 - original.x, -original.y, -original.z);
+            assert!(
+                inverted.abs_diff_eq(expected, 1e-4),
+                "{space:?}: {inverted:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn contrast_curve_around_pivot() {
+        let pivot = 0.18;
+        for color in [Color::srgb(0.8, 0.3, 0.2), Color::srgb(0.1, 0.15, 0.3)] {
+            let same = color.apply_contrast_curve(1.0, pivot);
+            assert!(
+                same.value.abs_diff_eq(color.value, 1e-5),
+                "{:?}",
+                same.value
+            );
+
+            let luminance = color.relative_luminance();
+            let contrasty = color.apply_contrast_curve(2.0, pivot);
+            let distance = |luminance: Float| (luminance / pivot).log2().abs();
+            let new_distance = distance(contrasty.relative_luminance());
+            assert!(
+                new_distance > distance(luminance) && new_distance < 2.0 * distance(luminance),
+                "{luminance} {new_distance}"
+            );
+            let chromaticity = color.to(color_spaces::CIE_XYZ).value;
+            let new_chromaticity = contrasty.to(color_spaces::CIE_XYZ).value;
+            assert!(
+                (chromaticity / chromaticity.y)
+                    .abs_diff_eq(new_chromaticity / new_chromaticity.y, 1e-4)
+            );
+
+            let reversed = contrasty.apply_contrast_curve(0.5, pivot);
+            assert!(
+                reversed.value.abs_diff_eq(color.value, 1e-4),
+                "{:?}",
+                reversed.value
+            );
+        }
+        let bright = Color::new(0.5, 0.5, 0.5, color_spaces::LINEAR_SRGB);
+        assert!(bright.apply_contrast_curve(1.5, pivot).relative_luminance() > 0.5);
+        let dark = Color::new(0.05, 0.05, 0.05, color_spaces::LINEAR_SRGB);
+        assert!(dark.apply_contrast_curve(1.5, pivot).relative_luminance() < 0.05);
+        let gray = Color::new(pivot, pivot, pivot, color_spaces::LINEAR_SRGB);
+        assert!(
+            gray.apply_contrast_curve(3.0, pivot)
+                .value
+                .abs_diff_eq(gray.value, 1e-5)
+        );
+        let black = Color::srgb(0.0, 0.0, 0.0);
+        assert_eq!(black.apply_contrast_curve(2.0, pivot).value, black.value);
+
+        // Close to the pivot the curve has the given slope in stops, further
+        // away it rolls off towards 8 stops.
+        let stops = |luminance: Float| (luminance / pivot).log2();
+        let curved = |luminance: Float| {
+            let gray = Color::new(luminance, luminance, luminance, color_spaces::LINEAR_SRGB);
+            stops(gray.apply_contrast_curve(2.0, pivot).relative_luminance())
+        };
+        let near = pivot * 1.01;
+        assert!(
+            (curved(near) - 2.0 * stops(near)).abs() < 1e-4,
+            "{}",
+            curved(near)
+        );
+        let far = pivot * 32.0;
+        assert!(
+            curved(far) > stops(far) && curved(far) < 8.0,
+            "{}",
+            curved(far)
+        );
+        assert!(curved(pivot * 32.0) - curved(pivot * 16.0) < stops(32.0) - stops(16.0));
+        let beyond = Color::new(100.0, 100.0, 100.0, color_spaces::LINEAR_SRGB);
+        assert_eq!(beyond.apply_contrast_curve(2.0, pivot).value, beyond.value);
+    }
+}
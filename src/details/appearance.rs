@@ -0,0 +1,416 @@
+//! Color appearance effects, which make colors look different from what their
+//! colorimetry suggests, and the CAM16 color appearance model.
+use super::{
+    color::{Color, WhitePoint},
+    transform::xyz_to_cie_lab,
+};
+use crate::{Float, Mat3, PI, Vec3};
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+use num_traits::Float as _;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Nayatani's K_Br for an adapting luminance of 63.66 cd/m².
+const ADAPTING_LUMINANCE_FACTOR: Float = 1.0;
+
+/// The CAM16 matrix from CIE XYZ to the sharpened cone responses.
+#[rustfmt::skip]
+const M16: Mat3 = Mat3::from_cols_array(&[
+    0.401288, -0.250268, -0.002079,
+    0.650173, 1.204414, 0.048952,
+    -0.051461, 0.045854, 0.953127,
+]);
+
+/// The surround of a [`Cam16ViewingConditions`], i.e. the luminance of the
+/// area around the viewed field relative to its white.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Cam16Surround {
+    /// E.g. viewing surface colors or a screen in a lit room.
+    Average,
+    /// E.g. watching television in a dim room.
+    Dim,
+    /// E.g. projecting film in a dark room.
+    Dark,
+}
+
+impl Cam16Surround {
+    /// Returns the factor of the degree of adaptation F, the impact of the
+    /// surround c and the chromatic induction factor N_c.
+    fn factors(self) -> (Float, Float, Float) {
+        match self {
+            Self::Average => (1.0, 0.69, 1.0),
+            Self::Dim => (0.9, 0.59, 0.9),
+            Self::Dark => (0.8, 0.525, 0.8),
+        }
+    }
+}
+
+/// The viewing conditions of the CAM16 color appearance model, for
+/// [`TransformFn::Cam16Ucs`](super::color::TransformFn::Cam16Ucs).
+///
+/// The white of the viewing conditions is the white point of the color space.
+/// Conditions compare and hash by the exact bits of their values.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Cam16ViewingConditions {
+    /// The luminance of the adapting field L_A in cd/m², commonly 20% of the
+    /// luminance of the white.
+    pub adapting_luminance: Float,
+    /// The luminance of the background Y_b relative to the white, in
+    /// [0, 100].
+    pub background_luminance: Float,
+    pub surround: Cam16Surround,
+}
+
+impl Cam16ViewingConditions {
+    /// The viewing conditions of the sRGB reference viewing environment of
+    /// IEC 61966-2-1: an adapting luminance of 64 lx / π at 20% reflectance,
+    /// a background of 20% and an average surround.
+    pub const DEFAULT: Self = Self::new(64.0 / PI * 0.2, 20.0, Cam16Surround::Average);
+
+    pub const fn new(
+        adapting_luminance: Float,
+        background_luminance: Float,
+        surround: Cam16Surround,
+    ) -> Self {
+        Self {
+            adapting_luminance,
+            background_luminance,
+            surround,
+        }
+    }
+
+    fn to_bits(self) -> (u64, u64, Cam16Surround) {
+        #[allow(clippy::unnecessary_cast)]
+        let bits = |value: Float| value.to_bits() as u64;
+        (
+            bits(self.adapting_luminance),
+            bits(self.background_luminance),
+            self.surround,
+        )
+    }
+}
+
+impl Default for Cam16ViewingConditions {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl PartialEq for Cam16ViewingConditions {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bits() == other.to_bits()
+    }
+}
+
+impl Eq for Cam16ViewingConditions {}
+
+impl core::hash::Hash for Cam16ViewingConditions {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.to_bits().hash(state);
+    }
+}
+
+/// The values of CAM16 which only depend on the viewing conditions and white
+/// point.
+#[derive(Copy, Clone)]
+pub(crate) struct Cam16Parameters {
+    /// The scale of the cone responses for the chromatic adaptation.
+    d_rgb: Vec3,
+    /// The luminance level adaptation factor F_L.
+    f_l: Float,
+    c: Float,
+    n_c: Float,
+    n: Float,
+    z: Float,
+    n_bb: Float,
+    /// The achromatic response A_w of the white.
+    a_w: Float,
+}
+
+impl Cam16Parameters {
+    pub(crate) fn new(conditions: &Cam16ViewingConditions, white_point: WhitePoint) -> Self {
+        let (f, c, n_c) = conditions.surround.factors();
+        let l_a = conditions.adapting_luminance;
+        let white = white_point.xyz() * 100.0;
+        let rgb_w = M16 * white;
+        let d = (f * (1.0 - (1.0 / 3.6) * ((-l_a - 42.0) / 92.0).exp())).clamp(0.0, 1.0);
+        let d_rgb = Vec3::new(
+            d * white.y / rgb_w.x + 1.0 - d,
+            d * white.y / rgb_w.y + 1.0 - d,
+            d * white.y / rgb_w.z + 1.0 - d,
+        );
+        let k4 = (1.0 / (5.0 * l_a + 1.0)).powi(4);
+        let f_l = 0.2 * k4 * 5.0 * l_a + 0.1 * (1.0 - k4).powi(2) * (5.0 * l_a).cbrt();
+        let n = conditions.background_luminance / white.y;
+        let n_bb = 0.725 * n.powf(-0.2);
+        let mut parameters = Self {
+            d_rgb,
+            f_l,
+            c,
+            n_c,
+            n,
+            z: 1.48 + n.sqrt(),
+            n_bb,
+            a_w: 0.0,
+        };
+        let [r, g, b] = parameters.adapted_responses(white);
+        parameters.a_w = (2.0 * r + g + 0.05 * b) * n_bb;
+        parameters
+    }
+
+    /// Returns the post-adaptation cone responses of `xyz`, without the
+    /// offset of 0.1, which cancels out in all uses.
+    fn adapted_responses(&self, xyz: Vec3) -> [Float; 3] {
+        let rgb = M16 * xyz;
+        let adapt = |value: Float, scale: Float| {
+            let x = (self.f_l * (value * scale).abs() / 100.0).powf(0.42);
+            (400.0 * x / (x + 27.13)).copysign(value)
+        };
+        [
+            adapt(rgb.x, self.d_rgb.x),
+            adapt(rgb.y, self.d_rgb.y),
+            adapt(rgb.z, self.d_rgb.z),
+        ]
+    }
+
+    /// The inverse of [`adapted_responses()`](Self::adapted_responses).
+    fn xyz(&self, [r, g, b]: [Float; 3]) -> Vec3 {
+        let unadapt = |value: Float, scale: Float| {
+            let x = (27.13 * value.abs() / (400.0 - value.abs())).max(0.0);
+            (100.0 / self.f_l * x.powf(1.0 / 0.42)).copysign(value) / scale
+        };
+        M16.inverse()
+            * Vec3::new(
+                unadapt(r, self.d_rgb.x),
+                unadapt(g, self.d_rgb.y),
+                unadapt(b, self.d_rgb.z),
+            )
+    }
+
+    /// The factor of the chroma depending on the background.
+    fn background_chroma_factor(&self) -> Float {
+        (1.64 - (0.29 as Float).powf(self.n)).powf(0.73)
+    }
+
+    /// The factor of the chroma magnitude t depending on the hue.
+    fn chroma_magnitude_factor(&self, hue: Float) -> Float {
+        let eccentricity = 0.25 * ((hue + 2.0).cos() + 3.8);
+        50000.0 / 13.0 * self.n_c * self.n_bb * eccentricity
+    }
+}
+
+/// Converts CIE XYZ relative to `white_point`, with a Y of 1 for the white,
+/// to CAM16-UCS J', a', b' under the viewing `conditions`, per Li et al.
+/// (2017), "Comprehensive color solutions: CAM16, CAT16, and CAM16-UCS".
+pub fn xyz_to_cam16_ucs(
+    color: Vec3,
+    white_point: WhitePoint,
+    conditions: &Cam16ViewingConditions,
+) -> Vec3 {
+    xyz_to_cam16_ucs_with(color, &Cam16Parameters::new(conditions, white_point))
+}
+
+/// Like [`xyz_to_cam16_ucs()`], with the `parameters` of the viewing
+/// conditions and white point computed up front.
+pub(crate) fn xyz_to_cam16_ucs_with(color: Vec3, parameters: &Cam16Parameters) -> Vec3 {
+    let [r, g, b] = parameters.adapted_responses(color * 100.0);
+    let a = r - 12.0 * g / 11.0 + b / 11.0;
+    let b_ = (r + g - 2.0 * b) / 9.0;
+    let hue = b_.atan2(a);
+    let achromatic = (2.0 * r + g + 0.05 * b) * parameters.n_bb;
+    let j = 100.0
+        * (achromatic / parameters.a_w)
+            .max(0.0)
+            .powf(parameters.c * parameters.z);
+    let t = parameters.chroma_magnitude_factor(hue) * a.hypot(b_) / (r + g + 1.05 * b + 0.305);
+    let chroma = t.powf(0.9) * (j / 100.0).sqrt() * parameters.background_chroma_factor();
+    let colorfulness = chroma * parameters.f_l.powf(0.25);
+
+    let j_ucs = 1.7 * j / (1.0 + 0.007 * j);
+    let m_ucs = (1.0 + 0.0228 * colorfulness).ln() / 0.0228;
+    Vec3::new(j_ucs, m_ucs * hue.cos(), m_ucs * hue.sin())
+}
+
+/// The inverse of [`xyz_to_cam16_ucs()`].
+pub fn cam16_ucs_to_xyz(
+    color: Vec3,
+    white_point: WhitePoint,
+    conditions: &Cam16ViewingConditions,
+) -> Vec3 {
+    cam16_ucs_to_xyz_with(color, &Cam16Parameters::new(conditions, white_point))
+}
+
+/// The inverse of [`xyz_to_cam16_ucs_with()`].
+pub(crate) fn cam16_ucs_to_xyz_with(color: Vec3, parameters: &Cam16Parameters) -> Vec3 {
+    let j = (color.x / (1.7 - 0.007 * color.x)).max(0.0);
+    let hue = color.z.atan2(color.y);
+    let colorfulness = ((0.0228 * color.y.hypot(color.z)).exp() - 1.0) / 0.0228;
+    let chroma = colorfulness / parameters.f_l.powf(0.25);
+    let t = if chroma == 0.0 || j == 0.0 {
+        0.0
+    } else {
+        (chroma / ((j / 100.0).sqrt() * parameters.background_chroma_factor())).powf(1.0 / 0.9)
+    };
+    let achromatic = parameters.a_w * (j / 100.0).powf(1.0 / (parameters.c * parameters.z));
+
+    let p1 = parameters.chroma_magnitude_factor(hue);
+    let p2 = achromatic / parameters.n_bb;
+    let (sin, cos) = hue.sin_cos();
+    let gamma = 23.0 * (p2 + 0.305) * t / (23.0 * p1 + 11.0 * t * cos + 108.0 * t * sin);
+    let (a, b) = (gamma * cos, gamma * sin);
+    let responses = [
+        (460.0 * p2 + 451.0 * a + 288.0 * b) / 1403.0,
+        (460.0 * p2 - 891.0 * a - 261.0 * b) / 1403.0,
+        (460.0 * p2 - 220.0 * a - 6300.0 * b) / 1403.0,
+    ];
+    parameters.xyz(responses) * (1.0 / 100.0)
+}
+
+/// Returns the CIE 1976 u'v' chromaticity of `xyz`.
+fn uv_prime(xyz: Vec3) -> (Float, Float) {
+    let denominator = xyz.x + 15.0 * xyz.y + 3.0 * xyz.z;
+    (4.0 * xyz.x / denominator, 9.0 * xyz.y / denominator)
+}
+
+impl Color {
+    /// Returns the CIE L* of the color corrected for the
+    /// Helmholtz–Kohlrausch effect, i.e. the lightness of a gray which looks
+    /// as bright as the color.
+    ///
+    /// Saturated colors look brighter than grays of the same luminance, blues
+    /// and purples more so than yellows. This uses the variable achromatic
+    /// color (VAC) method of Nayatani (1997), "Simple estimation methods for
+    /// the Helmholtz–Kohlrausch effect", which predicts brightness matches of
+    /// colors against grays:
+    ///
+    /// `L*_HK = L* + (-0.1340 q(θ) + 0.0872 K_Br) s_uv L*`
+    ///
+    /// where θ and s_uv are the hue angle and saturation in CIE 1976 u'v'
+    /// relative to the white point of the color's space, q(θ) models the hue
+    /// dependency and K_Br the dependency on the adapting luminance. The
+    /// adapting luminance is fixed at 63.66 cd/m², for which K_Br is 1. Grays
+    /// return their CIE L*.
+    pub fn hk_adjusted_lightness(&self) -> Float {
+        let white_point = self.space.white_point();
+        let xyz = self.xyz();
+        let lightness = xyz_to_cie_lab(xyz, white_point).x;
+        if xyz.y <= 0.0 {
+            return lightness;
+        }
+        let (u, v) = uv_prime(xyz);
+        let (white_u, white_v) = uv_prime(white_point.xyz());
+        let (du, dv) = (u - white_u, v - white_v);
+        let saturation = 13.0 * (du * du + dv * dv).sqrt();
+        let theta = dv.atan2(du);
+        let q = -0.01585
+            - 0.03017 * theta.cos()
+            - 0.04556 * (2.0 * theta).cos()
+            - 0.02667 * (3.0 * theta).cos()
+            - 0.00295 * (4.0 * theta).cos()
+            + 0.14592 * theta.sin()
+            + 0.05084 * (2.0 * theta).sin()
+            - 0.01900 * (3.0 * theta).sin()
+            - 0.00764 * (4.0 * theta).sin();
+        lightness + (-0.1340 * q + 0.0872 * ADAPTING_LUMINANCE_FACTOR) * saturation * lightness
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::details::{
+        color::{TransformFn, color_spaces},
+        transform::ColorTransform,
+    };
+
+    #[test]
+    fn saturated_blue_looks_brighter() {
+        let blue = Color::srgb(0.0, 0.0, 1.0);
+        let lightness = xyz_to_cie_lab(blue.xyz(), blue.space.white_point()).x;
+        let adjusted = blue.hk_adjusted_lightness();
+        assert!(adjusted > lightness + 5.0, "{adjusted} <= {lightness}");
+
+        let yellow = Color::srgb(1.0, 1.0, 0.0);
+        let yellow_lightness = xyz_to_cie_lab(yellow.xyz(), yellow.space.white_point()).x;
+        let yellow_gain = yellow.hk_adjusted_lightness() - yellow_lightness;
+        assert!(adjusted - lightness > yellow_gain);
+    }
+
+    #[test]
+    fn grays_keep_their_lightness() {
+        let gray = Color::srgb(0.5, 0.5, 0.5);
+        let lightness = xyz_to_cie_lab(gray.xyz(), gray.space.white_point()).x;
+        assert!((gray.hk_adjusted_lightness() - lightness).abs() < 1e-3);
+        assert!(Color::srgb(0.0, 0.0, 0.0).hk_adjusted_lightness().abs() < 1e-6);
+    }
+
+    #[test]
+    fn cam16_ucs() {
+        // Li et al. (2017) example, as computed by colour-science.
+        let conditions = Cam16ViewingConditions::new(318.31, 20.0, Cam16Surround::Average);
+        let xyz = Vec3::new(0.1901, 0.2, 0.2178);
+        let ucs = xyz_to_cam16_ucs(xyz, WhitePoint::D65, &conditions);
+        let (j, m, h): (Float, Float, Float) = (41.73121, 0.1074368, 217.068 * PI / 180.0);
+        let j_ucs = 1.7 * j / (1.0 + 0.007 * j);
+        let m_ucs = (1.0 + 0.0228 * m).ln() / 0.0228;
+        let expected = Vec3::new(j_ucs, m_ucs * h.cos(), m_ucs * h.sin());
+        assert!(ucs.abs_diff_eq(expected, 1e-2), "{ucs:?} != {expected:?}");
+
+        for xyz in [
+            Vec3::new(0.1901, 0.2, 0.2178),
+            Vec3::new(0.4124, 0.2126, 0.0193),
+            Vec3::new(0.9505, 1.0, 1.089),
+            Vec3::new(0.0, 0.0, 0.0),
+        ] {
+            for conditions in [
+                conditions,
+                Cam16ViewingConditions::DEFAULT,
+                Cam16ViewingConditions::new(10.0, 10.0, Cam16Surround::Dark),
+            ] {
+                let back = cam16_ucs_to_xyz(
+                    xyz_to_cam16_ucs(xyz, WhitePoint::D65, &conditions),
+                    WhitePoint::D65,
+                    &conditions,
+                );
+                assert!(back.abs_diff_eq(xyz, 1e-4), "{back:?} != {xyz:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn cam16_ucs_color_space() {
+        let color = Color::srgb(0.8, 0.3, 0.1);
+        let ucs = color.to(color_spaces::CAM16_UCS);
+        let back = ucs.to(color_spaces::ENCODED_SRGB);
+        assert!(back.value.abs_diff_eq(color.value, 1e-4), "{back:?}");
+
+        let dark = color_spaces::CAM16_UCS.with_cam16_conditions(Cam16ViewingConditions::new(
+            10.0,
+            20.0,
+            Cam16Surround::Dark,
+        ));
+        assert_ne!(dark, color_spaces::CAM16_UCS);
+        assert!(color.to(dark).value.x > ucs.value.x);
+        assert_eq!(
+            TransformFn::by_name("CAM16-UCS"),
+            color_spaces::CAM16_UCS.transform_function()
+        );
+
+        // Transforms prepared for one white point stay correct for others.
+        let xyz = Vec3::new(0.4124, 0.2126, 0.0193);
+        let conditions = Cam16ViewingConditions::DEFAULT;
+        let transform = ColorTransform::with_white_point(
+            None,
+            Some(TransformFn::Cam16Ucs(conditions)),
+            WhitePoint::D65,
+        )
+        .unwrap();
+        for white_point in [WhitePoint::D65, WhitePoint::D50] {
+            let ucs = transform.apply(xyz, white_point);
+            let expected = xyz_to_cam16_ucs(xyz, white_point, &conditions);
+            assert!(ucs.abs_diff_eq(expected, 1e-5), "{ucs:?} != {expected:?}");
+        }
+    }
+}
@@ -6,10 +6,15 @@
 //!
 //! [`Sharp`(LmsConeSpace::Sharp) is used as the default for conversions by
 //! [`ColorConversion`][crate::details::conversion::ColorConversion].
-use crate::{Mat3, Vec3};
+use super::{
+    color::{RgbPrimaries, WhitePoint},
+    xyz::{rgb_to_xyz, xyz_to_rgb},
+};
+use crate::{Float, Mat3, Vec3};
 
 /// Supported conversion methods.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LmsConeSpace {
     VonKries,
     Bradford,
@@ -17,30 +22,44 @@ pub enum LmsConeSpace {
     Sharp,
     CmcCat2000,
     Cat02,
+    /// Scales CIE XYZ directly, i.e. uses the identity as the cone space.
+    XyzScaling,
 }
 
+/// The chromatic adaptation method, for
+/// [`ColorConversion::with_cat()`](crate::ColorConversion::with_cat). Each
+/// method adapts in a different [`LmsConeSpace`].
+pub type CatMethod = LmsConeSpace;
+
 impl LmsConeSpace {
     /// Returns the matrix for the given cone space variant.
+    pub fn matrix(&self) -> Mat3 {
+        Mat3::from_cols_array(&self.cols_array_f64().map(|value| value as Float))
+    }
+
+    /// Returns the columns of the matrix for the given cone space variant at
+    /// `f64` precision, regardless of [`Float`].
     // from S. Bianco. "Two New von Kries Based Chromatic Adapatation Transforms
     // Found by Numerical Optimization."
     #[rustfmt::skip]
-    pub fn matrix(&self) -> Mat3 {
+    pub(crate) fn cols_array_f64(&self) -> [f64; 9] {
         match self {
             LmsConeSpace::VonKries => {
-                Mat3::from_cols_array(&[0.40024, -0.2263, 0.0, 0.7076, 1.16532, 0.0, -0.08081, 0.0457, 0.91822])
+                [0.40024, -0.2263, 0.0, 0.7076, 1.16532, 0.0, -0.08081, 0.0457, 0.91822]
             }
             LmsConeSpace::Bradford => {
-                Mat3::from_cols_array(&[0.8951, -0.7502, 0.0389, 0.2664, 1.7135, -0.0685, -0.1614, 0.0367, 1.0296])
+                [0.8951, -0.7502, 0.0389, 0.2664, 1.7135, -0.0685, -0.1614, 0.0367, 1.0296]
             }
             LmsConeSpace::Sharp => {
-                Mat3::from_cols_array(&[1.2694, -0.8364, 0.0297, -0.0988, 1.8006, -0.0315, -0.1706, 0.0357, 1.0018])
+                [1.2694, -0.8364, 0.0297, -0.0988, 1.8006, -0.0315, -0.1706, 0.0357, 1.0018]
             }
             LmsConeSpace::CmcCat2000 => {
-                Mat3::from_cols_array(&[0.7982, -0.5918, 0.0008, 0.3389, 1.5512, 0.239, -0.1371, 0.0406, 0.9753])
+                [0.7982, -0.5918, 0.0008, 0.3389, 1.5512, 0.239, -0.1371, 0.0406, 0.9753]
             }
             LmsConeSpace::Cat02 => {
-                Mat3::from_cols_array(&[0.7328, -0.7036, 0.0030, 0.4296, 1.6975, 0.0136, -0.1624, 0.0061, 0.9834])
+                [0.7328, -0.7036, 0.0030, 0.4296, 1.6975, 0.0136, -0.1624, 0.0061, 0.9834]
             }
+            LmsConeSpace::XyzScaling => [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
         }
     }
 
@@ -63,3 +82,401 @@ impl LmsConeSpace {
         cone_space_transform.inverse() * src_to_dst_cone * cone_space_transform
     }
 }
+
+/// Adapts linear colors with `primaries` in place from the `from` white point
+/// to the `to` white point, using `method`.
+///
+/// The values must be in the linear RGB space with `primaries` and the `from`
+/// white point, and are in the space with `primaries` and the `to` white
+/// point afterwards. The adaptation matrix is built once for all values, so
+/// this is the preferred way to white balance whole images.
+pub fn adapt_slice(
+    values: &mut [Vec3],
+    primaries: RgbPrimaries,
+    from: WhitePoint,
+    to: WhitePoint,
+    method: LmsConeSpace,
+) {
+    if from == to {
+        return;
+    }
+    let (to_xyz, from_xyz) = if primaries == RgbPrimaries::CieXyz {
+        (Mat3::IDENTITY, Mat3::IDENTITY)
+    } else {
+        (
+            rgb_to_xyz(primaries.values(), from.values()),
+            xyz_to_rgb(primaries.values(), to.values()),
+        )
+    };
+    let adaptation = method.chromatic_adaptation_transform(
+        Vec3::from_slice(from.values()),
+        Vec3::from_slice(to.values()),
+    );
+    let mat = from_xyz * adaptation * to_xyz;
+    for value in values {
+        *value = mat * *value;
+    }
+}
+
+/// The CIE 2006 physiologically relevant observers, defined by the cone
+/// fundamentals of Stockman and Sharpe (2000) for a 2° and a 10° field size.
+///
+/// The cone fundamentals are the spectral sensitivities of the L, M and S
+/// cones, normalized to a peak of 1.0. CIE 170-2:2015 derives the cone
+/// fundamental based CIE XYZ color matching functions from them with a linear
+/// transform, [`lms_to_xyz()`](Self::lms_to_xyz), chosen so that illuminant E
+/// has the chromaticity x = y = 1/3. They are an alternative to the CIE 1931
+/// and CIE 1964 standard observers, and are better suited for small-field and
+/// individual observer colorimetry.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Cie2006Observer {
+    /// The 2° field size observer.
+    TwoDegree,
+    /// The 10° field size observer.
+    TenDegree,
+}
+
+impl Cie2006Observer {
+    /// The wavelength in nanometers of the first entry of
+    /// [`cone_fundamentals()`](Self::cone_fundamentals).
+    pub const FIRST_WAVELENGTH: Float = 390.0;
+    /// The wavelength step in nanometers between the entries of
+    /// [`cone_fundamentals()`](Self::cone_fundamentals).
+    pub const WAVELENGTH_STEP: Float = 5.0;
+
+    /// Returns the L, M and S cone fundamentals, tabulated in 5nm steps from
+    /// 390nm to 780nm.
+    ///
+    /// The S cone fundamental is only defined up to 615nm and is zero beyond.
+    pub fn cone_fundamentals(&self) -> &'static [[Float; 3]] {
+        match self {
+            Cie2006Observer::TwoDegree => &CONE_FUNDAMENTALS_2,
+            Cie2006Observer::TenDegree => &CONE_FUNDAMENTALS_10,
+        }
+    }
+
+    /// Returns the cone fundamentals at `wavelength` in nanometers, linearly
+    /// interpolated between the tabulated values and zero outside of them.
+    pub fn lms(&self, wavelength: Float) -> Vec3 {
+        let table = self.cone_fundamentals();
+        let position = (wavelength - Self::FIRST_WAVELENGTH) / Self::WAVELENGTH_STEP;
+        if !(0.0..=(table.len() - 1) as Float).contains(&position) {
+            return Vec3::new(0.0, 0.0, 0.0);
+        }
+        let index = (position as usize).min(table.len() - 2);
+        let t = position - index as Float;
+        let [a, b] = [table[index], table[index + 1]];
+        Vec3::new(
+            a[0] + (b[0] - a[0]) * t,
+            a[1] + (b[1] - a[1]) * t,
+            a[2] + (b[2] - a[2]) * t,
+        )
+    }
+
+    /// Returns the cone fundamental based CIE XYZ color matching functions at
+    /// `wavelength` in nanometers.
+    pub fn xyz(&self, wavelength: Float) -> Vec3 {
+        self.lms_to_xyz() * self.lms(wavelength)
+    }
+
+    /// Returns the matrix converting cone responses to CIE XYZ, as defined by
+    /// CIE 170-2:2015.
+    #[rustfmt::skip]
+    pub fn lms_to_xyz(&self) -> Mat3 {
+        match self {
+            Cie2006Observer::TwoDegree => {
+                Mat3::from_cols_array(&[1.947_354_7, 0.689_902_7, 0.0, -1.414_451_2, 0.348_321_9, 0.0, 0.364_763_3, 0.0, 1.934_853_4])
+            }
+            Cie2006Observer::TenDegree => {
+                Mat3::from_cols_array(&[1.939_864_4, 0.692_839_3, 0.0, -1.346_643_6, 0.349_675_7, 0.0, 0.430_449_4, 0.0, 2.146_879_5])
+            }
+        }
+    }
+
+    /// Returns the matrix converting CIE XYZ to cone responses, the inverse of
+    /// [`lms_to_xyz()`](Self::lms_to_xyz).
+    pub fn xyz_to_lms(&self) -> Mat3 {
+        self.lms_to_xyz().inverse()
+    }
+}
+
+#[rustfmt::skip]
+const CONE_FUNDAMENTALS_2: [[Float; 3]; 79] = [
+    [0.000415004, 0.000368349, 0.00954729], // 390
+    [0.00105192, 0.000958659, 0.023825], // 395
+    [0.00240836, 0.00226991, 0.0566498], // 400
+    [0.00483339, 0.0047001, 0.122451], // 405
+    [0.00872127, 0.00879369, 0.233008], // 410
+    [0.0133837, 0.0145277, 0.381363], // 415
+    [0.018448, 0.0216648, 0.543618], // 420
+    [0.0229318, 0.0295714, 0.674474], // 425
+    [0.0281877, 0.0394566, 0.802555], // 430
+    [0.0341054, 0.0518198, 0.903572], // 435
+    [0.0402563, 0.0647782, 0.99102], // 440
+    [0.044938, 0.0758812, 0.991515], // 445
+    [0.049864, 0.0870524, 0.955393], // 450
+    [0.0553419, 0.0981934, 0.86024], // 455
+    [0.0647164, 0.116272, 0.786704], // 460
+    [0.0806894, 0.144541, 0.738268], // 465
+    [0.0994755, 0.175893, 0.646359], // 470
+    [0.118802, 0.205398, 0.516411], // 475
+    [0.140145, 0.235754, 0.390333], // 480
+    [0.163952, 0.268063, 0.290322], // 485
+    [0.191556, 0.30363, 0.211867], // 490
+    [0.232926, 0.357061, 0.160526], // 495
+    [0.288959, 0.427764, 0.122839], // 500
+    [0.359716, 0.515587, 0.0888966], // 505
+    [0.443683, 0.61552, 0.0608209], // 510
+    [0.536494, 0.719154, 0.0428123], // 515
+    [0.628561, 0.81661, 0.0292033], // 520
+    [0.70472, 0.88555, 0.0193912], // 525
+    [0.77063, 0.935687, 0.0126013], // 530
+    [0.825711, 0.968858, 0.00809454], // 535
+    [0.881011, 0.995217, 0.005089], // 540
+    [0.919067, 0.997193, 0.00316893], // 545
+    [0.940198, 0.977193, 0.00195896], // 550
+    [0.965733, 0.956583, 0.00120277], // 555
+    [0.981445, 0.91775, 0.000740174], // 560
+    [0.994486, 0.873205, 0.000455979], // 565
+    [0.999993, 0.813509, 0.0002818], // 570
+    [0.99231, 0.740291, 0.000175039], // 575
+    [0.969429, 0.653274, 0.000109454], // 580
+    [0.955602, 0.572597, 6.89991e-5], // 585
+    [0.927673, 0.492599, 4.39024e-5], // 590
+    [0.885969, 0.411246, 2.82228e-5], // 595
+    [0.833982, 0.334428, 1.83459e-5], // 600
+    [0.775103, 0.264872, 1.20667e-5], // 605
+    [0.705713, 0.205273, 8.03488e-6], // 610
+    [0.630773, 0.156243, 5.41843e-6], // 615
+    [0.554224, 0.116642, 0.0], // 620
+    [0.479941, 0.0855872, 0.0], // 625
+    [0.400711, 0.0621121, 0.0], // 630
+    [0.327864, 0.0444879, 0.0], // 635
+    [0.265784, 0.0314283, 0.0], // 640
+    [0.213284, 0.0218037, 0.0], // 645
+    [0.165141, 0.015448, 0.0], // 650
+    [0.124749, 0.0107119, 0.0], // 655
+    [0.0930085, 0.00730256, 0.0], // 660
+    [0.06851, 0.0049718, 0.0], // 665
+    [0.0498661, 0.00343667, 0.0], // 670
+    [0.0358233, 0.00237617, 0.0], // 675
+    [0.025379, 0.00163734, 0.0], // 680
+    [0.0177201, 0.00112128, 0.0], // 685
+    [0.0121701, 0.000761049, 0.0], // 690
+    [0.0084717, 0.000525458, 0.0], // 695
+    [0.00589749, 0.000365316, 0.0], // 700
+    [0.00409129, 0.000253417, 0.0], // 705
+    [0.00280447, 0.000174402, 0.0], // 710
+    [0.00192058, 0.000120608, 0.0], // 715
+    [0.00132687, 8.41716e-5, 0.0], // 720
+    [0.000917777, 5.8935e-5, 0.0], // 725
+    [0.000639373, 4.16048e-5, 0.0], // 730
+    [0.000446035, 2.94354e-5, 0.0], // 735
+    [0.000310869, 2.0886e-5, 0.0], // 740
+    [0.000219329, 1.50458e-5, 0.0], // 745
+    [0.000154549, 1.08201e-5, 0.0], // 750
+    [0.000109508, 7.82272e-6, 0.0], // 755
+    [7.79912e-5, 5.69093e-6, 0.0], // 760
+    [5.56264e-5, 4.13998e-6, 0.0], // 765
+    [3.99295e-5, 3.02683e-6, 0.0], // 770
+    [2.86163e-5, 2.211e-6, 0.0], // 775
+    [2.07321e-5, 1.63433e-6, 0.0], // 780
+];
+
+#[rustfmt::skip]
+const CONE_FUNDAMENTALS_10: [[Float; 3]; 79] = [
+    [0.000407619, 0.000358227, 0.00614265], // 390
+    [0.00106921, 0.000964828, 0.0159515], // 395
+    [0.00254073, 0.00237208, 0.0396308], // 400
+    [0.00531546, 0.00512316, 0.0897612], // 405
+    [0.00998836, 0.00998841, 0.17853], // 410
+    [0.016013, 0.0172596, 0.305941], // 415
+    [0.0233957, 0.0273163, 0.462692], // 420
+    [0.0309104, 0.0396929, 0.60957], // 425
+    [0.039781, 0.0555384, 0.756885], // 430
+    [0.0494172, 0.0750298, 0.869984], // 435
+    [0.0594619, 0.0957612, 0.96696], // 440
+    [0.0686537, 0.11622, 0.993337], // 445
+    [0.0795647, 0.139493, 0.991329], // 450
+    [0.0907704, 0.162006, 0.906735], // 455
+    [0.106664, 0.193202, 0.823726], // 460
+    [0.128336, 0.232275, 0.737043], // 465
+    [0.151651, 0.271441, 0.610456], // 470
+    [0.177116, 0.310372, 0.470894], // 475
+    [0.20794, 0.355066, 0.350108], // 480
+    [0.244046, 0.405688, 0.258497], // 485
+    [0.282752, 0.456137, 0.185298], // 490
+    [0.334786, 0.52297, 0.135351], // 495
+    [0.391705, 0.591003, 0.096799], // 500
+    [0.456252, 0.666404, 0.0649614], // 505
+    [0.526538, 0.743612, 0.0412337], // 510
+    [0.599867, 0.816808, 0.02713], // 515
+    [0.675313, 0.889214, 0.0176298], // 520
+    [0.737108, 0.934977, 0.0113252], // 525
+    [0.7889, 0.961962, 0.0071709], // 530
+    [0.837403, 0.981481, 0.00454287], // 535
+    [0.890871, 0.998931, 0.00283352], // 540
+    [0.92666, 0.991383, 0.00175573], // 545
+    [0.944527, 0.961875, 0.0010823], // 550
+    [0.970703, 0.935829, 0.000664512], // 555
+    [0.985636, 0.890949, 0.000408931], // 560
+    [0.996979, 0.840969, 0.000251918], // 565
+    [0.999543, 0.776526, 0.000155688], // 570
+    [0.987057, 0.700013, 9.67045e-5], // 575
+    [0.957841, 0.611728, 6.04706e-5], // 580
+    [0.939781, 0.531825, 3.81202e-5], // 585
+    [0.906693, 0.454142, 2.42549e-5], // 590
+    [0.859606, 0.376527, 1.55924e-5], // 595
+    [0.803173, 0.304378, 1.01356e-5], // 600
+    [0.74068, 0.239837, 6.66656e-6], // 605
+    [0.668991, 0.185104, 4.43906e-6], // 610
+    [0.593248, 0.140431, 2.99354e-6], // 615
+    [0.517449, 0.104573, 0.0], // 620
+    [0.445125, 0.0765841, 0.0], // 625
+    [0.369168, 0.055499, 0.0], // 630
+    [0.300316, 0.0397097, 0.0], // 635
+    [0.242316, 0.0280314, 0.0], // 640
+    [0.19373, 0.0194366, 0.0], // 645
+    [0.149509, 0.013766, 0.0], // 650
+    [0.112638, 0.00954317, 0.0], // 655
+    [0.0838077, 0.00650454, 0.0], // 660
+    [0.0616384, 0.00442795, 0.0], // 665
+    [0.0448132, 0.0030605, 0.0], // 670
+    [0.032166, 0.00211596, 0.0], // 675
+    [0.0227738, 0.00145798, 0.0], // 680
+    [0.0158939, 0.000998431, 0.0], // 685
+    [0.0109123, 0.000677654, 0.0], // 690
+    [0.00759453, 0.000467871, 0.0], // 695
+    [0.00528607, 0.000325278, 0.0], // 700
+    [0.00366675, 0.000225641, 0.0], // 705
+    [0.00251327, 0.000155285, 0.0], // 710
+    [0.00172108, 0.000107388, 0.0], // 715
+    [0.001189, 7.49453e-5, 0.0], // 720
+    [0.000822396, 5.24748e-5, 0.0], // 725
+    [0.000572917, 3.70445e-5, 0.0], // 730
+    [0.00039967, 2.62088e-5, 0.0], // 735
+    [0.000278553, 1.85965e-5, 0.0], // 740
+    [0.000196528, 1.33965e-5, 0.0], // 745
+    [0.000138482, 9.63395e-6, 0.0], // 750
+    [9.81226e-5, 6.96523e-6, 0.0], // 755
+    [6.98827e-5, 5.06711e-6, 0.0], // 760
+    [4.9843e-5, 3.68617e-6, 0.0], // 765
+    [3.57781e-5, 2.69504e-6, 0.0], // 770
+    [2.56411e-5, 1.96864e-6, 0.0], // 775
+    [1.85766e-5, 1.45518e-6, 0.0], // 780
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn adaptation_matrices_match_reference() {
+        // D65 → D50 matrices from Bruce Lindbloom's "Chromatic Adaptation", as
+        // rows.
+        let d65 = Vec3::new(0.95047, 1.0, 1.08883);
+        let d50 = Vec3::new(0.96422, 1.0, 0.82521);
+        #[rustfmt::skip]
+        let references = [
+            (CatMethod::Bradford, [
+                1.0478112, 0.0228866, -0.0501270,
+                0.0295424, 0.9904844, -0.0170491,
+                -0.0092345, 0.0150436, 0.7521316,
+            ]),
+            (CatMethod::VonKries, [
+                1.0160803, 0.0552297, -0.0521326,
+                0.0060666, 0.9955661, -0.0012235,
+                0.0, 0.0, 0.7578869,
+            ]),
+            (CatMethod::XyzScaling, [
+                1.0144665, 0.0, 0.0,
+                0.0, 1.0, 0.0,
+                0.0, 0.0, 0.7578869,
+            ]),
+        ];
+        for (method, rows) in references {
+            let expected = Mat3::from_cols_array(&rows).transpose();
+            let mat = method.chromatic_adaptation_transform(d65, d50);
+            assert!(
+                mat.abs_diff_eq(expected, 1e-5),
+                "{method:?}: {mat:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn adapt_slice_matches_per_color_adaptation() {
+        use crate::details::conversion::LinearColorConversion;
+        use crate::spaces;
+
+        let mut values: [Vec3; 1000] = core::array::from_fn(|i| {
+            let i = i as Float;
+            Vec3::new((i * 0.37).fract(), (i * 0.71).fract(), (i * 0.13).fract())
+        });
+        let original = values;
+        adapt_slice(
+            &mut values,
+            RgbPrimaries::Bt709,
+            WhitePoint::D65,
+            WhitePoint::D50,
+            LmsConeSpace::Sharp,
+        );
+        let conversion = LinearColorConversion::new(
+            spaces::LINEAR_SRGB,
+            spaces::LINEAR_SRGB.with_whitepoint(WhitePoint::D50),
+        );
+        for (adapted, original) in values.iter().zip(original) {
+            let expected = conversion.convert(original);
+            assert!(
+                adapted.abs_diff_eq(expected, 1e-5),
+                "{adapted:?} != {expected:?}"
+            );
+        }
+
+        let mut unchanged = original;
+        adapt_slice(
+            &mut unchanged,
+            RgbPrimaries::Bt709,
+            WhitePoint::D65,
+            WhitePoint::D65,
+            LmsConeSpace::Bradford,
+        );
+        assert_eq!(unchanged, original);
+    }
+
+    #[test]
+    fn cie_2006_fundamentals_integrate_to_equal_energy_white() {
+        for observer in [Cie2006Observer::TwoDegree, Cie2006Observer::TenDegree] {
+            let lms = observer
+                .cone_fundamentals()
+                .iter()
+                .fold(Vec3::new(0.0, 0.0, 0.0), |sum, &[l, m, s]| {
+                    sum + Vec3::new(l, m, s)
+                });
+            let xyz = observer.lms_to_xyz() * lms;
+            let sum = xyz.x + xyz.y + xyz.z;
+            let (x, y) = (xyz.x / sum, xyz.y / sum);
+            assert!(
+                (x - 1.0 / 3.0).abs() < 2e-4 && (y - 1.0 / 3.0).abs() < 2e-4,
+                "{observer:?}: {x}, {y}"
+            );
+            assert!((observer.xyz_to_lms() * xyz).abs_diff_eq(lms, 1e-3));
+        }
+    }
+
+    #[test]
+    fn cie_2006_fundamentals_peak_at_one() {
+        let observer = Cie2006Observer::TwoDegree;
+        let peaks = Vec3::new(
+            observer.lms(570.0).x,
+            observer.lms(545.0).y,
+            observer.lms(440.0).z,
+        );
+        assert!(
+            peaks.abs_diff_eq(Vec3::new(1.0, 1.0, 1.0), 1e-2),
+            "{peaks:?}"
+        );
+        assert_eq!(observer.lms(385.0), Vec3::new(0.0, 0.0, 0.0));
+        assert_eq!(observer.lms(830.0), Vec3::new(0.0, 0.0, 0.0));
+    }
+}
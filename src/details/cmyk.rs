@@ -0,0 +1,154 @@
+//! Naive device CMYK, for print workflows.
+//!
+//! Device CMYK is the complement of encoded sRGB, i.e. `C = 1 - R'` etc.,
+//! with the black ink `K` replacing part of the gray component shared by
+//! cyan, magenta and yellow. This ignores ink and paper characteristics, so it
+//! is no substitute for an ICC profile of an actual printing process.
+use super::color::{Color, ColorSpace, color_spaces};
+use crate::Float;
+
+/// Maximum difference per component of encoded sRGB colors in [0, 1] after
+/// converting them to [`CmykColor`] and back.
+pub const CMYK_ROUND_TRIP_EPSILON: Float = 1e-5;
+
+/// How much of the gray component of cyan, magenta and yellow is replaced by
+/// black ink in [`Color::to_cmyk()`].
+///
+/// The gray component is `min(C, M, Y)` before black generation.
+#[derive(Debug, Clone, Copy)]
+pub enum BlackGen {
+    /// Uses no black ink.
+    None,
+    /// Under color removal: replaces the gray component by black only in
+    /// shadows, where it is above `start`, ramping up to full replacement for
+    /// black.
+    Ucr { start: Float },
+    /// Gray component replacement: replaces the given fraction of the gray
+    /// component by black, `1.0` replacing all of it.
+    Gcr(Float),
+    /// Returns the black ink for a gray component. Results are clamped to
+    /// [0, gray component].
+    Custom(fn(Float) -> Float),
+}
+
+impl BlackGen {
+    /// Returns the black ink for the gray component `gray`, in [0, `gray`].
+    fn black(&self, gray: Float) -> Float {
+        let black = match *self {
+            BlackGen::None => 0.0,
+            BlackGen::Ucr { start } if start >= 1.0 => 0.0,
+            BlackGen::Ucr { start } => ((gray - start) / (1.0 - start)).max(0.0) * gray,
+            BlackGen::Gcr(fraction) => fraction * gray,
+            BlackGen::Custom(black) => black(gray),
+        };
+        black.clamp(0.0, gray)
+    }
+}
+
+/// A color in naive device CMYK, see the [module documentation](self).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CmykColor {
+    pub c: Float,
+    pub m: Float,
+    pub y: Float,
+    pub k: Float,
+}
+
+impl CmykColor {
+    pub const fn new(c: Float, m: Float, y: Float, k: Float) -> Self {
+        Self { c, m, y, k }
+    }
+
+    /// Converts the color to encoded sRGB and from there to `space`.
+    pub fn to_color(&self, space: ColorSpace) -> Color {
+        let white = 1.0 - self.k;
+        Color::srgb(
+            (1.0 - self.c) * white,
+            (1.0 - self.m) * white,
+            (1.0 - self.y) * white,
+        )
+        .to(space)
+    }
+}
+
+impl Color {
+    /// Converts the color to naive device CMYK, generating black ink with
+    /// `black_generation`.
+    ///
+    /// Components of the encoded sRGB color outside of [0, 1] are clamped.
+    /// Converting the result back with [`CmykColor::to_color()`] reproduces
+    /// colors in the sRGB gamut within [`CMYK_ROUND_TRIP_EPSILON`], for any
+    /// black generation.
+    pub fn to_cmyk(&self, black_generation: BlackGen) -> CmykColor {
+        let rgb = self.to(color_spaces::ENCODED_SRGB).value;
+        let [c, m, y] = [rgb.x, rgb.y, rgb.z].map(|value| 1.0 - value.clamp(0.0, 1.0));
+        let k = black_generation.black(c.min(m).min(y));
+        if k >= 1.0 {
+            return CmykColor::new(0.0, 0.0, 0.0, 1.0);
+        }
+        let remove_black = |ink: Float| (ink - k) / (1.0 - k);
+        CmykColor::new(remove_black(c), remove_black(m), remove_black(y), k)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn black_generation() {
+        let gray = Color::srgb(0.25, 0.25, 0.25);
+        assert_eq!(
+            gray.to_cmyk(BlackGen::None),
+            CmykColor::new(0.75, 0.75, 0.75, 0.0)
+        );
+        assert_eq!(
+            gray.to_cmyk(BlackGen::Gcr(1.0)),
+            CmykColor::new(0.0, 0.0, 0.0, 0.75)
+        );
+        let ucr = gray.to_cmyk(BlackGen::Ucr { start: 0.5 });
+        assert!((ucr.k - 0.375).abs() < 1e-6, "{ucr:?}");
+        assert_eq!(
+            Color::srgb(0.9, 0.9, 0.9)
+                .to_cmyk(BlackGen::Ucr { start: 0.5 })
+                .k,
+            0.0
+        );
+        let custom = gray.to_cmyk(BlackGen::Custom(|gray| 2.0 * gray));
+        assert_eq!(custom.k, 0.75);
+        assert_eq!(
+            Color::srgb(0.0, 0.0, 0.0).to_cmyk(BlackGen::Gcr(1.0)),
+            CmykColor::new(0.0, 0.0, 0.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn cmyk_round_trip() {
+        for black_generation in [
+            BlackGen::None,
+            BlackGen::Ucr { start: 0.3 },
+            BlackGen::Gcr(0.5),
+            BlackGen::Gcr(1.0),
+        ] {
+            for rgb in [
+                [0.0, 0.0, 0.0],
+                [1.0, 1.0, 1.0],
+                [0.2, 0.5, 0.9],
+                [0.05, 0.1, 0.02],
+                [0.7, 0.3, 0.3],
+            ] {
+                let color = Color::srgb(rgb[0], rgb[1], rgb[2]);
+                let back = color
+                    .to_cmyk(black_generation)
+                    .to_color(color_spaces::ENCODED_SRGB);
+                assert!(
+                    back.value.abs_diff_eq(color.value, CMYK_ROUND_TRIP_EPSILON),
+                    "{black_generation:?}: {:?} != {:?}",
+                    back.value,
+                    color.value
+                );
+            }
+        }
+    }
+}
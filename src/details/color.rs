@@ -1,5 +1,12 @@
-use super::{conversion::ColorConversion, transform::ColorTransform};
-use crate::{Float, Vec3};
+use super::{
+    appearance::Cam16ViewingConditions,
+    cat::CatMethod,
+    conversion::{ColorConversion, luminance_row},
+    transform::{ColorTransform, GammaExponent, LumaCoefficients, RegisteredTransform},
+};
+use crate::{Float, PI, TAU, Vec3};
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+use num_traits::Float as _;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -8,12 +15,71 @@ use serde::{Deserialize, Serialize};
 pub enum ColorError {
     /// Failed to canonicalize custom values to a known standard.
     CanonicalizationFailed,
+    /// The transform function clamps values, so the conversion can't be
+    /// unbounded.
+    ClampingTransform(TransformFn),
+    /// The colors of an operation are not in the same color space.
+    MismatchedColorSpaces,
+    /// A value is outside of the domain of the transform function.
+    OutOfDomain(TransformFn),
+    /// The string is not a supported CSS color.
+    InvalidCss,
+    /// The string is not the name of a known color space.
+    UnknownColorSpaceName,
+    /// The source and destination buffers have different lengths, given as
+    /// source and destination length.
+    MismatchedBufferLengths(usize, usize),
+    /// The length of a buffer of interleaved components is not a multiple of
+    /// 3.
+    IncompleteBuffer(usize),
+    /// The colors of an operation have different white points, which
+    /// [`WhitePointPolicy::Strict`] rejects.
+    MismatchedWhitePoints(WhitePoint, WhitePoint),
+    /// The weights of a blend sum to zero, or there are no colors to blend.
+    ZeroTotalWeight,
+    /// The primaries are not finite, have a chromaticity with y ≤ 0, or don't
+    /// span a triangle.
+    DegeneratePrimaries,
+    /// The white point XYZ values are not finite and positive.
+    NonPositiveWhitePoint,
+    /// The white point chromaticity is outside of the triangle of the
+    /// primaries.
+    WhitePointOutsideGamut,
 }
 
 impl core::fmt::Display for ColorError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::CanonicalizationFailed => write!(f, "No matching standard values found"),
+            Self::ClampingTransform(transform_fn) => {
+                write!(f, "{transform_fn:?} clamps values outside its range")
+            }
+            Self::MismatchedColorSpaces => write!(f, "Colors are in different color spaces"),
+            Self::OutOfDomain(transform_fn) => {
+                write!(f, "Value is outside of the domain of {transform_fn:?}")
+            }
+            Self::InvalidCss => write!(f, "Invalid or unsupported CSS color"),
+            Self::UnknownColorSpaceName => write!(f, "Unknown color space name"),
+            Self::MismatchedBufferLengths(src, dst) => {
+                write!(
+                    f,
+                    "Source length {src} differs from destination length {dst}"
+                )
+            }
+            Self::IncompleteBuffer(len) => {
+                write!(f, "Buffer length {len} is not a multiple of 3")
+            }
+            Self::MismatchedWhitePoints(a, b) => {
+                write!(f, "Colors have different white points {a:?} and {b:?}")
+            }
+            Self::ZeroTotalWeight => write!(f, "Weights of the blend sum to zero"),
+            Self::DegeneratePrimaries => write!(f, "Primaries don't span a triangle"),
+            Self::NonPositiveWhitePoint => {
+                write!(f, "White point XYZ values are not finite and positive")
+            }
+            Self::WhitePointOutsideGamut => {
+                write!(f, "White point is outside of the gamut of the primaries")
+            }
         }
     }
 }
@@ -21,6 +87,66 @@ impl core::fmt::Display for ColorError {
 #[cfg(feature = "std")]
 impl std::error::Error for ColorError {}
 
+/// How operations on several colors, like [`Color::mix()`], treat colors
+/// whose spaces have different white points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum WhitePointPolicy {
+    /// Adapts the colors to a common white point, like [`Color::to()`].
+    #[default]
+    Adapt,
+    /// Fails with [`ColorError::MismatchedWhitePoints`], to catch colors
+    /// which unintentionally use different white points.
+    Strict,
+}
+
+impl WhitePointPolicy {
+    /// Returns an error if the policy is [`Strict`](Self::Strict) and the
+    /// spaces of `a` and `b` have different white points.
+    pub fn check(self, a: &Color, b: &Color) -> Result<(), ColorError> {
+        let (a, b) = (a.space.white_point(), b.space.white_point());
+        if self == Self::Strict && !a.approx_eq(&b) {
+            Err(ColorError::MismatchedWhitePoints(a, b))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The maximum difference per value for which
+/// [`RgbPrimaries::approx_eq()`], [`WhitePoint::approx_eq()`] and
+/// [`ColorSpace::approx_eq()`] consider `Custom` values equal.
+///
+/// This absorbs rounding differences between computed and literal values,
+/// while being far below the tolerance used to detect standard values in
+/// [`RgbPrimaries::from_rgb_xy()`] and [`WhitePoint::from_xy()`]. `==` compares
+/// `Custom` values bitwise instead, as approximate equality isn't transitive.
+pub const CUSTOM_EPSILON: Float = 1e-6;
+
+fn values_approx_eq(a: &[Float], b: &[Float]) -> bool {
+    a.iter()
+        .zip(b)
+        .all(|(a, b)| (a - b).abs() <= CUSTOM_EPSILON)
+}
+
+fn bits_eq(a: &[Float], b: &[Float]) -> bool {
+    a.iter().zip(b).all(|(a, b)| a.to_bits() == b.to_bits())
+}
+
+/// Helper function to lowercase `name` into `buffer`, dropping separators.
+/// Returns `None` if the name does not fit.
+fn normalize_name<'a>(name: &str, buffer: &'a mut [u8]) -> Option<&'a [u8]> {
+    let mut len = 0;
+    for byte in name.bytes() {
+        if matches!(byte, b' ' | b'-' | b'_' | b'.') {
+            continue;
+        }
+        *buffer.get_mut(len)? = byte.to_ascii_lowercase();
+        len += 1;
+    }
+    Some(&buffer[..len])
+}
+
 /// Helper function to check if two sets of primaries match within epsilon tolerance.
 fn primaries_match(a: &[[Float; 2]; 3], b: &[[Float; 2]; 3], epsilon: Float) -> bool {
     a.iter()
@@ -28,6 +154,22 @@ fn primaries_match(a: &[[Float; 2]; 3], b: &[[Float; 2]; 3], epsilon: Float) ->
         .all(|(ap, bp)| (ap[0] - bp[0]).abs() < epsilon && (ap[1] - bp[1]).abs() < epsilon)
 }
 
+/// Returns the largest difference of a chromaticity coordinate of two sets of
+/// primaries.
+fn primaries_distance(a: &[[Float; 2]; 3], b: &[[Float; 2]; 3]) -> Float {
+    a.as_flattened()
+        .iter()
+        .zip(b.as_flattened())
+        .fold(0.0, |max: Float, (a, b)| max.max((a - b).abs()))
+}
+
+/// Returns the largest difference of a component of two XYZ values.
+fn xyz_distance(a: &[Float; 3], b: &[Float; 3]) -> Float {
+    a.iter()
+        .zip(b)
+        .fold(0.0, |max: Float, (a, b)| max.max((a - b).abs()))
+}
+
 /// Helper function to check if two XYZ values match within epsilon tolerance.
 fn xyz_match(a: &[Float; 3], b: &[Float; 3], epsilon: Float) -> bool {
     a.iter()
@@ -40,7 +182,11 @@ fn xyz_match(a: &[Float; 3], b: &[Float; 3], epsilon: Float) -> bool {
 #[derive(Debug, Copy, Clone, PartialEq, Hash, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TransformFn {
-    /// The sRGB transfer functions (aka 'gamma correction').
+    /// The sRGB transfer functions (aka 'gamma correction'), which are
+    /// piecewise with a linear segment near black. For the pure 2.2 gamma
+    /// some pipelines approximate sRGB with, use
+    /// [`TransformFn::gamma(2.2)`](Self::gamma), as in
+    /// [`SRGB_GAMMA_2_2`](color_spaces::SRGB_GAMMA_2_2).
     Srgb,
     /// Oklab conversion from xyz.
     OkLab,
@@ -73,25 +219,278 @@ pub enum TransformFn {
     IctCpPq,
     /// BT.2100 ICtCp with HLG transfer function.
     IctCpHlg,
-    /// The BT.601/BT.709/BT.2020 (they are equivalent) OETF and inverse.
+    /// The BT.601/BT.709 OETF and inverse, which are equivalent.
     Bt601,
     /// SMPTE ST 2084:2014 aka "Perceptual Quantizer" transfer functions used in
     /// BT.2100 for digitally created/distributed HDR content.
     Pq,
-    // ACEScc is a logarithmic transform
-    // ACES_CC,
-    // ACEScct is a logarithmic transform with toe
-    // ACES_CCT,
+    /// ACEScc, a logarithmic transform.
+    AcesCc,
+    /// ACEScct, a logarithmic transform with a linear toe.
+    AcesCct,
+    /// The BT.2020 OETF and inverse with the constants α = 1.099 and β = 0.018
+    /// for 10-bit systems.
+    Bt2020_10Bit,
+    /// The BT.2020 OETF and inverse with the more precise constants
+    /// α = 1.0993 and β = 0.0181 for 12-bit systems.
+    Bt2020_12Bit,
+    /// Sony S-Log3, a logarithmic camera encoding with a linear toe.
+    SLog3,
+    /// (Hue, Whiteness, Blackness), where W is the smallest component and B
+    /// is 1 minus the largest component.
+    Hwb,
+    /// CAM16-UCS J', a', b' conversion from xyz under the given viewing
+    /// conditions, see [`ColorSpace::with_cam16_conditions()`].
+    Cam16Ucs(Cam16ViewingConditions),
+    /// Full-range Y'CbCr with the given luma weights, applying the [BT.709
+    /// OETF](TransformFn::Bt601) before the matrix. Cb and Cr are centered at
+    /// 0.
+    YCbCr(LumaCoefficients),
+    /// The BT.2100 Hybrid Log-Gamma OETF and inverse, mapping scene light
+    /// normalized to [0, 1] to the HLG signal. Apply
+    /// [`hlg_ootf()`](super::transform::hlg_ootf) to the scene light for
+    /// display light.
+    Hlg,
+    /// The DCI-P3 EOTF and inverse of SMPTE RP 431-2, a pure 2.6 gamma, i.e.
+    /// equivalent to [`Gamma`](Self::Gamma) with an exponent of 2.6.
+    DciP3,
+    /// A pure power transfer function, e.g. for display gammas: encodes
+    /// linear values with a power of 1 / exponent and decodes them with a
+    /// power of the exponent, so `Gamma(GammaExponent(2.2))` decodes with
+    /// `x^2.2`. Negative values are mirrored, i.e. the power is applied to
+    /// their magnitude. See [`TransformFn::gamma()`].
+    Gamma(GammaExponent),
+    /// A transform function registered at runtime with
+    /// [`register_transform()`](super::registry::register_transform), which
+    /// serializes as its registered name.
+    Registered(RegisteredTransform),
+}
+
+/// The variant of a [`TransformFn`] without its parameters, e.g. to index
+/// tables by transform function.
+///
+/// The discriminants of the variants `TransformFn` had before it gained
+/// parameterized variants are unchanged, so `transform_fn.kind() as u8`
+/// replaces casting the `TransformFn` itself.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TransformFnKind {
+    Srgb,
+    OkLab,
+    OkLch,
+    CieXyY,
+    CieLab,
+    CieLch,
+    Cie1960Ucs,
+    Cie1960UcsUvV,
+    Cie1964Uvw,
+    Cie1976Luv,
+    Hsl,
+    Hsv,
+    Hsi,
+    IctCpPq,
+    IctCpHlg,
+    Bt601,
+    Pq,
+    AcesCc,
+    AcesCct,
+    Bt2020_10Bit,
+    Bt2020_12Bit,
+    SLog3,
+    Hwb,
+    Cam16Ucs,
+    YCbCr,
+    Hlg,
+    DciP3,
+    Gamma,
+    Registered,
+}
+
+impl TransformFnKind {
+    pub const ENUM_COUNT: TransformFnKind = TransformFnKind::Registered;
 }
 
 impl TransformFn {
-    pub const ENUM_COUNT: TransformFn = TransformFn::Pq;
+    /// The kind of the last variant. As the last variants carry data, this is
+    /// a [`TransformFnKind`] rather than a `TransformFn`.
+    pub const ENUM_COUNT: TransformFnKind = TransformFnKind::ENUM_COUNT;
+
+    /// Whether the transform function clamps values, i.e. values outside of
+    /// its range do not survive a round trip through it.
+    ///
+    /// The PQ based transforms clamp negative light, as they are defined on
+    /// absolute luminance. ACEScc clamps negative values and both ACEScc and
+    /// ACEScct clamp values beyond the half float range when decoding.
+    pub fn clamps(&self) -> bool {
+        matches!(
+            self,
+            Self::Pq | Self::IctCpPq | Self::AcesCc | Self::AcesCct
+        )
+    }
+
+    /// A [pure power transfer function](Self::Gamma) decoding with a power of
+    /// `exponent`, e.g. `TransformFn::gamma(2.2)`.
+    pub const fn gamma(exponent: Float) -> TransformFn {
+        Self::Gamma(GammaExponent(exponent))
+    }
+
+    /// The variant of the transform function without its parameters.
+    pub const fn kind(&self) -> TransformFnKind {
+        match self {
+            Self::Srgb => TransformFnKind::Srgb,
+            Self::OkLab => TransformFnKind::OkLab,
+            Self::OkLch => TransformFnKind::OkLch,
+            Self::CieXyY => TransformFnKind::CieXyY,
+            Self::CieLab => TransformFnKind::CieLab,
+            Self::CieLch => TransformFnKind::CieLch,
+            Self::Cie1960Ucs => TransformFnKind::Cie1960Ucs,
+            Self::Cie1960UcsUvV => TransformFnKind::Cie1960UcsUvV,
+            Self::Cie1964Uvw => TransformFnKind::Cie1964Uvw,
+            Self::Cie1976Luv => TransformFnKind::Cie1976Luv,
+            Self::Hsl => TransformFnKind::Hsl,
+            Self::Hsv => TransformFnKind::Hsv,
+            Self::Hsi => TransformFnKind::Hsi,
+            Self::IctCpPq => TransformFnKind::IctCpPq,
+            Self::IctCpHlg => TransformFnKind::IctCpHlg,
+            Self::Bt601 => TransformFnKind::Bt601,
+            Self::Pq => TransformFnKind::Pq,
+            Self::AcesCc => TransformFnKind::AcesCc,
+            Self::AcesCct => TransformFnKind::AcesCct,
+            Self::Bt2020_10Bit => TransformFnKind::Bt2020_10Bit,
+            Self::Bt2020_12Bit => TransformFnKind::Bt2020_12Bit,
+            Self::SLog3 => TransformFnKind::SLog3,
+            Self::Hwb => TransformFnKind::Hwb,
+            Self::Cam16Ucs(_) => TransformFnKind::Cam16Ucs,
+            Self::YCbCr(_) => TransformFnKind::YCbCr,
+            Self::Hlg => TransformFnKind::Hlg,
+            Self::DciP3 => TransformFnKind::DciP3,
+            Self::Gamma(_) => TransformFnKind::Gamma,
+            Self::Registered(_) => TransformFnKind::Registered,
+        }
+    }
+
+    /// Looks up a transform function by name, e.g. as found in file metadata.
+    ///
+    /// Names are matched ignoring case, spaces, `-`, `_` and `.`, so `"ACEScc"`
+    /// and `"aces_cc"` both resolve to [`AcesCc`](Self::AcesCc). With the
+    /// `std` feature, names which match no built-in transform function are
+    /// looked up exactly in the transform functions registered with
+    /// [`register_transform()`](super::registry::register_transform).
+    pub fn by_name(name: &str) -> Option<TransformFn> {
+        let built_in = Self::built_in_by_name(name);
+        #[cfg(feature = "std")]
+        let built_in = built_in.or_else(|| super::registry::registered_transform(name));
+        built_in
+    }
+
+    fn built_in_by_name(name: &str) -> Option<TransformFn> {
+        let mut key = [0u8; 16];
+        let key = normalize_name(name, &mut key)?;
+        Some(match key {
+            b"srgb" => Self::Srgb,
+            b"oklab" => Self::OkLab,
+            b"oklch" => Self::OkLch,
+            b"ciexyy" | b"xyy" => Self::CieXyY,
+            b"cielab" | b"lab" => Self::CieLab,
+            b"cielch" | b"lch" => Self::CieLch,
+            b"cie1960ucs" => Self::Cie1960Ucs,
+            b"cie1960ucsuvv" => Self::Cie1960UcsUvV,
+            b"cie1964uvw" => Self::Cie1964Uvw,
+            b"cie1976luv" | b"luv" => Self::Cie1976Luv,
+            b"hsl" => Self::Hsl,
+            b"hsv" => Self::Hsv,
+            b"hsi" => Self::Hsi,
+            b"hwb" => Self::Hwb,
+            b"ictcppq" => Self::IctCpPq,
+            b"ictcphlg" => Self::IctCpHlg,
+            b"bt601" | b"bt709" => Self::Bt601,
+            b"bt2020" | b"bt202010bit" => Self::Bt2020_10Bit,
+            b"bt202012bit" => Self::Bt2020_12Bit,
+            b"pq" | b"st2084" => Self::Pq,
+            b"hlg" | b"aribstdb67" => Self::Hlg,
+            b"dcip3" => Self::DciP3,
+            b"gamma18" => Self::gamma(1.8),
+            b"gamma22" => Self::gamma(2.2),
+            b"gamma24" => Self::gamma(2.4),
+            b"gamma26" => Self::gamma(2.6),
+            b"acescc" => Self::AcesCc,
+            b"acescct" => Self::AcesCct,
+            b"slog3" => Self::SLog3,
+            b"cam16ucs" => Self::Cam16Ucs(Cam16ViewingConditions::DEFAULT),
+            b"ycbcr" | b"ycbcrbt709" => Self::YCbCr(LumaCoefficients::BT709),
+            b"ycbcrbt2020" => Self::YCbCr(LumaCoefficients::BT2020),
+            _ => return None,
+        })
+    }
+
+    /// The range of linear values the transform function is defined for, i.e.
+    /// the input of its forward and the output of its inverse direction.
+    ///
+    /// `None` if the transform function accepts any value.
+    pub fn linear_domain(&self) -> Option<ValueRange> {
+        match self {
+            // Absolute luminance in cd/m².
+            Self::Pq | Self::IctCpPq => {
+                Some(ValueRange::new(Vec3::splat(0.0), Vec3::splat(10000.0)))
+            }
+            Self::IctCpHlg => Some(ValueRange::new(
+                Vec3::splat(0.0),
+                Vec3::splat(Float::INFINITY),
+            )),
+            Self::Hlg => Some(ValueRange::new(Vec3::splat(0.0), Vec3::splat(1.0))),
+            _ => None,
+        }
+    }
+
+    /// The range of encoded values the transform function is defined for, i.e.
+    /// the output of its forward and the input of its inverse direction.
+    ///
+    /// `None` if the transform function accepts any value.
+    pub fn encoded_domain(&self) -> Option<ValueRange> {
+        match self {
+            Self::Pq | Self::Hlg => Some(ValueRange::new(Vec3::splat(0.0), Vec3::splat(1.0))),
+            Self::IctCpPq | Self::IctCpHlg => Some(ValueRange::new(
+                Vec3::new(0.0, -0.5, -0.5),
+                Vec3::new(1.0, 0.5, 0.5),
+            )),
+            _ => None,
+        }
+    }
 }
 
-/// A set of primary colors picked to define an RGB color space.
-#[repr(u8)]
+/// A per-component range of values, see [`TransformFn::linear_domain()`].
 #[derive(Debug, Copy, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ValueRange {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl ValueRange {
+    pub const fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    /// Whether all components of `value` lie within the range.
+    pub fn contains(&self, value: Vec3) -> bool {
+        value.x >= self.min.x
+            && value.y >= self.min.y
+            && value.z >= self.min.z
+            && value.x <= self.max.x
+            && value.y <= self.max.y
+            && value.z <= self.max.z
+    }
+}
+
+/// A set of primary colors picked to define an RGB color space.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(from = "RgbPrimariesProxy")
+)]
 #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 pub enum RgbPrimaries {
     /// BT.709 is the sRGB primaries.
@@ -109,6 +508,15 @@ pub enum RgbPrimaries {
     AdobeWide,
     Apple,
     ProPhoto,
+    /// Sony S-Gamut3, which has the same primaries as S-Gamut.
+    SGamut3,
+    /// Sony S-Gamut3.Cine, a smaller gamut than S-Gamut3 which is easier to
+    /// grade for digital cinema.
+    SGamut3Cine,
+    /// S-Gamut3 as measured for the Sony Venice cameras.
+    VeniceSGamut3,
+    /// S-Gamut3.Cine as measured for the Sony Venice cameras.
+    VeniceSGamut3Cine,
     CieRgb,
     /// The reference XYZ color space
     CieXyz,
@@ -116,6 +524,18 @@ pub enum RgbPrimaries {
     Custom([[Float; 2]; 3]),
 }
 impl RgbPrimaries {
+    /// Compares `self` and `other` like `==`, but considers `Custom` values
+    /// equal when their chromaticities differ by at most [`CUSTOM_EPSILON`],
+    /// e.g. computed and literal ones.
+    pub fn approx_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Custom(a), Self::Custom(b)) => {
+                values_approx_eq(a.as_flattened(), b.as_flattened())
+            }
+            _ => self == other,
+        }
+    }
+
     pub const ENUM_COUNT: RgbPrimaries = RgbPrimaries::CieXyz;
 
     pub const fn values(&self) -> &[[Float; 2]; 3] {
@@ -132,6 +552,18 @@ impl RgbPrimaries {
                 [0.036598, 0.000105],
             ],
             Self::Apple => &[[0.625, 0.34], [0.28, 0.595], [0.155, 0.07]],
+            Self::SGamut3 => &[[0.730, 0.280], [0.140, 0.855], [0.100, -0.050]],
+            Self::SGamut3Cine => &[[0.766, 0.275], [0.225, 0.800], [0.089, -0.087]],
+            Self::VeniceSGamut3 => &[
+                [0.740464, 0.279364],
+                [0.089241, 0.893810],
+                [0.110488, -0.052579],
+            ],
+            Self::VeniceSGamut3Cine => &[
+                [0.775902, 0.274502],
+                [0.188683, 0.828685],
+                [0.101337, -0.089188],
+            ],
             Self::P3 => &[[0.680, 0.320], [0.265, 0.690], [0.150, 0.060]],
             Self::CieRgb => &[[0.7350, 0.2650], [0.2740, 0.7170], [0.1670, 0.0090]],
             Self::CieXyz => &[[1.0, 0.0], [0.0, 1.0], [0.0, 0.0]],
@@ -141,6 +573,8 @@ impl RgbPrimaries {
 
     /// Create RGB primaries from CIE xy chromaticity coordinates.
     /// Automatically detects and returns known primaries if values match within tolerance.
+    /// With the `std` feature, primaries registered at runtime are detected
+    /// as well, see the [`registry`](super::registry) module.
     pub fn from_rgb_xy(r: [Float; 2], g: [Float; 2], b: [Float; 2]) -> Self {
         const EPSILON: Float = 1e-4;
         let values = [r, g, b];
@@ -155,6 +589,10 @@ impl RgbPrimaries {
             Self::AdobeWide,
             Self::ProPhoto,
             Self::Apple,
+            Self::SGamut3,
+            Self::SGamut3Cine,
+            Self::VeniceSGamut3,
+            Self::VeniceSGamut3Cine,
             Self::P3,
             Self::CieRgb,
             Self::CieXyz,
@@ -166,14 +604,31 @@ impl RgbPrimaries {
             }
         }
 
+        #[cfg(feature = "std")]
+        if let Some(registered) = super::registry::find_primaries(|registered| {
+            primaries_match(&values, registered, EPSILON)
+        }) {
+            return Self::Custom(registered);
+        }
+
         Self::Custom(values)
     }
 
     /// Try to canonicalize Custom variant to a known one in place.
     /// Returns Ok(()) if successful (or already canonical), Err if no match found.
     pub fn canonicalize(&mut self) -> Result<(), ColorError> {
+        self.canonicalize_with_tol(1e-4)
+    }
+
+    /// Like [`canonicalize()`](Self::canonicalize), but matches chromaticities
+    /// which differ from known primaries by less than `tolerance`, e.g. `1e-3`
+    /// for values rounded to three decimals.
+    ///
+    /// If the values match several known primaries, the closest one wins, i.e.
+    /// the one with the smallest largest difference of a chromaticity
+    /// coordinate.
+    pub fn canonicalize_with_tol(&mut self, tolerance: Float) -> Result<(), ColorError> {
         if let Self::Custom(values) = self {
-            const EPSILON: Float = 1e-4;
             let variants = [
                 Self::Bt709,
                 Self::Bt2020,
@@ -183,65 +638,158 @@ impl RgbPrimaries {
                 Self::AdobeWide,
                 Self::ProPhoto,
                 Self::Apple,
+                Self::SGamut3,
+                Self::SGamut3Cine,
+                Self::VeniceSGamut3,
+                Self::VeniceSGamut3Cine,
                 Self::P3,
                 Self::CieRgb,
                 Self::CieXyz,
             ];
 
-            for variant in &variants {
-                if primaries_match(values, variant.values(), EPSILON) {
-                    *self = *variant;
-                    return Ok(());
-                }
-            }
-            Err(ColorError::CanonicalizationFailed)
+            let closest = variants
+                .iter()
+                .map(|variant| (variant, primaries_distance(values, variant.values())))
+                .filter(|(_, distance)| *distance < tolerance)
+                .min_by(|(_, a), (_, b)| a.total_cmp(b));
+            *self = *closest.ok_or(ColorError::CanonicalizationFailed)?.0;
+            Ok(())
         } else {
             Ok(()) // Already canonical
         }
     }
 }
 
+/// `Custom` primaries compare bitwise, see
+/// [`approx_eq()`](RgbPrimaries::approx_eq) to absorb rounding differences.
+impl PartialEq for RgbPrimaries {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Custom(a), Self::Custom(b)) => bits_eq(a.as_flattened(), b.as_flattened()),
+            _ => core::mem::discriminant(self) == core::mem::discriminant(other),
+        }
+    }
+}
+
 impl core::hash::Hash for RgbPrimaries {
     fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         core::mem::discriminant(self).hash(state);
         if let Self::Custom(values) = self {
-            for primary in values {
-                for coord in primary {
-                    // Handle -0.0 vs 0.0 and NaN cases
-                    #[cfg(not(feature = "f64"))]
-                    let bits: u32 = if *coord == 0.0 {
-                        0u32 // Both -0.0 and 0.0 hash to same value
-                    } else if coord.is_nan() {
-                        u32::MAX // All NaN values hash to same value
-                    } else {
-                        coord.to_bits()
-                    };
-
-                    #[cfg(feature = "f64")]
-                    let bits: u64 = if *coord == 0.0 {
-                        0u64 // Both -0.0 and 0.0 hash to same value
-                    } else if coord.is_nan() {
-                        u64::MAX // All NaN values hash to same value
-                    } else {
-                        coord.to_bits()
-                    };
-
-                    bits.hash(state);
+            for value in values.as_flattened() {
+                value.to_bits().hash(state);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "color-matrices")]
+impl RgbPrimaries {
+    /// The index of the variant, for comparing variants in const contexts.
+    const fn variant_index(&self) -> u8 {
+        match self {
+            Self::Bt709 => 0,
+            Self::Bt2020 => 1,
+            Self::AcesAp0 => 2,
+            Self::AcesAp1 => 3,
+            Self::P3 => 4,
+            Self::Adobe1998 => 5,
+            Self::AdobeWide => 6,
+            Self::Apple => 7,
+            Self::ProPhoto => 8,
+            Self::SGamut3 => 9,
+            Self::SGamut3Cine => 10,
+            Self::VeniceSGamut3 => 11,
+            Self::VeniceSGamut3Cine => 12,
+            Self::CieRgb => 13,
+            Self::CieXyz => 14,
+            Self::Custom(_) => u8::MAX,
+        }
+    }
+
+    /// Compares `self` and `other` like `==`, in const contexts.
+    pub(crate) const fn const_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Custom(a), Self::Custom(b)) => {
+                let (a, b) = (a.as_flattened(), b.as_flattened());
+                let mut i = 0;
+                while i < a.len() {
+                    if a[i].to_bits() != b[i].to_bits() {
+                        return false;
+                    }
+                    i += 1;
                 }
+                true
             }
+            _ => self.variant_index() == other.variant_index(),
         }
     }
 }
 
 impl Eq for RgbPrimaries {}
 
+/// Deserializes like [`RgbPrimaries`], which converts from it to canonicalize
+/// the deserialized value.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(rename = "RgbPrimaries")]
+enum RgbPrimariesProxy {
+    Bt709,
+    Bt2020,
+    AcesAp0,
+    AcesAp1,
+    P3,
+    Adobe1998,
+    AdobeWide,
+    Apple,
+    ProPhoto,
+    SGamut3,
+    SGamut3Cine,
+    VeniceSGamut3,
+    VeniceSGamut3Cine,
+    CieRgb,
+    CieXyz,
+    Custom([[Float; 2]; 3]),
+}
+
+/// Deserialized `Custom` primaries which match known primaries are
+/// [canonicalized](RgbPrimaries::canonicalize).
+#[cfg(feature = "serde")]
+impl From<RgbPrimariesProxy> for RgbPrimaries {
+    fn from(proxy: RgbPrimariesProxy) -> Self {
+        let mut primaries = match proxy {
+            RgbPrimariesProxy::Bt709 => Self::Bt709,
+            RgbPrimariesProxy::Bt2020 => Self::Bt2020,
+            RgbPrimariesProxy::AcesAp0 => Self::AcesAp0,
+            RgbPrimariesProxy::AcesAp1 => Self::AcesAp1,
+            RgbPrimariesProxy::P3 => Self::P3,
+            RgbPrimariesProxy::Adobe1998 => Self::Adobe1998,
+            RgbPrimariesProxy::AdobeWide => Self::AdobeWide,
+            RgbPrimariesProxy::Apple => Self::Apple,
+            RgbPrimariesProxy::ProPhoto => Self::ProPhoto,
+            RgbPrimariesProxy::SGamut3 => Self::SGamut3,
+            RgbPrimariesProxy::SGamut3Cine => Self::SGamut3Cine,
+            RgbPrimariesProxy::VeniceSGamut3 => Self::VeniceSGamut3,
+            RgbPrimariesProxy::VeniceSGamut3Cine => Self::VeniceSGamut3Cine,
+            RgbPrimariesProxy::CieRgb => Self::CieRgb,
+            RgbPrimariesProxy::CieXyz => Self::CieXyz,
+            RgbPrimariesProxy::Custom(primaries) => Self::Custom(primaries),
+        };
+        let _ = primaries.canonicalize();
+        primaries
+    }
+}
+
 /// Defines the color white ("achromatic point") in an RGB color system.
 ///
 /// White points are derived from an "illuminant" which are defined
 /// as some reference lighting condition based on a Spectral Power Distribution.
 #[repr(u8)]
-#[derive(Debug, Copy, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(from = "WhitePointProxy")
+)]
 #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 pub enum WhitePoint {
     /// Incandescent/tungsten
@@ -273,6 +821,16 @@ pub enum WhitePoint {
     Custom([Float; 3]),
 }
 impl WhitePoint {
+    /// Compares `self` and `other` like `==`, but considers `Custom` values
+    /// equal when they differ by at most [`CUSTOM_EPSILON`], e.g. computed and
+    /// literal ones.
+    pub fn approx_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Custom(a), Self::Custom(b)) => values_approx_eq(a, b),
+            _ => self == other,
+        }
+    }
+
     pub const ENUM_COUNT: WhitePoint = WhitePoint::F11;
 
     // Pulled from http://www.brucelindbloom.com/index.html?Eqn_ChromAdapt.html
@@ -298,9 +856,24 @@ impl WhitePoint {
         }
     }
 
+    /// Returns the CIE xy chromaticity coordinates of the white point.
+    pub fn xy(&self) -> [Float; 2] {
+        let [x, y, z] = *self.values();
+        let sum = x + y + z;
+        [x / sum, y / sum]
+    }
+
+    /// Returns the CIE XYZ tristimulus values of the white point, normalized
+    /// to Y=1.0 like [`values()`](Self::values).
+    pub fn xyz(&self) -> Vec3 {
+        Vec3::from_slice(self.values())
+    }
+
     /// Create a white point from CIE xy chromaticity coordinates.
     /// Converts to XYZ with Y=1.0.
     /// Automatically detects and returns known white points if values match within tolerance.
+    /// With the `std` feature, white points registered at runtime are detected
+    /// as well, see the [`registry`](super::registry) module.
     pub fn from_xy(x: Float, y: Float) -> Self {
         const EPSILON: Float = 1e-4;
         let z = 1.0 - x - y;
@@ -329,14 +902,29 @@ impl WhitePoint {
             }
         }
 
+        #[cfg(feature = "std")]
+        if let Some(registered) =
+            super::registry::find_white_point(|registered| xyz_match(&xyz, registered, EPSILON))
+        {
+            return Self::Custom(registered);
+        }
+
         Self::Custom(xyz)
     }
 
     /// Try to canonicalize Custom variant to a known one in place.
     /// Returns Ok(()) if successful (or already canonical), Err if no match found.
     pub fn canonicalize(&mut self) -> Result<(), ColorError> {
+        self.canonicalize_with_tol(1e-4)
+    }
+
+    /// Like [`canonicalize()`](Self::canonicalize), but matches XYZ values
+    /// which differ from known white points by less than `tolerance`.
+    ///
+    /// If the values match several known white points, the closest one wins,
+    /// i.e. the one with the smallest largest difference of a component.
+    pub fn canonicalize_with_tol(&mut self, tolerance: Float) -> Result<(), ColorError> {
         if let Self::Custom(values) = self {
-            const EPSILON: Float = 1e-4;
             let variants = [
                 Self::A,
                 Self::B,
@@ -353,51 +941,131 @@ impl WhitePoint {
                 Self::F11,
             ];
 
-            for variant in &variants {
-                if xyz_match(values, variant.values(), EPSILON) {
-                    *self = *variant;
-                    return Ok(());
-                }
-            }
-            Err(ColorError::CanonicalizationFailed)
+            let closest = variants
+                .iter()
+                .map(|variant| (variant, xyz_distance(values, variant.values())))
+                .filter(|(_, distance)| *distance < tolerance)
+                .min_by(|(_, a), (_, b)| a.total_cmp(b));
+            *self = *closest.ok_or(ColorError::CanonicalizationFailed)?.0;
+            Ok(())
         } else {
             Ok(()) // Already canonical
         }
     }
 }
 
+/// `Custom` white points compare bitwise, see
+/// [`approx_eq()`](WhitePoint::approx_eq) to absorb rounding differences.
+impl PartialEq for WhitePoint {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Custom(a), Self::Custom(b)) => bits_eq(a, b),
+            _ => core::mem::discriminant(self) == core::mem::discriminant(other),
+        }
+    }
+}
+
 impl core::hash::Hash for WhitePoint {
     fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         core::mem::discriminant(self).hash(state);
         if let Self::Custom(values) = self {
-            for coord in values {
-                // Handle -0.0 vs 0.0 and NaN cases
-                #[cfg(not(feature = "f64"))]
-                let bits: u32 = if *coord == 0.0 {
-                    0u32 // Both -0.0 and 0.0 hash to same value
-                } else if coord.is_nan() {
-                    u32::MAX // All NaN values hash to same value
-                } else {
-                    coord.to_bits()
-                };
+            for value in values {
+                value.to_bits().hash(state);
+            }
+        }
+    }
+}
 
-                #[cfg(feature = "f64")]
-                let bits: u64 = if *coord == 0.0 {
-                    0u64 // Both -0.0 and 0.0 hash to same value
-                } else if coord.is_nan() {
-                    u64::MAX // All NaN values hash to same value
-                } else {
-                    coord.to_bits()
-                };
+#[cfg(feature = "color-matrices")]
+impl WhitePoint {
+    /// The index of the variant, for comparing variants in const contexts.
+    const fn variant_index(&self) -> u8 {
+        match self {
+            Self::A => 0,
+            Self::B => 1,
+            Self::C => 2,
+            Self::E => 3,
+            Self::D50 => 4,
+            Self::D55 => 5,
+            Self::D60 => 6,
+            Self::D65 => 7,
+            Self::D75 => 8,
+            Self::P3Dci => 9,
+            Self::F2 => 10,
+            Self::F7 => 11,
+            Self::F11 => 12,
+            Self::Custom(_) => u8::MAX,
+        }
+    }
 
-                bits.hash(state);
+    /// Compares `self` and `other` like `==`, in const contexts.
+    pub(crate) const fn const_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Custom(a), Self::Custom(b)) => {
+                let mut i = 0;
+                while i < a.len() {
+                    if a[i].to_bits() != b[i].to_bits() {
+                        return false;
+                    }
+                    i += 1;
+                }
+                true
             }
+            _ => self.variant_index() == other.variant_index(),
         }
     }
 }
 
 impl Eq for WhitePoint {}
 
+/// Deserializes like [`WhitePoint`], which converts from it to canonicalize
+/// the deserialized value.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(rename = "WhitePoint")]
+enum WhitePointProxy {
+    A,
+    B,
+    C,
+    E,
+    D50,
+    D55,
+    D60,
+    D65,
+    D75,
+    P3Dci,
+    F2,
+    F7,
+    F11,
+    Custom([Float; 3]),
+}
+
+/// Deserialized `Custom` white points which match known white points are
+/// [canonicalized](WhitePoint::canonicalize).
+#[cfg(feature = "serde")]
+impl From<WhitePointProxy> for WhitePoint {
+    fn from(proxy: WhitePointProxy) -> Self {
+        let mut white_point = match proxy {
+            WhitePointProxy::A => Self::A,
+            WhitePointProxy::B => Self::B,
+            WhitePointProxy::C => Self::C,
+            WhitePointProxy::E => Self::E,
+            WhitePointProxy::D50 => Self::D50,
+            WhitePointProxy::D55 => Self::D55,
+            WhitePointProxy::D60 => Self::D60,
+            WhitePointProxy::D65 => Self::D65,
+            WhitePointProxy::D75 => Self::D75,
+            WhitePointProxy::P3Dci => Self::P3Dci,
+            WhitePointProxy::F2 => Self::F2,
+            WhitePointProxy::F7 => Self::F7,
+            WhitePointProxy::F11 => Self::F11,
+            WhitePointProxy::Custom(white_point) => Self::Custom(white_point),
+        };
+        let _ = white_point.canonicalize();
+        white_point
+    }
+}
+
 /// A color space defined in data by its [primaries][RgbPrimaries], [white
 /// point][WhitePoint], and an optional [invertible transform
 /// function][TransformFn].
@@ -440,6 +1108,15 @@ pub struct ColorSpace {
 }
 
 impl ColorSpace {
+    /// Compares `self` and `other` like `==`, but considers `Custom`
+    /// primaries and white points equal within [`CUSTOM_EPSILON`], see
+    /// [`RgbPrimaries::approx_eq()`] and [`WhitePoint::approx_eq()`].
+    pub fn approx_eq(&self, other: &Self) -> bool {
+        self.primaries.approx_eq(&other.primaries)
+            && self.white_point.approx_eq(&other.white_point)
+            && self.transform_fn == other.transform_fn
+    }
+
     pub const fn new(
         primaries: RgbPrimaries,
         white_point: WhitePoint,
@@ -452,6 +1129,64 @@ impl ColorSpace {
         }
     }
 
+    /// Looks up a built-in color space by name, e.g. as found in file
+    /// metadata.
+    ///
+    /// Names are matched like in [`TransformFn::by_name()`]. Names of RGB
+    /// spaces refer to their encoded variant, prefix them with `linear` for
+    /// the linear one, e.g. `"linear srgb"`. ACEScc and ACEScct share their
+    /// primaries and white point with ACEScg and only differ in their transform
+    /// function, so `"ACEScc"` and `"ACEScct"` resolve to distinct spaces.
+    pub fn by_name(name: &str) -> Option<ColorSpace> {
+        use color_spaces::*;
+
+        let mut key = [0u8; 24];
+        let key = normalize_name(name, &mut key)?;
+        Some(match key {
+            b"srgb" => ENCODED_SRGB,
+            b"srgbgamma22" => SRGB_GAMMA_2_2,
+            b"linearsrgb" => LINEAR_SRGB,
+            b"bt709" | b"rec709" => ENCODED_BT_709,
+            b"linearbt709" | b"linearrec709" => BT_709,
+            b"bt2020" | b"rec2020" => ENCODED_BT_2020,
+            b"linearbt2020" | b"linearrec2020" => BT_2020,
+            b"bt2100pq" | b"rec2100pq" => ENCODED_BT_2100_PQ,
+            b"bt2100hlg" | b"rec2100hlg" => ENCODED_BT_2100_HLG,
+            b"acescg" => ACES_CG,
+            b"acescc" => ACES_CC,
+            b"acescct" => ACES_CCT,
+            b"aces20651" | b"aces" => ACES_2065_1,
+            b"ciergb" => CIE_RGB,
+            b"ciexyz" | b"xyz" => CIE_XYZ,
+            b"oklab" => OK_LAB,
+            b"oklch" => OK_LCH,
+            b"cam16ucs" => CAM16_UCS,
+            b"hwb" => HWB,
+            b"ycbcr" | b"ycbcrbt709" => YCBCR_BT709,
+            b"ycbcrbt2020" => YCBCR_BT2020,
+            b"ictcppq" => ICT_CP_PQ,
+            b"ictcphlg" => ICT_CP_HLG,
+            b"displayp3" => ENCODED_DISPLAY_P3,
+            b"lineardisplayp3" => DISPLAY_P3,
+            b"p3d60" => P3_D60,
+            b"p3theater" | b"lineardcip3" | b"linearp3dci" => P3_THEATER,
+            b"dcip3" | b"p3dci" => ENCODED_DCI_P3,
+            b"adobe1998" | b"adobergb" => ADOBE_1998,
+            b"adobewide" => ADOBE_WIDE,
+            b"prophoto" => PRO_PHOTO,
+            b"apple" => APPLE,
+            b"sgamut3" => S_GAMUT3,
+            b"sgamut3slog3" => ENCODED_S_GAMUT3,
+            b"sgamut3cine" => S_GAMUT3_CINE,
+            b"sgamut3cineslog3" => ENCODED_S_GAMUT3_CINE,
+            b"venicesgamut3" => VENICE_S_GAMUT3,
+            b"venicesgamut3slog3" => ENCODED_VENICE_S_GAMUT3,
+            b"venicesgamut3cine" => VENICE_S_GAMUT3_CINE,
+            b"venicesgamut3cineslog3" => ENCODED_VENICE_S_GAMUT3_CINE,
+            _ => return None,
+        })
+    }
+
     pub(crate) const fn linear(primaries: RgbPrimaries, white_point: WhitePoint) -> Self {
         Self {
             primaries,
@@ -473,20 +1208,81 @@ impl ColorSpace {
         }
     }
 
+    /// The primaries of the space, as the known variant if the space was
+    /// created with [`Custom`](RgbPrimaries::Custom) chromaticities matching
+    /// one, see [`RgbPrimaries::canonicalize()`].
     pub fn primaries(&self) -> RgbPrimaries {
-        self.primaries
+        let mut primaries = self.primaries;
+        let _ = primaries.canonicalize();
+        primaries
     }
 
+    /// The white point of the space, as the known variant if the space was
+    /// created with a [`Custom`](WhitePoint::Custom) white point matching one,
+    /// see [`WhitePoint::canonicalize()`].
     pub fn white_point(&self) -> WhitePoint {
-        self.white_point
+        let mut white_point = self.white_point;
+        let _ = white_point.canonicalize();
+        white_point
     }
 
+    /// The transform function of the space, or `None` for linear spaces.
     pub fn transform_function(&self) -> Option<TransformFn> {
         self.transform_fn
     }
 
+    /// Returns the pure red, green and blue of the space, i.e. `[1, 0, 0]`,
+    /// `[0, 1, 0]` and `[0, 0, 1]` in this space, e.g. to visualize the
+    /// corners of its gamut.
+    pub fn primary_colors(&self) -> [Color; 3] {
+        [
+            Color::new(1.0, 0.0, 0.0, *self),
+            Color::new(0.0, 1.0, 0.0, *self),
+            Color::new(0.0, 0.0, 1.0, *self),
+        ]
+    }
+
+    /// Returns the nominal `[min, max]` range of each component, e.g. for
+    /// slider bounds in color pickers.
+    ///
+    /// RGB spaces use [0, 1], for linear spaces the range of SDR values, and
+    /// CIE XYZ uses [0, white point]. Lab-like spaces use the usual ranges of
+    /// their axes, e.g. [-128, 127] for CIELAB a and b, which colors may
+    /// exceed. Hues are in degrees, [0, 360], except for Oklch, where they are
+    /// in radians, [-π, π]. Colors outside of the ranges can still be valid.
+    pub fn component_ranges(&self) -> [[Float; 2]; 3] {
+        let Some(transform_fn) = self.transform_fn else {
+            if self.primaries == RgbPrimaries::CieXyz {
+                return self.white_point.values().map(|max| [0.0, max]);
+            }
+            return [[0.0, 1.0]; 3];
+        };
+        match transform_fn {
+            TransformFn::OkLab => [[0.0, 1.0], [-0.4, 0.4], [-0.4, 0.4]],
+            TransformFn::OkLch => [[0.0, 1.0], [0.0, 0.4], [-PI, PI]],
+            TransformFn::CieLab => [[0.0, 100.0], [-128.0, 127.0], [-128.0, 127.0]],
+            TransformFn::CieLch => [[0.0, 100.0], [0.0, 150.0], [0.0, 360.0]],
+            TransformFn::Cie1964Uvw => [[-100.0, 100.0], [-100.0, 100.0], [-17.0, 100.0]],
+            TransformFn::Cie1976Luv => [[0.0, 100.0], [-134.0, 224.0], [-140.0, 122.0]],
+            TransformFn::Cam16Ucs(_) => [[0.0, 100.0], [-50.0, 50.0], [-50.0, 50.0]],
+            TransformFn::Hsl | TransformFn::Hsv | TransformFn::Hsi | TransformFn::Hwb => {
+                [[0.0, 360.0], [0.0, 1.0], [0.0, 1.0]]
+            }
+            TransformFn::IctCpPq | TransformFn::IctCpHlg | TransformFn::YCbCr(_) => {
+                [[0.0, 1.0], [-0.5, 0.5], [-0.5, 0.5]]
+            }
+            TransformFn::AcesCc => [[-0.3584, 1.4679]; 3],
+            TransformFn::AcesCct => [[0.0729, 1.4679]; 3],
+            _ => [[0.0, 1.0]; 3],
+        }
+    }
+
     /// Creates a new color space with the primaries and white point from
     /// `this`, but with the provided [`TransformFn`].
+    ///
+    /// E.g. `BT_2020.with_transform(TransformFn::Pq)` attaches the PQ transfer
+    /// function to linear BT.2020, giving
+    /// [`ENCODED_BT_2100_PQ`](color_spaces::ENCODED_BT_2100_PQ).
     pub fn with_transform(&self, new_transform: TransformFn) -> Self {
         Self {
             primaries: self.primaries,
@@ -495,6 +1291,17 @@ impl ColorSpace {
         }
     }
 
+    /// Creates a new color space with the primaries and white point from
+    /// `this`, but with the [CAM16-UCS transform][TransformFn::Cam16Ucs] under
+    /// the given viewing `conditions`.
+    ///
+    /// E.g. `CAM16_UCS.with_cam16_conditions(...)` changes the viewing
+    /// conditions of [`CAM16_UCS`](color_spaces::CAM16_UCS), which uses
+    /// [`Cam16ViewingConditions::DEFAULT`].
+    pub fn with_cam16_conditions(&self, conditions: Cam16ViewingConditions) -> Self {
+        self.with_transform(TransformFn::Cam16Ucs(conditions))
+    }
+
     /// Creates a new color space with the transform function and white point
     /// from `this`, but with the provided [`WhitePoint`].
     pub fn with_whitepoint(&self, new_wp: WhitePoint) -> Self {
@@ -505,6 +1312,32 @@ impl ColorSpace {
         }
     }
 
+    /// Creates a new color space with the transform function from `this` and
+    /// the provided [`WhitePoint`], whose primaries are the primaries of `this`
+    /// adapted to `new_wp` with `cat`.
+    ///
+    /// Unlike [`with_whitepoint()`](Self::with_whitepoint), which keeps the
+    /// chromaticities of the primaries, this keeps their appearance, so
+    /// [`ColorConversion::with_cat()`] with the same `cat` maps colors from
+    /// `this` to the new space unchanged. The adapted primaries are
+    /// [`Custom`](RgbPrimaries::Custom) unless they match known ones.
+    pub fn with_whitepoint_using(&self, new_wp: WhitePoint, cat: CatMethod) -> Self {
+        if self.primaries == RgbPrimaries::CieXyz || self.white_point == new_wp {
+            return self.with_whitepoint(new_wp);
+        }
+        let adaptation = cat.chromatic_adaptation_transform(self.white_point.xyz(), new_wp.xyz());
+        let [r, g, b] = self.primaries.values().map(|[x, y]| {
+            let xyz = adaptation * Vec3::new(x / y, 1.0, (1.0 - x - y) / y);
+            let sum = xyz.x + xyz.y + xyz.z;
+            [xyz.x / sum, xyz.y / sum]
+        });
+        Self {
+            primaries: RgbPrimaries::from_rgb_xy(r, g, b),
+            white_point: new_wp,
+            transform_fn: self.transform_fn,
+        }
+    }
+
     /// Creates a new color space with the primaries and transform function from
     /// `this`, but with the provided [`RgbPrimaries`].
     pub fn with_primaries(&self, primaries: RgbPrimaries) -> Self {
@@ -563,31 +1396,160 @@ impl ColorSpace {
     }
 }
 
-pub mod color_spaces {
-    use super::*;
+/// Smallest area of the triangle of valid primaries in the CIE xy diagram.
+const MIN_PRIMARIES_AREA: Float = 1e-6;
 
-    /// Linear sRGB is a linear encoding in [BT.709
-    /// primaries][RgbPrimaries::Bt709] with a [D65
-    /// whitepoint.][WhitePoint::D65] Linear sRGB is equivalent to [BT_709].
-    pub const LINEAR_SRGB: ColorSpace = ColorSpace::linear(RgbPrimaries::Bt709, WhitePoint::D65);
+/// Builds a [`ColorSpace`] from primaries, a white point and a transform
+/// function, validating that they describe a usable space.
+///
+/// Unset parts default to those of [linear sRGB](color_spaces::LINEAR_SRGB).
+///
+/// ```
+/// use kolor::details::color::{ColorSpaceBuilder, RgbPrimaries, TransformFn, WhitePoint};
+///
+/// let space = ColorSpaceBuilder::new()
+///     .primaries(RgbPrimaries::from_rgb_xy([0.7, 0.29], [0.2, 0.72], [0.14, 0.05]))
+///     .white_point(WhitePoint::D65)
+///     .transform(TransformFn::Srgb)
+///     .build()
+///     .unwrap();
+/// assert_eq!(space.transform_function(), Some(TransformFn::Srgb));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ColorSpaceBuilder {
+    primaries: RgbPrimaries,
+    white_point: WhitePoint,
+    transform_fn: Option<TransformFn>,
+}
 
-    /// Encoded sRGB is [linear sRGB][LINEAR_SRGB] with the [sRGB
-    /// OETF](TransformFn::Srgb) applied (also called 'gamma-compressed').
-    pub const ENCODED_SRGB: ColorSpace = ColorSpace::new(
-        RgbPrimaries::Bt709,
-        WhitePoint::D65,
-        Some(TransformFn::Srgb),
-    );
+impl Default for ColorSpaceBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-    /// BT.709 is a linear encoding in [BT.709 primaries][RgbPrimaries::Bt709]
-    /// with a [D65 whitepoint.][WhitePoint::D65]. It's equivalent to [Linear
-    /// sRGB][LINEAR_SRGB]
-    pub const BT_709: ColorSpace = ColorSpace::linear(RgbPrimaries::Bt709, WhitePoint::D65);
+impl ColorSpaceBuilder {
+    pub const fn new() -> Self {
+        Self {
+            primaries: RgbPrimaries::Bt709,
+            white_point: WhitePoint::D65,
+            transform_fn: None,
+        }
+    }
 
-    /// Encoded BT.709 is [BT.709](BT_709) with the [BT.709
-    /// OETF](TransformFn::Bt601) applied.
-    pub const ENCODED_BT_709: ColorSpace = ColorSpace::new(
-        RgbPrimaries::Bt709,
+    pub fn primaries(self, primaries: RgbPrimaries) -> Self {
+        Self { primaries, ..self }
+    }
+
+    pub fn white_point(self, white_point: WhitePoint) -> Self {
+        Self {
+            white_point,
+            ..self
+        }
+    }
+
+    pub fn transform(self, transform_fn: TransformFn) -> Self {
+        Self {
+            transform_fn: Some(transform_fn),
+            ..self
+        }
+    }
+
+    /// Builds the color space.
+    ///
+    /// Returns [`ColorError::DegeneratePrimaries`] if `Custom` primaries are
+    /// not finite, have a chromaticity with y ≤ 0 or if the triangle they span
+    /// has (almost) no area, [`ColorError::NonPositiveWhitePoint`] if the
+    /// components of a `Custom` white point are not finite and positive, and
+    /// [`ColorError::WhitePointOutsideGamut`] if the chromaticity of the white
+    /// point is not inside the triangle of the primaries. Known primaries and
+    /// white points are always valid on their own.
+    pub fn build(self) -> Result<ColorSpace, ColorError> {
+        let [r, g, b] = *self.primaries.values();
+        if let RgbPrimaries::Custom(values) = self.primaries
+            && values
+                .iter()
+                .any(|[x, y]| !x.is_finite() || !y.is_finite() || *y <= 0.0)
+        {
+            return Err(ColorError::DegeneratePrimaries);
+        }
+        // Twice the signed area of the triangle, positive if counterclockwise.
+        let cross = |a: [Float; 2], b: [Float; 2], c: [Float; 2]| {
+            (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+        };
+        let area = cross(r, g, b);
+        if area.abs() < 2.0 * MIN_PRIMARIES_AREA {
+            return Err(ColorError::DegeneratePrimaries);
+        }
+
+        if self
+            .white_point
+            .values()
+            .iter()
+            .any(|value| !value.is_finite() || *value <= 0.0)
+        {
+            return Err(ColorError::NonPositiveWhitePoint);
+        }
+        let w = self.white_point.xy();
+        let inside = [cross(r, g, w), cross(g, b, w), cross(b, r, w)]
+            .iter()
+            .all(|edge| edge * area.signum() > 0.0);
+        if !inside {
+            return Err(ColorError::WhitePointOutsideGamut);
+        }
+
+        Ok(ColorSpace::new(
+            self.primaries,
+            self.white_point,
+            self.transform_fn,
+        ))
+    }
+}
+
+impl TryFrom<&str> for ColorSpace {
+    type Error = ColorError;
+
+    /// Looks up the color space by name, see [`ColorSpace::by_name()`].
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        ColorSpace::by_name(name).ok_or(ColorError::UnknownColorSpaceName)
+    }
+}
+
+pub mod color_spaces {
+    use super::*;
+
+    /// Linear sRGB is a linear encoding in [BT.709
+    /// primaries][RgbPrimaries::Bt709] with a [D65
+    /// whitepoint.][WhitePoint::D65] Linear sRGB is equivalent to [BT_709].
+    pub const LINEAR_SRGB: ColorSpace = ColorSpace::linear(RgbPrimaries::Bt709, WhitePoint::D65);
+
+    /// Encoded sRGB is [linear sRGB][LINEAR_SRGB] with the [sRGB
+    /// OETF](TransformFn::Srgb) applied (also called 'gamma-compressed').
+    pub const ENCODED_SRGB: ColorSpace = ColorSpace::new(
+        RgbPrimaries::Bt709,
+        WhitePoint::D65,
+        Some(TransformFn::Srgb),
+    );
+
+    /// [Linear sRGB][LINEAR_SRGB] encoded with a [pure 2.2
+    /// gamma](TransformFn::gamma) instead of the piecewise [sRGB
+    /// OETF](TransformFn::Srgb), as some pipelines and legacy assets
+    /// approximate it. Both encodings differ most near black.
+    pub const SRGB_GAMMA_2_2: ColorSpace = ColorSpace::new(
+        RgbPrimaries::Bt709,
+        WhitePoint::D65,
+        Some(TransformFn::gamma(2.2)),
+    );
+
+    /// BT.709 is a linear encoding in [BT.709 primaries][RgbPrimaries::Bt709]
+    /// with a [D65 whitepoint.][WhitePoint::D65]. It's equivalent to [Linear
+    /// sRGB][LINEAR_SRGB]
+    pub const BT_709: ColorSpace = ColorSpace::linear(RgbPrimaries::Bt709, WhitePoint::D65);
+
+    /// Encoded BT.709 is [BT.709](BT_709) with the [BT.709
+    /// OETF](TransformFn::Bt601) applied.
+    pub const ENCODED_BT_709: ColorSpace = ColorSpace::new(
+        RgbPrimaries::Bt709,
         WhitePoint::D65,
         Some(TransformFn::Bt601),
     );
@@ -596,6 +1558,22 @@ pub mod color_spaces {
     /// with a [D60 whitepoint][WhitePoint::D60].
     pub const ACES_CG: ColorSpace = ColorSpace::linear(RgbPrimaries::AcesAp1, WhitePoint::D60);
 
+    /// ACEScc is a logarithmic encoding of [ACEScg](ACES_CG) with the
+    /// [ACEScc transform][TransformFn::AcesCc] applied.
+    pub const ACES_CC: ColorSpace = ColorSpace::new(
+        RgbPrimaries::AcesAp1,
+        WhitePoint::D60,
+        Some(TransformFn::AcesCc),
+    );
+
+    /// ACEScct is a logarithmic encoding of [ACEScg](ACES_CG) with a linear
+    /// toe, with the [ACEScct transform][TransformFn::AcesCct] applied.
+    pub const ACES_CCT: ColorSpace = ColorSpace::new(
+        RgbPrimaries::AcesAp1,
+        WhitePoint::D60,
+        Some(TransformFn::AcesCct),
+    );
+
     /// ACES2065-1 is a linear encoding in [AP0 primaries][RgbPrimaries::AcesAp0]
     /// with a [D60 whitepoint][WhitePoint::D60].
     pub const ACES_2065_1: ColorSpace = ColorSpace::linear(RgbPrimaries::AcesAp0, WhitePoint::D60);
@@ -616,12 +1594,12 @@ pub mod color_spaces {
     /// BT.2020.
     pub const BT_2020: ColorSpace = ColorSpace::linear(RgbPrimaries::Bt2020, WhitePoint::D65);
 
-    /// Encoded BT.2020 is [BT.2020](BT_2020) with the [BT.2020
-    /// OETF][TransformFn::Bt601] applied.
+    /// Encoded BT.2020 is [BT.2020](BT_2020) with the [10-bit BT.2020
+    /// OETF][TransformFn::Bt2020_10Bit] applied.
     pub const ENCODED_BT_2020: ColorSpace = ColorSpace::new(
         RgbPrimaries::Bt2020,
         WhitePoint::D65,
-        Some(TransformFn::Bt601),
+        Some(TransformFn::Bt2020_10Bit),
     );
 
     /// Encoded BT.2100 PQ is [BT.2020](BT_2020) (equivalent to the linear
@@ -630,6 +1608,15 @@ pub mod color_spaces {
     pub const ENCODED_BT_2100_PQ: ColorSpace =
         ColorSpace::new(RgbPrimaries::Bt2020, WhitePoint::D65, Some(TransformFn::Pq));
 
+    /// Encoded BT.2100 HLG is [BT.2020](BT_2020) (equivalent to the linear
+    /// BT.2100 space) with the [Hybrid Log-Gamma OETF][TransformFn::Hlg]
+    /// applied. Linear values are scene light, with 1.0 the nominal peak.
+    pub const ENCODED_BT_2100_HLG: ColorSpace = ColorSpace::new(
+        RgbPrimaries::Bt2020,
+        WhitePoint::D65,
+        Some(TransformFn::Hlg),
+    );
+
     /// Oklab is a non-linear, perceptual encoding in
     /// [XYZ][RgbPrimaries::CieXyz], with a [D65 whitepoint][WhitePoint::D65].
     ///
@@ -659,6 +1646,40 @@ pub mod color_spaces {
         Some(TransformFn::OkLch),
     );
 
+    /// HWB (Hue, Whiteness, Blackness) of [linear sRGB][LINEAR_SRGB], with
+    /// the hue in degrees.
+    ///
+    /// CSS `hwb()` applies HWB to encoded sRGB instead, see
+    /// [`Color::from_css()`] to parse it.
+    pub const HWB: ColorSpace =
+        ColorSpace::new(RgbPrimaries::Bt709, WhitePoint::D65, Some(TransformFn::Hwb));
+
+    /// CAM16-UCS, the uniform color space of the CAM16 color appearance model,
+    /// with a [D65 whitepoint][WhitePoint::D65] and the [default viewing
+    /// conditions][Cam16ViewingConditions::DEFAULT]. Use
+    /// [`ColorSpace::with_cam16_conditions()`] for other viewing conditions.
+    pub const CAM16_UCS: ColorSpace = ColorSpace::new(
+        RgbPrimaries::CieXyz,
+        WhitePoint::D65,
+        Some(TransformFn::Cam16Ucs(Cam16ViewingConditions::DEFAULT)),
+    );
+
+    /// Full-range Y'CbCr of [BT.709][BT_709] with the [BT.709 luma
+    /// coefficients][LumaCoefficients::BT709].
+    pub const YCBCR_BT709: ColorSpace = ColorSpace::new(
+        RgbPrimaries::Bt709,
+        WhitePoint::D65,
+        Some(TransformFn::YCbCr(LumaCoefficients::BT709)),
+    );
+
+    /// Full-range Y'CbCr of [BT.2020][BT_2020] with the [BT.2020 luma
+    /// coefficients][LumaCoefficients::BT2020].
+    pub const YCBCR_BT2020: ColorSpace = ColorSpace::new(
+        RgbPrimaries::Bt2020,
+        WhitePoint::D65,
+        Some(TransformFn::YCbCr(LumaCoefficients::BT2020)),
+    );
+
     /// ICtCp_PQ is a non-linear encoding in [BT.2020
     /// primaries][RgbPrimaries::Bt2020], with a [D65
     /// whitepoint][WhitePoint::D65], using the PQ transfer function
@@ -696,6 +1717,19 @@ pub mod color_spaces {
     /// point][WhitePoint::P3Dci]
     pub const P3_THEATER: ColorSpace = ColorSpace::linear(RgbPrimaries::P3, WhitePoint::P3Dci);
 
+    /// DCI-P3 is another name for [P3-DCI][P3_THEATER].
+    pub const DCI_P3: ColorSpace = P3_THEATER;
+
+    /// Encoded DCI-P3 is [DCI-P3][DCI_P3] with the [pure 2.6 gamma of
+    /// digital cinema][TransformFn::DciP3] applied. Unlike [Display
+    /// P3][ENCODED_DISPLAY_P3], it has the greenish DCI white point, which
+    /// conversions to D65 spaces adapt automatically.
+    pub const ENCODED_DCI_P3: ColorSpace = ColorSpace::new(
+        RgbPrimaries::P3,
+        WhitePoint::P3Dci,
+        Some(TransformFn::DciP3),
+    );
+
     /// Adobe RGB (1998) is a linear encoding in [Adobe 1998
     /// primaries][RgbPrimaries::Adobe1998] with a [D65 white
     /// point][WhitePoint::D65]
@@ -715,16 +1749,76 @@ pub mod color_spaces {
     /// with a [D65 white point][WhitePoint::D65]
     pub const APPLE: ColorSpace = ColorSpace::linear(RgbPrimaries::Apple, WhitePoint::D65);
 
+    /// S-Gamut3 is a linear encoding in [S-Gamut3
+    /// primaries][RgbPrimaries::SGamut3] with a [D65 white
+    /// point][WhitePoint::D65].
+    pub const S_GAMUT3: ColorSpace = ColorSpace::linear(RgbPrimaries::SGamut3, WhitePoint::D65);
+
+    /// Encoded S-Gamut3 is [S-Gamut3](S_GAMUT3) with the [S-Log3
+    /// curve][TransformFn::SLog3] applied, as recorded by Sony cameras.
+    pub const ENCODED_S_GAMUT3: ColorSpace = ColorSpace::new(
+        RgbPrimaries::SGamut3,
+        WhitePoint::D65,
+        Some(TransformFn::SLog3),
+    );
+
+    /// S-Gamut3.Cine is a linear encoding in [S-Gamut3.Cine
+    /// primaries][RgbPrimaries::SGamut3Cine] with a [D65 white
+    /// point][WhitePoint::D65].
+    pub const S_GAMUT3_CINE: ColorSpace =
+        ColorSpace::linear(RgbPrimaries::SGamut3Cine, WhitePoint::D65);
+
+    /// Encoded S-Gamut3.Cine is [S-Gamut3.Cine](S_GAMUT3_CINE) with the
+    /// [S-Log3 curve][TransformFn::SLog3] applied.
+    pub const ENCODED_S_GAMUT3_CINE: ColorSpace = ColorSpace::new(
+        RgbPrimaries::SGamut3Cine,
+        WhitePoint::D65,
+        Some(TransformFn::SLog3),
+    );
+
+    /// Venice S-Gamut3 is a linear encoding in [Venice S-Gamut3
+    /// primaries][RgbPrimaries::VeniceSGamut3] with a [D65 white
+    /// point][WhitePoint::D65].
+    pub const VENICE_S_GAMUT3: ColorSpace =
+        ColorSpace::linear(RgbPrimaries::VeniceSGamut3, WhitePoint::D65);
+
+    /// Encoded Venice S-Gamut3 is [Venice S-Gamut3](VENICE_S_GAMUT3) with the
+    /// [S-Log3 curve][TransformFn::SLog3] applied.
+    pub const ENCODED_VENICE_S_GAMUT3: ColorSpace = ColorSpace::new(
+        RgbPrimaries::VeniceSGamut3,
+        WhitePoint::D65,
+        Some(TransformFn::SLog3),
+    );
+
+    /// Venice S-Gamut3.Cine is a linear encoding in [Venice S-Gamut3.Cine
+    /// primaries][RgbPrimaries::VeniceSGamut3Cine] with a [D65 white
+    /// point][WhitePoint::D65].
+    pub const VENICE_S_GAMUT3_CINE: ColorSpace =
+        ColorSpace::linear(RgbPrimaries::VeniceSGamut3Cine, WhitePoint::D65);
+
+    /// Encoded Venice S-Gamut3.Cine is [Venice
+    /// S-Gamut3.Cine](VENICE_S_GAMUT3_CINE) with the [S-Log3
+    /// curve][TransformFn::SLog3] applied.
+    pub const ENCODED_VENICE_S_GAMUT3_CINE: ColorSpace = ColorSpace::new(
+        RgbPrimaries::VeniceSGamut3Cine,
+        WhitePoint::D65,
+        Some(TransformFn::SLog3),
+    );
+
     /// Array containing all built-in color spaces.
-    pub const ALL_COLOR_SPACES: [ColorSpace; 22] = [
+    pub const ALL_COLOR_SPACES: [ColorSpace; 35] = [
         color_spaces::LINEAR_SRGB,
         color_spaces::ENCODED_SRGB,
+        color_spaces::SRGB_GAMMA_2_2,
         color_spaces::BT_709,
         color_spaces::ENCODED_BT_709,
         color_spaces::BT_2020,
         color_spaces::ENCODED_BT_2020,
         color_spaces::ENCODED_BT_2100_PQ,
+        color_spaces::ENCODED_BT_2100_HLG,
         color_spaces::ACES_CG,
+        color_spaces::ACES_CC,
+        color_spaces::ACES_CCT,
         color_spaces::ACES_2065_1,
         color_spaces::CIE_RGB,
         color_spaces::CIE_XYZ,
@@ -735,10 +1829,19 @@ pub mod color_spaces {
         color_spaces::APPLE,
         color_spaces::P3_D60,
         color_spaces::P3_THEATER,
+        color_spaces::ENCODED_DCI_P3,
         color_spaces::DISPLAY_P3,
         color_spaces::ENCODED_DISPLAY_P3,
         color_spaces::ADOBE_1998,
         color_spaces::ADOBE_WIDE,
+        color_spaces::S_GAMUT3,
+        color_spaces::ENCODED_S_GAMUT3,
+        color_spaces::S_GAMUT3_CINE,
+        color_spaces::ENCODED_S_GAMUT3_CINE,
+        color_spaces::VENICE_S_GAMUT3,
+        color_spaces::ENCODED_VENICE_S_GAMUT3,
+        color_spaces::VENICE_S_GAMUT3_CINE,
+        color_spaces::ENCODED_VENICE_S_GAMUT3_CINE,
     ];
 }
 
@@ -746,9 +1849,30 @@ pub mod color_spaces {
 #[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Color {
+    /// Serialized as an array of 3 components, with or without `glam`.
+    #[cfg_attr(feature = "serde", serde(with = "vec3_array"))]
     pub value: Vec3,
     pub space: ColorSpace,
 }
+
+#[cfg(feature = "serde")]
+mod vec3_array {
+    use super::*;
+
+    pub fn serialize<S: serde::Serializer>(value: &Vec3, serializer: S) -> Result<S::Ok, S::Error> {
+        <[Float; 3]>::from(*value).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec3, D::Error> {
+        <[Float; 3]>::deserialize(deserializer).map(Vec3::from)
+    }
+}
+/// Maximum difference per component of [`Color::to_oklab_fast()`] from the
+/// exact Oklab conversion, for colors in the sRGB gamut.
+pub const OKLAB_FAST_MAX_ERROR: Float = 1e-4;
+
 impl Color {
     pub const fn new(x: Float, y: Float, z: Float, space: ColorSpace) -> Self {
         #[cfg(all(feature = "glam", feature = "f64"))]
@@ -792,6 +1916,7 @@ impl Color {
     }
 
     /// Returns a `Color` converted into the provided [`ColorSpace`].
+    #[must_use]
     pub fn to(&self, space: ColorSpace) -> Color {
         let conversion = ColorConversion::new(self.space, space);
         let new_color = conversion.convert(self.value);
@@ -801,17 +1926,105 @@ impl Color {
         }
     }
 
+    /// Returns the CIE XYZ tristimulus values of the color, relative to the
+    /// white point of its own space, i.e. without chromatic adaptation.
+    ///
+    /// The white of an RGB space has the XYZ of its white point, with Y=1.
+    pub fn xyz(&self) -> Vec3 {
+        self.to(ColorSpace::linear(
+            RgbPrimaries::CieXyz,
+            self.space.white_point,
+        ))
+        .value
+    }
+
+    /// Returns the relative luminance of the color, i.e. its CIE Y relative to
+    /// the white point of its own space, where the white of an RGB space has a
+    /// luminance of 1.
+    ///
+    /// The color is decoded into the linear space of its primaries and white
+    /// point, whose luminance weights are derived from those primaries instead
+    /// of using the BT.709 coefficients. Colors in non-RGB spaces, e.g. CIE
+    /// LAB with an XYZ reference, return the Y of their reference directly.
+    pub fn luminance(&self) -> Float {
+        let linear = self.to(ColorSpace::linear(
+            self.space.primaries,
+            self.space.white_point,
+        ));
+        luminance_row(linear.space).dot(linear.value)
+    }
+
+    /// Same as [`luminance()`](Self::luminance).
+    pub fn relative_luminance(&self) -> Float {
+        self.luminance()
+    }
+
+    /// Returns the color with its relative luminance set to `luminance`,
+    /// keeping its CIE xy chromaticity.
+    ///
+    /// Black has no chromaticity, so setting the luminance of black gives a
+    /// color with the chromaticity of the white point of its space. The
+    /// returned color is in the same space as `self`.
+    pub fn with_luminance(&self, luminance: Float) -> Color {
+        let white_point = self.space.white_point;
+        let xyz = self.xyz();
+        let sum = xyz.x + xyz.y + xyz.z;
+        let xyy = if xyz.y > 0.0 && sum != 0.0 {
+            Vec3::new(xyz.x / sum, xyz.y / sum, luminance)
+        } else {
+            let [x, y] = white_point.xy();
+            Vec3::new(x, y, luminance)
+        };
+        let xyz = Vec3::new(
+            xyy.x * xyy.z / xyy.y,
+            xyy.z,
+            (1.0 - xyy.x - xyy.y) * xyy.z / xyy.y,
+        );
+        Color::from_xyz(xyz, white_point).to(self.space)
+    }
+
+    /// Creates a color from CIE XYZ tristimulus values relative to
+    /// `white_point`.
+    ///
+    /// The returned color is in CIE XYZ with the given white point and can be
+    /// converted into other spaces with [`to()`](Self::to).
+    pub const fn from_xyz(xyz: Vec3, white_point: WhitePoint) -> Color {
+        Color {
+            value: xyz,
+            space: ColorSpace::linear(RgbPrimaries::CieXyz, white_point),
+        }
+    }
+
+    /// Converts the color to [`OK_LAB`](color_spaces::OK_LAB) with an
+    /// approximation of the cube root, for real-time use where the exact
+    /// conversion of [`to()`](Self::to) is too slow.
+    ///
+    /// This is approximate: for colors in the sRGB gamut, each Oklab
+    /// component differs from the exact one by at most
+    /// [`OKLAB_FAST_MAX_ERROR`].
+    pub fn to_oklab_fast(&self) -> Color {
+        let xyz = self.to(color_spaces::CIE_XYZ).value;
+        Color {
+            value: super::transform::xyz_to_ok_lab_fast(xyz, WhitePoint::D65),
+            space: color_spaces::OK_LAB,
+        }
+    }
+
     pub fn to_linear(&self) -> Color {
         if self.space.is_linear() {
             *self
         } else {
-            let transform = ColorTransform::new(self.space.transform_function(), None)
-                .unwrap_or_else(|| {
-                    panic!(
-                        "expected transform for {:?}",
-                        self.space.transform_function()
-                    )
-                });
+            let transform = ColorTransform::with_white_point(
+                self.space.transform_function(),
+                None,
+                self.space.white_point,
+            )
+            .unwrap_or_else(|| {
+                panic!(
+                    "expected transform for {:?}",
+                    self.space.transform_function()
+                )
+            });
             let new_color_value = transform.apply(self.value, self.space().white_point);
             Self {
                 value: new_color_value,
@@ -819,6 +2032,347 @@ impl Color {
             }
         }
     }
+
+    /// Returns `true` if no component of the color is NaN or infinite.
+    ///
+    /// Conversions don't check their input, so non-finite components, e.g.
+    /// from dividing by zero, propagate silently into the converted value.
+    pub fn is_finite(&self) -> bool {
+        self.value.x.is_finite() && self.value.y.is_finite() && self.value.z.is_finite()
+    }
+
+    /// Returns a copy of the color with every NaN or infinite component
+    /// replaced by the corresponding component of `fallback`.
+    pub fn sanitize(&self, fallback: Vec3) -> Color {
+        let pick = |value: Float, fallback: Float| {
+            if value.is_finite() { value } else { fallback }
+        };
+        Color {
+            value: Vec3::new(
+                pick(self.value.x, fallback.x),
+                pick(self.value.y, fallback.y),
+                pick(self.value.z, fallback.z),
+            ),
+            space: self.space,
+        }
+    }
+
+    /// Rounds each component of the color, in its current space, to the
+    /// nearest multiple of `step`, e.g. to snap HSL values of a color picker
+    /// to a grid.
+    ///
+    /// If `step` is not positive and finite, the color is returned unchanged.
+    pub fn round_to_step(&self, step: Float) -> Color {
+        if !(step > 0.0 && step.is_finite()) {
+            return *self;
+        }
+        let round = |value: Float| (value / step).round() * step;
+        Color {
+            value: Vec3::new(
+                round(self.value.x),
+                round(self.value.y),
+                round(self.value.z),
+            ),
+            space: self.space,
+        }
+    }
+}
+
+/// How [`Color::mix_many()`] averages hues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HueInterpolation {
+    /// Weights each hue by its chroma (or saturation), and the chroma of the
+    /// blend is the length of the averaged hue vector. This equals blending
+    /// in the corresponding rectangular space, so colors of opposing hues
+    /// blend to a gray.
+    #[default]
+    ChromaWeighted,
+    /// Averages the hues as unit vectors, ignoring chroma, which is averaged
+    /// separately. Blends keep the average saturation of the colors.
+    Circular,
+}
+
+/// Returns the index of the hue component in colors of `space`, the index of
+/// its chroma component if it has one and the value of a full turn of the
+/// hue, or `None` if `space` is not cylindrical.
+fn hue_components(space: ColorSpace) -> Option<(usize, Option<usize>, Float)> {
+    match space.transform_function()? {
+        TransformFn::OkLch => Some((2, Some(1), TAU)),
+        TransformFn::CieLch => Some((2, Some(1), 360.0)),
+        TransformFn::Hsl | TransformFn::Hsv | TransformFn::Hsi => Some((0, Some(1), 360.0)),
+        TransformFn::Hwb => Some((0, None, 360.0)),
+        _ => None,
+    }
+}
+
+impl Color {
+    /// Applies `op` to the linear light values of `self` and `rhs`, which is
+    /// converted into the space of `self` first, and returns the result
+    /// encoded like `self`.
+    fn linear_op(self, rhs: Color, op: impl Fn(Vec3, Vec3) -> Vec3) -> Color {
+        let linear_space = self.space.as_linear();
+        let value = op(self.to(linear_space).value, rhs.to(linear_space).value);
+        Color {
+            value,
+            space: linear_space,
+        }
+        .to(self.space)
+    }
+
+    /// Adds the stored components of `other` to the stored components of
+    /// `self`, without linearizing them first.
+    ///
+    /// This is naive component math, e.g. for animating a color in HSL. For
+    /// physically correct addition of light use the `+` operator instead.
+    ///
+    /// Returns [`ColorError::MismatchedColorSpaces`] if `other` is not in the
+    /// space of `self`.
+    pub fn add_raw(&self, other: Color) -> Result<Color, ColorError> {
+        self.raw_op(other, |a, b| a + b)
+    }
+
+    /// Subtracts the stored components of `other` from the stored components
+    /// of `self`, like [`add_raw()`](Self::add_raw).
+    pub fn sub_raw(&self, other: Color) -> Result<Color, ColorError> {
+        self.raw_op(other, |a, b| a - b)
+    }
+
+    /// Multiplies the stored components with `factor`, without linearizing
+    /// them first. For scaling the amount of light use the `*` operator
+    /// instead.
+    pub fn scale_raw(&self, factor: Float) -> Color {
+        Color {
+            value: self.value * factor,
+            space: self.space,
+        }
+    }
+
+    /// Blends the weighted `stops` in `space`, returning the result in the
+    /// space of the first stop.
+    ///
+    /// Components are averaged by weight. In cylindrical spaces, i.e. Oklch,
+    /// CIE Lch, HSL, HSV, HSI and HWB, the hue is averaged on the circle
+    /// instead, as set by `hue_interpolation`, so that e.g. hues of 350° and
+    /// 10° average to 0° and not 180°. Weights may be negative to
+    /// extrapolate.
+    ///
+    /// Returns [`ColorError::ZeroTotalWeight`] if the weights sum to zero, which
+    /// includes empty `stops`.
+    pub fn mix_many(
+        stops: &[(Color, Float)],
+        space: ColorSpace,
+        hue_interpolation: HueInterpolation,
+    ) -> Result<Color, ColorError> {
+        let total: Float = stops.iter().map(|(_, weight)| weight).sum();
+        if total == 0.0 {
+            return Err(ColorError::ZeroTotalWeight);
+        }
+        let mut value = Vec3::splat(0.0);
+        for (color, weight) in stops {
+            value += color.to(space).value * (weight / total);
+        }
+        if let Some((hue, chroma, turn)) = hue_components(space) {
+            let (mut sin, mut cos): (Float, Float) = (0.0, 0.0);
+            for (color, weight) in stops {
+                let value = <[Float; 3]>::from(color.to(space).value);
+                let angle = value[hue] / turn * TAU;
+                let length = match (hue_interpolation, chroma) {
+                    (HueInterpolation::ChromaWeighted, Some(chroma)) => value[chroma],
+                    _ => 1.0,
+                };
+                sin += weight / total * length * angle.sin();
+                cos += weight / total * length * angle.cos();
+            }
+            let mut components = <[Float; 3]>::from(value);
+            let mut angle = sin.atan2(cos) / TAU * turn;
+            if turn == 360.0 && angle < 0.0 {
+                angle += 360.0;
+            }
+            components[hue] = angle;
+            if let (HueInterpolation::ChromaWeighted, Some(chroma)) = (hue_interpolation, chroma) {
+                // Hues pointing in different directions cancel out, like
+                // mixing in a rectangular space such as Oklab.
+                components[chroma] = (sin * sin + cos * cos).sqrt();
+            }
+            value = Vec3::from_slice(&components);
+        }
+        let space_of_first = stops[0].0.space;
+        Ok(Color { value, space }.to(space_of_first))
+    }
+
+    /// Interpolates linearly between `self` at `t` = 0 and `other` at `t` = 1
+    /// in `space`, without clamping `t`, so values outside of [0, 1]
+    /// extrapolate beyond the colors.
+    ///
+    /// In cylindrical spaces, the hue is interpolated along the shorter arc
+    /// like in [`mix()`](Self::mix). Extrapolated colors can easily be
+    /// out of gamut or non-physical, e.g. have negative linear light
+    /// components, and are not mapped back. The returned color is in the space
+    /// of `self`.
+    pub fn lerp_unclamped(&self, other: Color, t: Float, space: ColorSpace) -> Color {
+        let a = self.to(space).value;
+        let b = other.to(space).value;
+        let mut value = a + (b - a) * t;
+        if let Some((hue, _, turn)) = hue_components(space) {
+            let (a, b) = (<[Float; 3]>::from(a)[hue], <[Float; 3]>::from(b)[hue]);
+            let delta = b - a;
+            let delta = delta - turn * (delta / turn).round();
+            let angle = a + delta * t;
+            // Hues given in degrees are in [0, 360), in radians in [-π, π).
+            let start = if turn == 360.0 { 0.0 } else { -0.5 * turn };
+            let mut components = <[Float; 3]>::from(value);
+            components[hue] = angle - turn * ((angle - start) / turn).floor();
+            value = Vec3::from_slice(&components);
+        }
+        Color { value, space }.to(self.space)
+    }
+
+    /// Mixes `self` at `t` = 0 with `other` at `t` = 1 in `space`, e.g. in
+    /// Oklab for perceptually even gradients or in [`as_linear()`] light for
+    /// an optical blend. `t` is clamped to [0, 1], see
+    /// [`lerp_unclamped()`](Self::lerp_unclamped) to extrapolate.
+    ///
+    /// In cylindrical spaces, i.e. Oklch, CIE Lch, HSL, HSV, HSI and HWB, the
+    /// hue is interpolated along the shorter arc, so e.g. hues of 350° and 10°
+    /// mix through 0° and not 180°.
+    ///
+    /// With [`WhitePointPolicy::Adapt`], both colors are converted to `space`
+    /// with [`to()`](Self::to), which adapts them to its white point. With
+    /// [`WhitePointPolicy::Strict`], colors with different white points are an
+    /// error. The returned color is in the space of `self`.
+    ///
+    /// [`as_linear()`]: ColorSpace::as_linear
+    pub fn mix(
+        &self,
+        other: &Color,
+        t: Float,
+        space: ColorSpace,
+        policy: WhitePointPolicy,
+    ) -> Result<Color, ColorError> {
+        policy.check(self, other)?;
+        Ok(self.lerp_unclamped(*other, t.clamp(0.0, 1.0), space))
+    }
+
+    /// Converts the color into `space` like [`to()`](Self::to), returning the
+    /// intermediate value after each step of the conversion, labeled by step.
+    ///
+    /// Steps which the conversion skips, e.g. decoding a linear color, are not
+    /// reported.
+    #[cfg(feature = "std")]
+    pub fn trace_to(&self, space: ColorSpace) -> Vec<(String, Vec3)> {
+        let conversion = ColorConversion::new(self.space, space);
+        let mut steps = Vec::with_capacity(3);
+        let mut value = self.value;
+        if let Some(transform_fn) = conversion.src_transform_fn() {
+            value = conversion.apply_src_transform(value);
+            steps.push((format!("decode {transform_fn:?}"), value));
+        }
+        if let Some(linear) = conversion.linear_transform() {
+            value = linear.convert(value);
+            steps.push((
+                format!(
+                    "matrix {:?}/{:?} -> {:?}/{:?}",
+                    linear.input_space().primaries(),
+                    linear.input_space().white_point(),
+                    linear.output_space().primaries(),
+                    linear.output_space().white_point()
+                ),
+                value,
+            ));
+        }
+        if let Some(transform_fn) = conversion.dst_transform_fn() {
+            value = conversion.apply_dst_transform(value);
+            steps.push((format!("encode {transform_fn:?}"), value));
+        }
+        steps
+    }
+
+    fn raw_op(&self, other: Color, op: impl Fn(Vec3, Vec3) -> Vec3) -> Result<Color, ColorError> {
+        if self.space != other.space {
+            return Err(ColorError::MismatchedColorSpaces);
+        }
+        Ok(Color {
+            value: op(self.value, other.value),
+            space: self.space,
+        })
+    }
+}
+
+/// Adds two colors in linear light.
+///
+/// Both colors are converted into the linear version of the space of the left
+/// hand side, added and converted back into the space of the left hand side.
+/// Adding two sRGB colors thus adds the amounts of light, not the encoded
+/// component values. Use [`Color::add_raw()`] for naive component math.
+impl core::ops::Add for Color {
+    type Output = Color;
+
+    fn add(self, rhs: Color) -> Color {
+        self.linear_op(rhs, |a, b| a + b)
+    }
+}
+
+/// Subtracts two colors in linear light, like [`Add`](core::ops::Add).
+impl core::ops::Sub for Color {
+    type Output = Color;
+
+    fn sub(self, rhs: Color) -> Color {
+        self.linear_op(rhs, |a, b| a - b)
+    }
+}
+
+/// Scales the amount of light of a color, i.e. the color is linearized,
+/// scaled and re-encoded.
+impl core::ops::Mul<Float> for Color {
+    type Output = Color;
+
+    fn mul(self, rhs: Float) -> Color {
+        self.linear_op(self, |a, _| a * rhs)
+    }
+}
+
+/// Divides the amount of light of a color, like [`Mul`](core::ops::Mul).
+impl core::ops::Div<Float> for Color {
+    type Output = Color;
+
+    fn div(self, rhs: Float) -> Color {
+        self.linear_op(self, |a, _| a / rhs)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<Color> for glam::Vec3 {
+    /// Returns the components of the color, without its space.
+    fn from(color: Color) -> Self {
+        #[cfg(not(feature = "f64"))]
+        return color.value;
+        #[cfg(feature = "f64")]
+        return color.value.as_vec3();
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<Color> for glam::Vec3A {
+    /// Returns the components of the color, without its space.
+    fn from(color: Color) -> Self {
+        glam::Vec3::from(color).into()
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<(glam::Vec3, ColorSpace)> for Color {
+    fn from((value, space): (glam::Vec3, ColorSpace)) -> Self {
+        #[cfg(feature = "f64")]
+        let value = value.as_dvec3();
+        Color { value, space }
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<(glam::Vec3A, ColorSpace)> for Color {
+    fn from((value, space): (glam::Vec3A, ColorSpace)) -> Self {
+        Color::from((glam::Vec3::from(value), space))
+    }
 }
 
 #[cfg(test)]
@@ -848,7 +2402,7 @@ mod test {
         let expected = Vec3::new(0.6262097, 0.34919018, 0.9063317);
         assert!(
             result.abs_diff_eq(expected, 0.001),
-            "{result} != {expected}"
+            "{result:?} != {expected:?}"
         );
     }
 
@@ -865,10 +2419,759 @@ mod test {
     // }
 
     #[test]
+    fn adding_black_is_identity() {
+        let color = Color::srgb(0.2, 0.5, 0.8);
+        let sum = color + Color::srgb(0.0, 0.0, 0.0);
+        assert_eq!(sum.space, spaces::ENCODED_SRGB);
+        assert!(
+            sum.value.abs_diff_eq(color.value, 1e-6),
+            "{sum:?} != {color:?}"
+        );
+    }
+
+    #[test]
+    fn arithmetic_happens_in_linear_light() {
+        let gray = Color::srgb(0.5, 0.5, 0.5);
+        let linear = gray.to_linear().value;
+
+        let sum = (gray + gray).to_linear().value;
+        assert!(sum.abs_diff_eq(linear * 2.0, 1e-5), "{sum:?}");
+        assert!((gray + gray).value.x < 1.0);
+
+        let scaled = (gray * 2.0).to_linear().value;
+        assert!(scaled.abs_diff_eq(linear * 2.0, 1e-5), "{scaled:?}");
+
+        let halved = (gray / 2.0).to_linear().value;
+        assert!(halved.abs_diff_eq(linear / 2.0, 1e-5), "{halved:?}");
+
+        let difference = (gray - gray.to(spaces::ACES_CG)).to_linear().value;
+        assert!(
+            difference.abs_diff_eq(Vec3::splat(0.0), 1e-5),
+            "{difference:?}"
+        );
+    }
+
+    #[test]
+    fn strict_mixing_rejects_different_white_points() {
+        let d50 = Color::new(0.4, 0.3, 0.2, spaces::PRO_PHOTO);
+        let d65 = Color::srgb(0.5, 0.5, 0.5);
+        assert_eq!(
+            d50.mix(
+                &d65,
+                0.5,
+                spaces::PRO_PHOTO.as_linear(),
+                WhitePointPolicy::Strict
+            )
+            .unwrap_err(),
+            ColorError::MismatchedWhitePoints(WhitePoint::D50, WhitePoint::D65)
+        );
+
+        let mixed = d50
+            .mix(
+                &d65,
+                0.5,
+                spaces::PRO_PHOTO.as_linear(),
+                WhitePointPolicy::Adapt,
+            )
+            .unwrap();
+        assert_eq!(mixed.space, spaces::PRO_PHOTO);
+        let expected = (d50.value + d65.to(spaces::PRO_PHOTO).value) * 0.5;
+        assert!(mixed.value.abs_diff_eq(expected, 1e-6), "{mixed:?}");
+
+        let other = Color::srgb(1.0, 0.0, 0.0);
+        let strict = d65
+            .mix(&other, 2.0, spaces::LINEAR_SRGB, WhitePointPolicy::Strict)
+            .unwrap();
+        assert!(strict.value.abs_diff_eq(other.value, 1e-6), "{strict:?}");
+    }
+
+    #[test]
+    fn raw_arithmetic_uses_stored_components() {
+        let a = Color::srgb(0.25, 0.5, 0.125);
+        let b = Color::srgb(0.25, 0.125, 0.5);
+        assert_eq!(a.add_raw(b).unwrap().value, Vec3::new(0.5, 0.625, 0.625));
+        assert_eq!(a.sub_raw(b).unwrap().value, Vec3::new(0.0, 0.375, -0.375));
+        let scaled = a.scale_raw(2.0);
+        assert_eq!(scaled.value, Vec3::new(0.5, 1.0, 0.25));
+        assert_eq!(scaled.space, spaces::ENCODED_SRGB);
+        assert!(matches!(
+            a.add_raw(b.to(spaces::LINEAR_SRGB)),
+            Err(ColorError::MismatchedColorSpaces)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn trace_srgb_to_oklab() {
+        let color = Color::srgb(0.35, 0.75, 0.8);
+        let steps = color.trace_to(spaces::OK_LAB);
+        let labels: Vec<_> = steps.iter().map(|(label, _)| label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "decode Srgb",
+                "matrix Bt709/D65 -> CieXyz/D65",
+                "encode OkLab"
+            ]
+        );
+        assert!(steps[0].1.abs_diff_eq(color.to_linear().value, 1e-6));
+        let oklab = color.to(spaces::OK_LAB).value;
+        assert!(steps[2].1.abs_diff_eq(oklab, 1e-6), "{steps:?}");
+
+        assert_eq!(
+            Color::srgb(0.5, 0.5, 0.5)
+                .trace_to(spaces::ENCODED_SRGB)
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn srgb_white_xyz_round_trip() {
+        let white = Color::srgb(1.0, 1.0, 1.0);
+        let xyz = white.xyz();
+        assert!(xyz.abs_diff_eq(WhitePoint::D65.xyz(), 1e-5), "{xyz:?}");
+
+        let color = Color::srgb(0.35, 0.75, 0.8);
+        let round_trip = Color::from_xyz(color.xyz(), WhitePoint::D65).to(spaces::ENCODED_SRGB);
+        assert!(
+            round_trip.value.abs_diff_eq(color.value, 1e-5),
+            "{round_trip:?} != {color:?}"
+        );
+
+        let aces_white = Color::new(1.0, 1.0, 1.0, spaces::ACES_CG).xyz();
+        assert!(
+            aces_white.abs_diff_eq(WhitePoint::D60.xyz(), 1e-5),
+            "{aces_white:?}"
+        );
+    }
+
+    #[test]
+    fn color_space_try_from_name() {
+        let space: Result<ColorSpace, _> = "oklab".try_into();
+        assert_eq!(space, Ok(spaces::OK_LAB));
+        let space: Result<ColorSpace, _> = "no such space".try_into();
+        assert!(matches!(space, Err(ColorError::UnknownColorSpaceName)));
+    }
+
+    #[test]
+    fn aces_cc_and_aces_cct_by_name() {
+        let cc = ColorSpace::by_name("ACEScc").unwrap();
+        let cct = ColorSpace::by_name("acescct").unwrap();
+        assert_eq!(cc, spaces::ACES_CC);
+        assert_eq!(cct, spaces::ACES_CCT);
+        assert_ne!(cc, cct);
+        assert_eq!(cc.as_linear(), cct.as_linear());
+
+        let descriptor = spaces::ACES_CG.with_transform(TransformFn::by_name("ACES_cct").unwrap());
+        assert_eq!(descriptor, spaces::ACES_CCT);
+        assert_eq!(TransformFn::by_name("ACES.cc"), Some(TransformFn::AcesCc));
+        assert_eq!(ColorSpace::by_name("aces cgt"), None);
+    }
+
+    #[test]
+    fn aces_cc_and_aces_cct_round_trip() {
+        // 18% grey encodes to the same value in both.
+        let grey = Color::new(0.18, 0.18, 0.18, spaces::ACES_CG);
+        for space in [spaces::ACES_CC, spaces::ACES_CCT] {
+            let encoded = grey.to(space);
+            assert!((encoded.value.x - 0.4135884).abs() < 1e-5, "{encoded:?}");
+            let round_trip = encoded.to(spaces::ACES_CG);
+            assert!(round_trip.value.abs_diff_eq(grey.value, 1e-5));
+        }
+        // The toe of ACEScct is linear, ACEScc stays logarithmic.
+        let dark = Color::new(0.001, 0.0, 0.5, spaces::ACES_CG);
+        let cct = dark.to(spaces::ACES_CCT);
+        assert!((cct.value.y - 0.0729055).abs() < 1e-6, "{cct:?}");
+        assert!(cct.to(spaces::ACES_CG).value.abs_diff_eq(dark.value, 1e-5));
+        let cc = dark.to(spaces::ACES_CC);
+        assert!((cc.value.y - -0.3584475).abs() < 1e-5, "{cc:?}");
+    }
+
+    #[test]
+    fn with_transform_builds_variants() {
+        assert_eq!(
+            spaces::LINEAR_SRGB.with_transform(TransformFn::Srgb),
+            spaces::ENCODED_SRGB
+        );
+        assert_eq!(
+            spaces::BT_2020.with_transform(TransformFn::Pq),
+            spaces::ENCODED_BT_2100_PQ
+        );
+        assert_eq!(
+            spaces::ENCODED_SRGB
+                .with_transform(TransformFn::OkLab)
+                .primaries(),
+            RgbPrimaries::Bt709
+        );
+    }
+
+    #[test]
+    fn white_point_xy_and_xyz() {
+        let [x, y] = WhitePoint::D65.xy();
+        assert!((x - 0.3127).abs() < 1e-4, "{x}");
+        assert!((y - 0.3290).abs() < 1e-4, "{y}");
+
+        let xyz = WhitePoint::D65.xyz();
+        assert!((xyz.y - 1.0).abs() < 1e-6);
+        assert!(xyz.abs_diff_eq(Vec3::new(0.95047, 1.0, 1.08883), 1e-6));
+
+        let custom = WhitePoint::Custom([0.9, 1.0, 1.1]);
+        let [x, y] = custom.xy();
+        assert!((x - 0.3).abs() < 1e-6 && (y - 1.0 / 3.0).abs() < 1e-6);
+        assert_eq!(custom.xyz(), Vec3::new(0.9, 1.0, 1.1));
+    }
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
     fn aces_cg_to_srgb() {
         let conversion = ColorConversion::new(spaces::ACES_CG, spaces::ENCODED_SRGB);
         let result = conversion.convert(Vec3::new(0.35, 0.1, 0.8));
         let expected = Vec3::new(0.713855624199, 0.271821975708, 0.955197274685);
         assert!(result.abs_diff_eq(expected, 0.01), "{result} != {expected}");
     }
+
+    #[test]
+    fn non_finite_colors_are_sanitized() {
+        assert!(Color::srgb(0.2, 0.4, 0.6).is_finite());
+        let broken = Color::srgb(Float::NAN, 0.4, Float::INFINITY);
+        assert!(!broken.is_finite());
+        assert!(!broken.to(spaces::OK_LAB).is_finite());
+
+        let sanitized = broken.sanitize(Vec3::new(0.0, 1.0, 1.0));
+        assert!(sanitized.is_finite());
+        assert_eq!(sanitized.value, Vec3::new(0.0, 0.4, 1.0));
+        assert_eq!(sanitized.space, spaces::ENCODED_SRGB);
+    }
+
+    #[test]
+    fn transform_fn_kinds_keep_discriminants() {
+        assert_eq!(TransformFn::Srgb.kind() as u8, 0);
+        assert_eq!(TransformFn::IctCpHlg.kind() as u8, 14);
+        assert_eq!(TransformFn::Pq.kind() as u8, 16);
+        assert_eq!(TransformFn::gamma(2.2).kind(), TransformFnKind::Gamma);
+        assert_eq!(
+            TransformFn::YCbCr(LumaCoefficients::BT709).kind(),
+            TransformFnKind::YCbCr
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn transform_fn_enum_count_is_the_last_variant() {
+        fn identity(value: Vec3, _: WhitePoint) -> Vec3 {
+            value
+        }
+        let registered =
+            crate::details::registry::register_transform("enum count", identity, identity);
+        assert_eq!(TransformFn::ENUM_COUNT, TransformFnKind::Registered);
+        assert_eq!(registered.kind(), TransformFn::ENUM_COUNT);
+        assert_eq!(
+            TransformFn::ENUM_COUNT as u8,
+            TransformFnKind::Gamma as u8 + 1
+        );
+    }
+
+    #[test]
+    fn luminance_uses_own_primaries() {
+        let white = Color::new(1.0, 1.0, 1.0, spaces::ENCODED_DISPLAY_P3);
+        assert!((white.luminance() - 1.0).abs() < 1e-5);
+
+        // The green of BT.2020 and Display P3 is brighter than the BT.709
+        // luminance coefficient of green.
+        let green = |space: ColorSpace| Color::new(0.0, 1.0, 0.0, space).luminance();
+        assert!((green(spaces::LINEAR_SRGB) - 0.7152).abs() < 1e-3);
+        assert!((green(spaces::BT_2020) - 0.678).abs() < 1e-3);
+        assert!((green(spaces::DISPLAY_P3) - 0.6917).abs() < 1e-3);
+
+        for color in [
+            Color::srgb(0.8, 0.4, 0.2),
+            Color::srgb(0.8, 0.4, 0.2).to(spaces::CIE_XYZ.to_cie_lab()),
+            Color::srgb(0.8, 0.4, 0.2).to(spaces::OK_LAB),
+        ] {
+            let (luminance, y) = (color.luminance(), color.xyz().y);
+            assert!((luminance - y).abs() < 1e-5, "{luminance:?} != {y:?}");
+        }
+    }
+
+    #[test]
+    fn with_luminance_keeps_chromaticity() {
+        let xy = |color: Color| {
+            let xyz = color.xyz();
+            let sum = xyz.x + xyz.y + xyz.z;
+            (xyz.x / sum, xyz.y / sum)
+        };
+        let color = Color::srgb(0.8, 0.4, 0.2);
+        let relit = color.with_luminance(0.05);
+        assert_eq!(relit.space, spaces::ENCODED_SRGB);
+        assert!((relit.relative_luminance() - 0.05).abs() < 1e-5);
+        let ((x, y), (relit_x, relit_y)) = (xy(color), xy(relit));
+        assert!((x - relit_x).abs() < 1e-5 && (y - relit_y).abs() < 1e-5);
+
+        let black = Color::srgb(0.0, 0.0, 0.0);
+        assert!(
+            black
+                .with_luminance(0.0)
+                .value
+                .abs_diff_eq(black.value, 1e-6)
+        );
+        let gray = black.with_luminance(1.0).value;
+        assert!(gray.abs_diff_eq(Vec3::splat(1.0), 1e-4), "{gray:?}");
+    }
+
+    #[test]
+    fn round_to_step_snaps_components() {
+        let color = Color::new(0.234, 0.51, 0.96, spaces::ENCODED_SRGB);
+        let snapped = color.round_to_step(0.1);
+        assert_eq!(snapped.space, color.space);
+        for component in [snapped.value.x, snapped.value.y, snapped.value.z] {
+            let steps = component / 0.1;
+            assert!((steps - steps.round()).abs() < 1e-4, "{component}");
+        }
+        assert!(
+            snapped.value.abs_diff_eq(Vec3::new(0.2, 0.5, 1.0), 1e-6),
+            "{snapped:?}"
+        );
+        for step in [0.0, -0.1, Float::NAN] {
+            assert_eq!(color.round_to_step(step).value, color.value);
+        }
+    }
+
+    #[test]
+    fn lerp_unclamped_extrapolates() {
+        let a = Color::new(0.2, 0.4, 0.1, spaces::LINEAR_SRGB);
+        let b = Color::new(0.3, 0.2, 0.5, spaces::LINEAR_SRGB);
+        let extrapolated = a.lerp_unclamped(b, 2.0, spaces::LINEAR_SRGB).value;
+        assert!(
+            extrapolated.abs_diff_eq(Vec3::new(0.4, 0.0, 0.9), 1e-6),
+            "{extrapolated:?}"
+        );
+        let midpoint = a.lerp_unclamped(b, 0.5, spaces::LINEAR_SRGB).value;
+        assert!(midpoint.abs_diff_eq(Vec3::new(0.25, 0.3, 0.3), 1e-6));
+
+        let encoded = Color::srgb(0.5, 0.5, 0.5).lerp_unclamped(b, -1.0, spaces::LINEAR_SRGB);
+        assert_eq!(encoded.space, spaces::ENCODED_SRGB);
+
+        let hsl = ColorSpace::new(RgbPrimaries::Bt709, WhitePoint::D65, Some(TransformFn::Hsl));
+        let magenta_red = Color::new(350.0, 1.0, 0.5, hsl);
+        let orange_red = Color::new(10.0, 1.0, 0.5, hsl);
+        let hue = magenta_red.lerp_unclamped(orange_red, 2.0, hsl).value.x;
+        assert!((hue - 30.0).abs() < 1e-3, "{hue}");
+    }
+
+    #[test]
+    fn mix_takes_the_shorter_hue_arc() {
+        let a = Color::srgb(1.0, 0.0, 0.0);
+        let b = Color::srgb(0.0, 0.0, 1.0);
+        let expected = (a.to(spaces::OK_LAB).value + b.to(spaces::OK_LAB).value) * 0.5;
+        let mixed = a
+            .mix(&b, 0.5, spaces::OK_LAB, WhitePointPolicy::Adapt)
+            .unwrap();
+        assert_eq!(mixed.space, spaces::ENCODED_SRGB);
+        let mixed = mixed.to(spaces::OK_LAB).value;
+        assert!(
+            mixed.abs_diff_eq(expected, 1e-4),
+            "{mixed:?} != {expected:?}"
+        );
+        let clamped = a
+            .mix(&b, -1.0, spaces::OK_LAB, WhitePointPolicy::Adapt)
+            .unwrap()
+            .value;
+        assert!(clamped.abs_diff_eq(a.value, 1e-5), "{clamped:?}");
+
+        let hsl = ColorSpace::new(RgbPrimaries::Bt709, WhitePoint::D65, Some(TransformFn::Hsl));
+        let magenta_red = Color::new(350.0, 1.0, 0.5, hsl);
+        let orange_red = Color::new(20.0, 1.0, 0.5, hsl);
+        let hue = |t| {
+            magenta_red
+                .mix(&orange_red, t, hsl, WhitePointPolicy::Adapt)
+                .unwrap()
+                .value
+                .x
+        };
+        assert!((hue(0.5) - 5.0).abs() < 1e-3, "{}", hue(0.5));
+        assert!((hue(0.25) - 357.5).abs() < 1e-3, "{}", hue(0.25));
+        let hue = orange_red
+            .mix(&magenta_red, 0.5, hsl, WhitePointPolicy::Adapt)
+            .unwrap()
+            .value
+            .x;
+        assert!((hue - 5.0).abs() < 1e-3, "{hue}");
+
+        // Oklch hues are in radians.
+        let lch = |hue: Float| Color::new(0.6, 0.1, hue, spaces::OK_LCH);
+        let hue = lch(3.0)
+            .mix(&lch(-3.0), 0.5, spaces::OK_LCH, WhitePointPolicy::Adapt)
+            .unwrap()
+            .value
+            .z;
+        assert!((hue.abs() - PI).abs() < 1e-4, "{hue}");
+    }
+
+    #[test]
+    fn custom_spaces_compare_exactly_or_approximately() {
+        let [[rx, ry], [gx, gy], [bx, by]] = *RgbPrimaries::Bt709.values();
+        let literal = ColorSpace::linear(
+            RgbPrimaries::Custom([[0.64, 0.33], [0.30, 0.60], [0.15, 0.06]]),
+            WhitePoint::D65,
+        );
+        let computed = ColorSpace::linear(
+            RgbPrimaries::Custom([[rx + 1e-7, ry], [gx, gy - 1e-7], [bx, by]]),
+            WhitePoint::D65,
+        );
+        assert!(literal.approx_eq(&computed));
+        assert_ne!(literal, computed);
+        assert_eq!(literal, literal);
+
+        let different = ColorSpace::linear(
+            RgbPrimaries::Custom([[0.64, 0.33], [0.30, 0.60], [0.15, 0.07]]),
+            WhitePoint::D65,
+        );
+        assert!(!literal.approx_eq(&different));
+
+        let white = WhitePoint::Custom([0.95047, 1.0, 1.08883]);
+        assert!(white.approx_eq(&WhitePoint::Custom([0.950_470_5, 1.0, 1.08883])));
+        assert_ne!(white, WhitePoint::Custom([0.950_470_5, 1.0, 1.08883]));
+        assert!(!white.approx_eq(&WhitePoint::Custom([0.951, 1.0, 1.08883])));
+        assert!(!white.approx_eq(&WhitePoint::D65));
+    }
+
+    #[test]
+    #[cfg(feature = "glam")]
+    fn glam_round_trip() {
+        let color = Color::srgb(0.25, 0.5, 1.0);
+        let vector = glam::Vec3A::from(color);
+        assert_eq!(vector, glam::Vec3A::new(0.25, 0.5, 1.0));
+        let back = Color::from((vector, spaces::ENCODED_SRGB));
+        assert_eq!(back.value, color.value);
+        assert_eq!(back.space, spaces::ENCODED_SRGB);
+        assert_eq!(glam::Vec3::from(back), glam::Vec3::new(0.25, 0.5, 1.0));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip() {
+        let color = Color::new(0.25, 0.5, 1.0, spaces::ACES_CCT);
+        let json = serde_json::to_string(&color).unwrap();
+        assert_eq!(
+            json,
+            r#"{"value":[0.25,0.5,1.0],"space":{"primaries":"AcesAp1","white_point":"D60","transform_fn":"AcesCct"}}"#
+        );
+        let back: Color = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.value, color.value);
+        assert_eq!(back.space, color.space);
+
+        let custom = ColorSpace::new(
+            RgbPrimaries::Custom([[0.7, 0.3], [0.2, 0.7], [0.1, 0.1]]),
+            WhitePoint::Custom([0.97, 1.0, 1.1]),
+            Some(TransformFn::Srgb),
+        );
+        let back: ColorSpace =
+            serde_json::from_str(&serde_json::to_string(&custom).unwrap()).unwrap();
+        assert_eq!(back, custom);
+
+        let near_d65: WhitePoint =
+            serde_json::from_str(r#"{"Custom":[0.95047,1.0,1.08883]}"#).unwrap();
+        assert!(matches!(near_d65, WhitePoint::D65));
+        let near_bt709: RgbPrimaries =
+            serde_json::from_str(r#"{"Custom":[[0.64,0.33],[0.3,0.6],[0.15,0.06]]}"#).unwrap();
+        assert!(matches!(near_bt709, RgbPrimaries::Bt709));
+
+        for space in color_spaces::ALL_COLOR_SPACES {
+            let back: ColorSpace =
+                serde_json::from_str(&serde_json::to_string(&space).unwrap()).unwrap();
+            assert_eq!(back, space);
+        }
+    }
+
+    #[test]
+    fn oklab_fast_error() {
+        let mut max_error: Float = 0.0;
+        let steps = 16;
+        for r in 0..=steps {
+            for g in 0..=steps {
+                for b in 0..=steps {
+                    let [r, g, b] = [r, g, b].map(|c| c as Float / steps as Float);
+                    let color = Color::srgb(r, g, b);
+                    let exact = color.to(color_spaces::OK_LAB).value;
+                    let fast = color.to_oklab_fast();
+                    assert_eq!(fast.space, color_spaces::OK_LAB);
+                    let d = fast.value - exact;
+                    max_error = max_error.max(d.x.abs()).max(d.y.abs()).max(d.z.abs());
+                }
+            }
+        }
+        assert!(
+            max_error <= OKLAB_FAST_MAX_ERROR,
+            "{max_error} > {OKLAB_FAST_MAX_ERROR}"
+        );
+    }
+
+    #[test]
+    fn accessors_return_known_variants() {
+        let space = ColorSpace::new(
+            RgbPrimaries::Custom([[0.64, 0.33], [0.3, 0.6], [0.15, 0.06]]),
+            WhitePoint::Custom([0.95047, 1.0, 1.08883]),
+            Some(TransformFn::Srgb),
+        );
+        assert!(matches!(space.primaries(), RgbPrimaries::Bt709));
+        assert!(matches!(space.white_point(), WhitePoint::D65));
+        assert_eq!(space.transform_function(), Some(TransformFn::Srgb));
+        assert_eq!(color_spaces::ACES_CG.primaries(), RgbPrimaries::AcesAp1);
+
+        let custom = RgbPrimaries::Custom([[0.6, 0.3], [0.3, 0.6], [0.1, 0.1]]);
+        let space = ColorSpace::new(custom, WhitePoint::D65, None);
+        assert_eq!(space.primaries(), custom);
+        assert_eq!(space.transform_function(), None);
+    }
+
+    #[test]
+    fn mix_many_averages_hues_on_the_circle() {
+        let stops = [
+            (Color::srgb(1.0, 0.0, 0.0), 1.0),
+            (Color::srgb(0.0, 1.0, 0.0), 1.0),
+            (Color::srgb(0.0, 0.0, 1.0), 1.0),
+        ];
+        let chroma = |color: Color| color.to(spaces::OK_LCH).value.y;
+        let mean_chroma = stops.iter().map(|(color, _)| chroma(*color)).sum::<Float>() / 3.0;
+        let mixed =
+            Color::mix_many(&stops, spaces::OK_LCH, HueInterpolation::ChromaWeighted).unwrap();
+        assert_eq!(mixed.space, spaces::ENCODED_SRGB);
+        assert!(chroma(mixed) < 0.25 * mean_chroma, "{}", chroma(mixed));
+        let oklab =
+            Color::mix_many(&stops, spaces::OK_LAB, HueInterpolation::ChromaWeighted).unwrap();
+        assert!(
+            mixed.value.abs_diff_eq(oklab.value, 1e-4),
+            "{mixed:?} {oklab:?}"
+        );
+
+        let hsl = ColorSpace::new(RgbPrimaries::Bt709, WhitePoint::D65, Some(TransformFn::Hsl));
+        let reds = [
+            (Color::new(350.0, 1.0, 0.5, hsl), 1.0),
+            (Color::new(20.0, 1.0, 0.5, hsl), 1.0),
+        ];
+        let red = Color::mix_many(&reds, hsl, HueInterpolation::Circular).unwrap();
+        assert!((red.value.x - 5.0).abs() < 1e-3, "{:?}", red.value);
+        assert!((red.value.y - 1.0).abs() < 1e-5, "{:?}", red.value);
+        let weighted = [(reds[0].0, 3.0), (reds[1].0, 1.0)];
+        let hue = Color::mix_many(&weighted, hsl, HueInterpolation::Circular)
+            .unwrap()
+            .value
+            .x;
+        assert!(hue > 355.0 && hue < 360.0, "{hue}");
+
+        let gray = Color::mix_many(&stops, spaces::LINEAR_SRGB, HueInterpolation::Circular)
+            .unwrap()
+            .to(spaces::LINEAR_SRGB);
+        assert!(
+            gray.value.abs_diff_eq(Vec3::splat(1.0 / 3.0), 1e-5),
+            "{gray:?}"
+        );
+
+        assert_eq!(
+            Color::mix_many(&[], spaces::OK_LCH, HueInterpolation::Circular).unwrap_err(),
+            ColorError::ZeroTotalWeight
+        );
+        let cancelling = [(stops[0].0, 1.0), (stops[1].0, -1.0)];
+        assert!(Color::mix_many(&cancelling, spaces::OK_LAB, HueInterpolation::Circular).is_err());
+    }
+
+    #[test]
+    fn component_ranges() {
+        let hsl = ColorSpace::new(RgbPrimaries::Bt709, WhitePoint::D65, Some(TransformFn::Hsl));
+        assert_eq!(
+            hsl.component_ranges(),
+            [[0.0, 360.0], [0.0, 1.0], [0.0, 1.0]]
+        );
+        assert_eq!(
+            color_spaces::LINEAR_SRGB.to_cie_lab().component_ranges(),
+            [[0.0, 100.0], [-128.0, 127.0], [-128.0, 127.0]]
+        );
+        assert_eq!(
+            color_spaces::ENCODED_SRGB.component_ranges(),
+            [[0.0, 1.0]; 3]
+        );
+        let xyz = color_spaces::CIE_XYZ.component_ranges();
+        assert_eq!(xyz[1], [0.0, 1.0]);
+        assert_eq!(xyz[2][1], WhitePoint::D65.values()[2]);
+    }
+
+    #[test]
+    fn srgb_primary_colors_have_bt709_chromaticities() {
+        let primaries = color_spaces::ENCODED_SRGB.primary_colors();
+        for (color, expected) in primaries.iter().zip(RgbPrimaries::Bt709.values()) {
+            assert_eq!(color.space, color_spaces::ENCODED_SRGB);
+            let xyz = color.to(color_spaces::CIE_XYZ).value;
+            let sum = xyz.x + xyz.y + xyz.z;
+            let xy = [xyz.x / sum, xyz.y / sum];
+            assert!(
+                (xy[0] - expected[0]).abs() < 1e-4 && (xy[1] - expected[1]).abs() < 1e-4,
+                "{xy:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn srgb_and_gamma_2_2_diverge_near_black() {
+        let piecewise = ColorConversion::new(spaces::ENCODED_SRGB, spaces::LINEAR_SRGB);
+        let gamma = ColorConversion::new(spaces::SRGB_GAMMA_2_2, spaces::LINEAR_SRGB);
+        let decode =
+            |conversion: &ColorConversion, value: Float| conversion.convert(Vec3::splat(value)).x;
+        for encoded in [0.005, 0.01, 0.02, 0.035] {
+            let (piecewise, gamma) = (decode(&piecewise, encoded), decode(&gamma, encoded));
+            // The linear segment of sRGB is far brighter than the power curve.
+            assert!(piecewise > 1.5 * gamma, "{encoded}: {piecewise} {gamma}");
+        }
+        for encoded in [0.1, 0.25, 0.5, 0.75, 1.0] {
+            let (piecewise, gamma) = (decode(&piecewise, encoded), decode(&gamma, encoded));
+            assert!(
+                (piecewise - gamma).abs() < 0.01,
+                "{encoded}: {piecewise} {gamma}"
+            );
+        }
+        assert_eq!(
+            ColorSpace::by_name("sRGB gamma 2.2"),
+            Some(spaces::SRGB_GAMMA_2_2)
+        );
+    }
+
+    #[test]
+    fn with_whitepoint_using_adapts_the_primaries() {
+        for cat in [CatMethod::Bradford, CatMethod::XyzScaling] {
+            let adapted = spaces::ENCODED_SRGB.with_whitepoint_using(WhitePoint::D50, cat);
+            assert_eq!(adapted.white_point(), WhitePoint::D50);
+            assert_eq!(adapted.transform_function(), Some(TransformFn::Srgb));
+            assert!(matches!(adapted.primaries(), RgbPrimaries::Custom(_)));
+
+            let conversion = ColorConversion::with_cat(spaces::ENCODED_SRGB, adapted, cat);
+            let color = Vec3::new(0.8, 0.3, 0.1);
+            let result = conversion.convert(color);
+            assert!(result.abs_diff_eq(color, 1e-5), "{cat:?}: {result:?}");
+        }
+        // A relabeled white point keeps the chromaticities instead.
+        let relabeled = spaces::ENCODED_SRGB.with_whitepoint(WhitePoint::D50);
+        assert_eq!(relabeled.primaries(), RgbPrimaries::Bt709);
+        assert_eq!(
+            spaces::CIE_XYZ.with_whitepoint_using(WhitePoint::D50, CatMethod::Bradford),
+            spaces::CIE_XYZ.with_whitepoint(WhitePoint::D50)
+        );
+    }
+
+    #[test]
+    fn dci_and_display_p3_green() {
+        let green = Vec3::new(0.0, 1.0, 0.0);
+        let display = ColorConversion::new(spaces::ENCODED_DISPLAY_P3, spaces::LINEAR_SRGB);
+        let expected = Vec3::new(-0.224901, 1.042063, -0.078654);
+        let result = display.convert(green);
+        assert!(
+            result.abs_diff_eq(expected, 1e-4),
+            "{result:?} != {expected:?}"
+        );
+
+        // The DCI white point is adapted to D65, here with Bradford.
+        let dci = ColorConversion::with_cat(
+            spaces::ENCODED_DCI_P3,
+            spaces::LINEAR_SRGB,
+            CatMethod::Bradford,
+        );
+        let expected = Vec3::new(-0.154855, 1.045569, -0.078599);
+        let result = dci.convert(green);
+        assert!(
+            result.abs_diff_eq(expected, 1e-4),
+            "{result:?} != {expected:?}"
+        );
+
+        let gray = ColorConversion::new(spaces::ENCODED_DCI_P3, spaces::DCI_P3)
+            .convert(Vec3::new(0.5, -0.5, 1.0));
+        let expected = Vec3::new(0.164938, -0.164938, 1.0);
+        assert!(gray.abs_diff_eq(expected, 1e-5), "{gray:?} != {expected:?}");
+        assert_eq!(ColorSpace::by_name("DCI-P3"), Some(spaces::ENCODED_DCI_P3));
+        assert_eq!(ColorSpace::by_name("P3-DCI"), Some(spaces::ENCODED_DCI_P3));
+        assert_eq!(ColorSpace::by_name("linear DCI-P3"), Some(spaces::DCI_P3));
+        assert_eq!(ColorSpace::by_name("P3 Theater"), Some(spaces::P3_THEATER));
+        assert_eq!(
+            ColorSpace::by_name("Display P3"),
+            Some(spaces::ENCODED_DISPLAY_P3)
+        );
+    }
+
+    #[test]
+    fn color_space_builder_validation() {
+        assert_eq!(
+            ColorSpaceBuilder::new().build(),
+            Ok(color_spaces::LINEAR_SRGB)
+        );
+        assert_eq!(
+            ColorSpaceBuilder::new()
+                .primaries(RgbPrimaries::Bt2020)
+                .transform(TransformFn::Pq)
+                .build(),
+            Ok(color_spaces::ENCODED_BT_2100_PQ)
+        );
+        assert_eq!(
+            ColorSpaceBuilder::new()
+                .primaries(RgbPrimaries::CieXyz)
+                .white_point(WhitePoint::E)
+                .build()
+                .map(|space| space.primaries()),
+            Ok(RgbPrimaries::CieXyz)
+        );
+
+        let collinear = RgbPrimaries::Custom([[0.2, 0.2], [0.4, 0.4], [0.6, 0.6]]);
+        let zero_y = RgbPrimaries::Custom([[0.64, 0.33], [0.3, 0.6], [0.15, 0.0]]);
+        let nan = RgbPrimaries::Custom([[0.64, 0.33], [Float::NAN, 0.6], [0.15, 0.06]]);
+        for primaries in [collinear, zero_y, nan] {
+            assert_eq!(
+                ColorSpaceBuilder::new().primaries(primaries).build(),
+                Err(ColorError::DegeneratePrimaries),
+                "{primaries:?}"
+            );
+        }
+        assert_eq!(
+            ColorSpaceBuilder::new()
+                .white_point(WhitePoint::Custom([0.95, 1.0, -0.1]))
+                .build(),
+            Err(ColorError::NonPositiveWhitePoint)
+        );
+        let narrow = RgbPrimaries::Custom([[0.6, 0.35], [0.55, 0.4], [0.5, 0.3]]);
+        assert_eq!(
+            ColorSpaceBuilder::new().primaries(narrow).build(),
+            Err(ColorError::WhitePointOutsideGamut)
+        );
+        // The order of the primaries doesn't matter for the gamut check.
+        let clockwise = RgbPrimaries::Custom([[0.15, 0.06], [0.3, 0.6], [0.64, 0.33]]);
+        assert!(
+            ColorSpaceBuilder::new()
+                .primaries(clockwise)
+                .build()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn canonicalize_with_tolerance() {
+        let mut rounded = RgbPrimaries::Custom([[0.640, 0.330], [0.300, 0.600], [0.150, 0.060]]);
+        assert!(rounded.canonicalize().is_ok());
+
+        // Rounded to 3 decimals, BT.709's green (0.3, 0.6) is off by 5e-4.
+        let mut rounded = RgbPrimaries::Custom([[0.640, 0.330], [0.3005, 0.600], [0.150, 0.060]]);
+        assert!({ rounded }.canonicalize().is_err());
+        assert!(rounded.canonicalize_with_tol(1e-3).is_ok());
+        assert!(matches!(rounded, RgbPrimaries::Bt709));
+
+        // Within 0.1 of P3, BT.709 and Adobe RGB, and closest to P3.
+        let mut between = RgbPrimaries::Custom([[0.675, 0.322], [0.28, 0.67], [0.15, 0.06]]);
+        assert!(between.canonicalize_with_tol(0.1).is_ok());
+        assert!(matches!(between, RgbPrimaries::P3), "{between:?}");
+
+        let mut white_point = WhitePoint::Custom([0.951, 1.0, 1.089]);
+        assert!({ white_point }.canonicalize().is_err());
+        assert!(white_point.canonicalize_with_tol(1e-3).is_ok());
+        assert!(matches!(white_point, WhitePoint::D65));
+        assert!(
+            WhitePoint::Custom([0.5, 1.0, 0.5])
+                .canonicalize_with_tol(1e-3)
+                .is_err()
+        );
+    }
 }
@@ -0,0 +1,149 @@
+//! Contrast metrics between text and background colors.
+use super::color::{Color, color_spaces};
+use crate::Float;
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+use num_traits::Float as _;
+
+/// Luminance below which APCA soft clamps black levels.
+const APCA_BLACK_THRESHOLD: Float = 0.022;
+/// Exponent of the APCA black level soft clamp.
+const APCA_BLACK_CLAMP: Float = 1.414;
+/// Luminance differences below which APCA reports no contrast.
+const APCA_DELTA_Y_MIN: Float = 0.0005;
+/// Scale of the APCA contrast, for both polarities.
+const APCA_SCALE: Float = 1.14;
+/// Offset removed from the APCA contrast, for both polarities.
+const APCA_OFFSET: Float = 0.027;
+/// Contrast below which APCA reports no contrast.
+const APCA_LOW_CLIP: Float = 0.1;
+
+/// Flare added to both luminances of the WCAG contrast ratio.
+const WCAG_FLARE: Float = 0.05;
+
+/// Returns the relative luminance of `color` as defined by WCAG 2.x, in
+/// linear sRGB with its components clamped to [0, 1].
+fn wcag_luminance(color: &Color) -> Float {
+    let rgb = color.to(color_spaces::LINEAR_SRGB).value;
+    let channel = |value: Float| value.clamp(0.0, 1.0);
+    let (r, g, b) = (channel(rgb.x), channel(rgb.y), channel(rgb.z));
+    Color::new(r, g, b, color_spaces::LINEAR_SRGB).luminance()
+}
+
+/// Returns the screen luminance of `color` as estimated by APCA, including
+/// the soft clamp of black levels.
+fn apca_luminance(color: &Color) -> Float {
+    let rgb = color.to(color_spaces::ENCODED_SRGB).value;
+    let channel = |value: Float| value.clamp(0.0, 1.0).powf(2.4);
+    let y = 0.2126729 * channel(rgb.x) + 0.7151522 * channel(rgb.y) + 0.0721750 * channel(rgb.z);
+    if y < APCA_BLACK_THRESHOLD {
+        y + (APCA_BLACK_THRESHOLD - y).powf(APCA_BLACK_CLAMP)
+    } else {
+        y
+    }
+}
+
+impl Color {
+    /// Returns the WCAG 2.x contrast ratio (L1 + 0.05) / (L2 + 0.05) between
+    /// `a` and `b`, where L1 is the relative luminance of the lighter color.
+    ///
+    /// The ratio doesn't depend on the order of the colors and ranges from 1
+    /// for equal luminances to 21 for black and white; WCAG AA requires 4.5
+    /// for body text. Both colors are converted to linear sRGB, on which WCAG
+    /// is defined, and clamped to [0, 1].
+    pub fn wcag_contrast(a: &Color, b: &Color) -> Float {
+        let (a, b) = (wcag_luminance(a), wcag_luminance(b));
+        (a.max(b) + WCAG_FLARE) / (a.min(b) + WCAG_FLARE)
+    }
+
+    /// Returns the APCA (Accessible Perceptual Contrast Algorithm, 0.0.98G-4g)
+    /// lightness contrast Lc of `text` on `background`.
+    ///
+    /// Dark text on a light background gives a positive Lc, light text on a
+    /// dark background a negative one, as APCA uses different exponents for
+    /// both polarities. Contrasts below the APCA low clip are reported as 0.
+    /// The magnitude ranges up to about 108; an Lc of 75 is the minimum
+    /// suggested for body text. Both colors are converted to encoded sRGB, on
+    /// which APCA is defined, and clamped to [0, 1].
+    pub fn apca_contrast(text: &Color, background: &Color) -> Float {
+        let text = apca_luminance(text);
+        let background = apca_luminance(background);
+        if (background - text).abs() < APCA_DELTA_Y_MIN {
+            return 0.0;
+        }
+        let contrast = if background > text {
+            let contrast = (background.powf(0.56) - text.powf(0.57)) * APCA_SCALE;
+            if contrast < APCA_LOW_CLIP {
+                0.0
+            } else {
+                contrast - APCA_OFFSET
+            }
+        } else {
+            let contrast = (background.powf(0.65) - text.powf(0.62)) * APCA_SCALE;
+            if contrast > -APCA_LOW_CLIP {
+                0.0
+            } else {
+                contrast + APCA_OFFSET
+            }
+        };
+        contrast * 100.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hex(rgb: u32) -> Color {
+        let channel = |shift: u32| ((rgb >> shift) & 0xFF) as Float / 255.0;
+        Color::srgb(channel(16), channel(8), channel(0))
+    }
+
+    #[test]
+    fn apca_reference_values() {
+        // From the APCA reference implementation.
+        for (text, background, expected) in [
+            (0x888888, 0xFFFFFF, 63.0565),
+            (0xFFFFFF, 0x888888, -68.5415),
+            (0x000000, 0xAAAAAA, 58.1463),
+            (0xAAAAAA, 0x000000, -56.2411),
+            (0x112233, 0xDDEEFF, 91.6683),
+            (0xDDEEFF, 0x112233, -93.0677),
+        ] {
+            let contrast = Color::apca_contrast(&hex(text), &hex(background));
+            assert!(
+                (contrast - expected).abs() < 1e-3,
+                "{text:06x} on {background:06x}: {contrast} != {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn wcag_reference_values() {
+        for (a, b, expected) in [
+            (0x000000, 0xFFFFFF, 21.0),
+            (0x777777, 0xFFFFFF, 4.4783),
+            (0x0000FF, 0xFFFFFF, 8.5942),
+            (0x777777, 0x777777, 1.0),
+        ] {
+            let contrast = Color::wcag_contrast(&hex(a), &hex(b));
+            assert!(
+                (contrast - expected).abs() < 1e-3,
+                "{a:06x} and {b:06x}: {contrast} != {expected}"
+            );
+            assert_eq!(contrast, Color::wcag_contrast(&hex(b), &hex(a)));
+        }
+
+        // Colors in other spaces are compared in linear sRGB.
+        let white = hex(0xFFFFFF).to(color_spaces::OK_LCH);
+        let gray = hex(0x777777).to(color_spaces::ACES_CG);
+        let contrast = Color::wcag_contrast(&gray, &white);
+        assert!((contrast - 4.4783).abs() < 1e-3, "{contrast}");
+    }
+
+    #[test]
+    fn apca_no_contrast() {
+        let gray = hex(0x777777);
+        assert_eq!(Color::apca_contrast(&gray, &gray), 0.0);
+        assert_eq!(Color::apca_contrast(&hex(0x777777), &hex(0x787878)), 0.0);
+    }
+}
@@ -1,5 +1,8 @@
+#[cfg(feature = "std")]
+use super::color::Color;
 use super::{
-    color::{RgbPrimaries, TransformFn},
+    cat::CatMethod,
+    color::{ColorError, RgbPrimaries, TransformFn},
     transform::ColorTransform,
     xyz::{rgb_to_xyz, xyz_to_rgb},
 };
@@ -7,13 +10,24 @@ use crate::{ColorSpace, Float, Mat3, Vec3};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+/// Maximum difference per matrix entry for which a composed matrix is
+/// considered equal to the directly derived one in
+/// [`LinearColorConversion::then()`].
+const COMPOSITION_EPSILON: Float = 1e-4;
+
 /// A transformation from one linear color space to another.
+///
+/// The transformation is a 3×3 matrix, optionally followed by adding an
+/// offset, which makes it affine, e.g. for limited-range YCbCr. Conversions
+/// without an offset don't pay for it.
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LinearColorConversion {
     mat: Mat3,
+    offset: Option<Vec3>,
     input_space: ColorSpace,
     output_space: ColorSpace,
+    cat: CatMethod,
 }
 
 impl LinearColorConversion {
@@ -26,33 +40,239 @@ impl LinearColorConversion {
     }
 
     pub fn convert(&self, color: Vec3) -> Vec3 {
-        self.mat * color
+        match self.offset {
+            Some(offset) => self.mat * color + offset,
+            None => self.mat * color,
+        }
     }
 
     pub fn matrix(&self) -> Mat3 {
         self.mat
     }
 
+    /// The offset added after multiplying with the [matrix](Self::matrix), or
+    /// `None` if the conversion is purely linear.
+    pub fn offset(&self) -> Option<Vec3> {
+        self.offset
+    }
+
+    /// Creates a conversion between `src` and `dst` which multiplies with
+    /// `mat`, instead of deriving the matrix from the primaries and white
+    /// points, e.g. for conversions to YCbCr.
+    pub fn from_matrix(src: ColorSpace, dst: ColorSpace, mat: Mat3) -> Self {
+        Self {
+            mat,
+            offset: None,
+            input_space: src,
+            output_space: dst,
+            cat: CatMethod::default(),
+        }
+    }
+
+    /// Adds `offset` to the result of the matrix multiplication, making the
+    /// conversion affine, e.g. to encode limited-range YCbCr. A zero offset
+    /// removes it.
+    pub fn with_offset(self, offset: Vec3) -> Self {
+        Self {
+            offset: (offset != Vec3::splat(0.0)).then_some(offset),
+            ..self
+        }
+    }
+
+    /// The chromatic adaptation method used between different white points.
+    pub fn cat(&self) -> CatMethod {
+        self.cat
+    }
+
+    /// Returns the conversion matrix computed at `f64` precision, regardless
+    /// of [`Float`], as an array of columns, i.e. indexed `[column][row]`.
+    ///
+    /// This allows exporting high precision matrices from applications using
+    /// `f32`. The matrix is derived anew from the primaries and white points of
+    /// the input and output spaces, which are given as [`Float`]. Matrices
+    /// which are not derived from the spaces, e.g. created with
+    /// [`from_matrix()`](Self::from_matrix) or adjusted by
+    /// [`ColorConversion::luminance_preserving()`] or
+    /// [`ColorConversion::with_reference_luminance()`], are returned as they
+    /// are, widened to `f64` without gaining precision. The
+    /// [offset](Self::offset) is never included.
+    pub fn matrix_f64(&self) -> [[f64; 3]; 3] {
+        if !self.is_derived_matrix() {
+            let mat = self.mat;
+            return [mat.x_axis, mat.y_axis, mat.z_axis].map(|col| widen(&col.into()));
+        }
+        let (src, dst) = (self.input_space, self.output_space);
+        let src_to_xyz = if src.primaries() == RgbPrimaries::CieXyz {
+            F64_IDENTITY
+        } else {
+            rgb_to_xyz_f64(src.primaries().values(), src.white_point().values())
+        };
+        let xyz_to_dst = if dst.primaries() == RgbPrimaries::CieXyz {
+            F64_IDENTITY
+        } else {
+            inverse_f64(rgb_to_xyz_f64(
+                dst.primaries().values(),
+                dst.white_point().values(),
+            ))
+        };
+        if src.white_point() == dst.white_point() {
+            return mul_f64(xyz_to_dst, src_to_xyz);
+        }
+        let cone_space = self.cat.cols_array_f64();
+        let cone_space = [
+            [cone_space[0], cone_space[1], cone_space[2]],
+            [cone_space[3], cone_space[4], cone_space[5]],
+            [cone_space[6], cone_space[7], cone_space[8]],
+        ];
+        let src_cone = mul_vec_f64(cone_space, widen(src.white_point().values()));
+        let dst_cone = mul_vec_f64(cone_space, widen(dst.white_point().values()));
+        let mut src_to_dst_cone = [[0.0; 3]; 3];
+        for i in 0..3 {
+            src_to_dst_cone[i][i] = dst_cone[i] / src_cone[i];
+        }
+        let white_point_transform = mul_f64(
+            inverse_f64(cone_space),
+            mul_f64(src_to_dst_cone, cone_space),
+        );
+        mul_f64(xyz_to_dst, mul_f64(white_point_transform, src_to_xyz))
+    }
+
+    /// Composes this conversion with `next` into a single conversion from the
+    /// input space of `self` to the output space of `next`.
+    ///
+    /// When both matrices are derived from their spaces, i.e. not adjusted or
+    /// created with [`from_matrix()`](Self::from_matrix), and their product
+    /// matches the matrix derived directly between the outer spaces, the
+    /// directly derived matrix is used instead. This merges white point
+    /// adaptations done by both conversions into a single CAT, and drops them
+    /// entirely when the outer spaces share a white point (e.g. D65 → D50
+    /// followed by D50 → D65), instead of accumulating rounding errors from
+    /// adapting back and forth. Otherwise the product is used as it is.
+    /// Offsets of both conversions are carried through `next`.
+    ///
+    /// # Panics
+    ///
+    /// If the output space of `self` is not the input space of `next`.
+    pub fn then(&self, next: &LinearColorConversion) -> Self {
+        if self.output_space != next.input_space {
+            panic!(
+                "{:?} does not continue from {:?}",
+                next.input_space, self.output_space
+            );
+        }
+        let composed = next.mat * self.mat;
+        let direct = (self.cat == next.cat && self.is_derived() && next.is_derived())
+            .then(|| Self::with_cat(self.input_space, next.output_space, self.cat).mat)
+            .filter(|direct| composed.abs_diff_eq(*direct, COMPOSITION_EPSILON));
+        let mat = direct.unwrap_or(composed);
+        let offset = match (self.offset, next.offset) {
+            (None, None) => None,
+            (offset, next_offset) => Some(
+                next.mat * offset.unwrap_or(Vec3::splat(0.0))
+                    + next_offset.unwrap_or(Vec3::splat(0.0)),
+            ),
+        };
+        Self {
+            mat,
+            offset,
+            input_space: self.input_space,
+            output_space: next.output_space,
+            cat: self.cat,
+        }
+    }
+
+    /// Whether the conversion is purely linear with the matrix derived from its
+    /// spaces, i.e. the matrix [`with_cat()`](Self::with_cat) creates.
+    fn is_derived(&self) -> bool {
+        self.offset.is_none() && self.is_derived_matrix()
+    }
+
+    /// Whether the matrix is the one [`with_cat()`](Self::with_cat) derives
+    /// from the spaces, regardless of the offset.
+    fn is_derived_matrix(&self) -> bool {
+        self.input_space.is_linear()
+            && self.output_space.is_linear()
+            && self.mat == Self::with_cat(self.input_space, self.output_space, self.cat).mat
+    }
+
+    /// Returns the conversion from the output to the input space, with the
+    /// inverse of the matrix of this conversion.
+    pub fn inverse(&self) -> Self {
+        let mat = self.mat.inverse();
+        Self {
+            mat,
+            offset: self.offset.map(|offset| Vec3::splat(0.0) - mat * offset),
+            input_space: self.output_space,
+            output_space: self.input_space,
+            cat: self.cat,
+        }
+    }
+
+    /// Analyzes the conversion matrix, see [`MatrixDecomposition`].
+    pub fn decompose(&self) -> MatrixDecomposition {
+        let mat = self.mat;
+        let scale = Vec3::new(mat.x_axis.x, mat.y_axis.y, mat.z_axis.z);
+        let off_diagonal = [
+            mat.x_axis.y,
+            mat.x_axis.z,
+            mat.y_axis.x,
+            mat.y_axis.z,
+            mat.z_axis.x,
+            mat.z_axis.y,
+        ]
+        .iter()
+        .fold(0.0, |max: Float, v| max.max(v.abs()));
+        MatrixDecomposition {
+            determinant: mat.determinant(),
+            scale,
+            off_diagonal,
+        }
+    }
+
     pub fn new(src: ColorSpace, dst: ColorSpace) -> Self {
+        Self::with_cat(src, dst, CatMethod::default())
+    }
+
+    /// Like [`new()`](Self::new), but adapts between different white points
+    /// with `cat` instead of the default [`Sharp`](CatMethod::Sharp) method.
+    pub fn with_cat(src: ColorSpace, dst: ColorSpace, cat: CatMethod) -> Self {
         if !src.is_linear() {
             panic!("{src:?} is not a linear color space");
         }
         if !dst.is_linear() {
             panic!("{dst:?} is not a linear color space");
         }
-        #[cfg(feature = "color-matrices")]
-        let const_conversion = super::generated_matrices::const_conversion_matrix(
-            src.primaries(),
-            src.white_point(),
-            dst.primaries(),
-            dst.white_point(),
-        );
-        #[cfg(not(feature = "color-matrices"))]
-        let const_conversion: Option<Mat3> = None;
+        let const_conversion = if src.primaries() == dst.primaries()
+            && src.white_point() == dst.white_point()
+        {
+            Some(Mat3::IDENTITY)
+        } else {
+            // The bundled matrices adapt with the default method.
+            #[cfg(feature = "color-matrices")]
+            let bundled = if cat == CatMethod::default() || src.white_point() == dst.white_point() {
+                super::generated_matrices::const_conversion_matrix(
+                    src.primaries(),
+                    src.white_point(),
+                    dst.primaries(),
+                    dst.white_point(),
+                )
+            } else {
+                None
+            };
+            #[cfg(not(feature = "color-matrices"))]
+            let bundled = None;
+            bundled
+        };
 
         let mat = if let Some(const_mat) = const_conversion {
+            #[cfg(feature = "tracing")]
+            tracing::trace!(?src, ?dst, "using bundled conversion matrix");
             const_mat
         } else {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::debug_span!("compute_conversion_matrix", ?src, ?dst).entered();
+            #[cfg(feature = "tracing")]
+            tracing::debug!("computing conversion matrix on demand");
             let src_to_xyz = if src.primaries() == RgbPrimaries::CieXyz {
                 Mat3::IDENTITY
             } else {
@@ -64,11 +284,10 @@ impl LinearColorConversion {
                 xyz_to_rgb(dst.primaries().values(), dst.white_point().values())
             };
             if src.white_point() != dst.white_point() {
-                let white_point_transform = super::cat::LmsConeSpace::Sharp
-                    .chromatic_adaptation_transform(
-                        Vec3::from_slice(src.white_point().values()),
-                        Vec3::from_slice(dst.white_point().values()),
-                    );
+                let white_point_transform = cat.chromatic_adaptation_transform(
+                    Vec3::from_slice(src.white_point().values()),
+                    Vec3::from_slice(dst.white_point().values()),
+                );
                 xyz_to_dst * white_point_transform * src_to_xyz
             } else {
                 xyz_to_dst * src_to_xyz
@@ -76,12 +295,167 @@ impl LinearColorConversion {
         };
         Self {
             mat,
+            offset: None,
             input_space: src,
             output_space: dst,
+            cat,
+        }
+    }
+}
+
+// Helpers for `LinearColorConversion::matrix_f64()`, on column-major matrices.
+
+const F64_IDENTITY: [[f64; 3]; 3] = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+#[allow(clippy::unnecessary_cast)]
+fn widen(values: &[Float; 3]) -> [f64; 3] {
+    values.map(|value| value as f64)
+}
+
+fn mul_vec_f64(mat: [[f64; 3]; 3], vec: [f64; 3]) -> [f64; 3] {
+    core::array::from_fn(|row| (0..3).map(|col| mat[col][row] * vec[col]).sum())
+}
+
+fn mul_f64(lhs: [[f64; 3]; 3], rhs: [[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    rhs.map(|col| mul_vec_f64(lhs, col))
+}
+
+fn inverse_f64(mat: [[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let [a, b, c] = mat;
+    let cross = |u: [f64; 3], v: [f64; 3]| {
+        [
+            u[1] * v[2] - u[2] * v[1],
+            u[2] * v[0] - u[0] * v[2],
+            u[0] * v[1] - u[1] * v[0],
+        ]
+    };
+    let rows = [cross(b, c), cross(c, a), cross(a, b)];
+    let determinant: f64 = (0..3).map(|i| a[i] * rows[0][i]).sum();
+    // The rows of the inverse are the cross products, so transpose them into
+    // columns.
+    core::array::from_fn(|col| core::array::from_fn(|row| rows[row][col] / determinant))
+}
+
+#[allow(clippy::unnecessary_cast)]
+fn rgb_to_xyz_f64(primaries: &[[Float; 2]; 3], white_point: &[Float; 3]) -> [[f64; 3]; 3] {
+    let base = primaries.map(|[x, y]| {
+        let (x, y) = (x as f64, y as f64);
+        [x / y, 1.0, (1.0 - x - y) / y]
+    });
+    let scale = mul_vec_f64(inverse_f64(base), widen(white_point));
+    core::array::from_fn(|col| base[col].map(|value| value * scale[col]))
+}
+
+/// Describes what a [`LinearColorConversion`] does to colors, as returned by
+/// [`LinearColorConversion::decompose()`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MatrixDecomposition {
+    /// The determinant of the conversion matrix.
+    ///
+    /// A linear conversion maps the unit cube of the source space onto a
+    /// parallelepiped in the destination space. The determinant is the ratio
+    /// of the volume of that parallelepiped to the volume of the unit cube,
+    /// i.e. how much the conversion grows (`> 1`) or shrinks (`< 1`) volumes
+    /// when measured in destination units. A negative determinant means the
+    /// conversion also flips the handedness of the coordinate system.
+    pub determinant: Float,
+    /// The diagonal of the matrix, i.e. how much each channel is scaled onto
+    /// itself.
+    pub scale: Vec3,
+    /// The largest absolute value of any off-diagonal entry, i.e. how much
+    /// the channels are mixed into each other.
+    pub off_diagonal: Float,
+}
+
+impl MatrixDecomposition {
+    /// Whether the conversion is a pure per-channel scaling, i.e. all
+    /// off-diagonal entries are within `epsilon` of zero.
+    pub fn is_diagonal(&self, epsilon: Float) -> bool {
+        self.off_diagonal <= epsilon
+    }
+}
+
+/// Returns the weights of the linear channels of `space` for CIE Y.
+pub(crate) fn luminance_row(space: ColorSpace) -> Vec3 {
+    if space.primaries() == RgbPrimaries::CieXyz {
+        Vec3::new(0.0, 1.0, 0.0)
+    } else {
+        let mat = rgb_to_xyz(space.primaries().values(), space.white_point().values());
+        Vec3::new(mat.x_axis.y, mat.y_axis.y, mat.z_axis.y)
+    }
+}
+
+/// A step of a [`ColorConversion`], as returned by
+/// [`ColorConversion::operations()`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ConversionOp {
+    /// Applies the inverse of the transform function, decoding the source
+    /// space into its linear space.
+    InverseTransform(TransformFn),
+    /// Multiplies with the matrix, converting between linear spaces.
+    LinearMatrix(Mat3),
+    /// Applies the transform function, encoding the linear destination space.
+    Transform(TransformFn),
+}
+
+/// A stage of a [`ColorConversion`], see [`ConversionError`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConversionStage {
+    /// The value passed to the conversion.
+    Input,
+    /// Decoding the source space with the inverse of its transform function.
+    SrcTransform,
+    /// The matrix converting between the linear spaces.
+    LinearTransform,
+    /// Encoding the destination space with its transform function.
+    DstTransform,
+}
+
+/// A conversion produced a non-finite value, as returned by
+/// [`ColorConversion::convert_checked()`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ConversionError {
+    /// A component became NaN in `stage` when converting `input`.
+    Nan { stage: ConversionStage, input: Vec3 },
+    /// A component became infinite, and none NaN, in `stage` when converting
+    /// `input`.
+    Infinite { stage: ConversionStage, input: Vec3 },
+}
+
+impl ConversionError {
+    /// The first stage which produced a non-finite value.
+    pub fn stage(&self) -> ConversionStage {
+        match *self {
+            Self::Nan { stage, .. } | Self::Infinite { stage, .. } => stage,
         }
     }
+
+    /// The value passed to the conversion.
+    pub fn input(&self) -> Vec3 {
+        match *self {
+            Self::Nan { input, .. } | Self::Infinite { input, .. } => input,
+        }
+    }
+}
+
+impl core::fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let kind = match self {
+            Self::Nan { .. } => "NaN",
+            Self::Infinite { .. } => "infinite",
+        };
+        write!(
+            f,
+            "Converting {:?} produced a {kind} value in {:?}",
+            self.input(),
+            self.stage()
+        )
+    }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for ConversionError {}
+
 /// Defines an operation that maps a 3-component vector from a source
 /// [`ColorSpace`] to a destination `ColorSpace`.
 #[derive(Copy, Clone)]
@@ -94,7 +468,9 @@ pub struct ColorConversion {
 }
 impl PartialEq for ColorConversion {
     fn eq(&self, other: &Self) -> bool {
-        self.src_space == other.src_space && self.dst_space == other.dst_space
+        self.src_space == other.src_space
+            && self.dst_space == other.dst_space
+            && self.linear_transform == other.linear_transform
     }
 }
 impl core::fmt::Debug for ColorConversion {
@@ -115,8 +491,15 @@ impl core::fmt::Debug for ColorConversion {
 
 impl ColorConversion {
     pub fn new(src: ColorSpace, dst: ColorSpace) -> Self {
+        Self::with_cat(src, dst, CatMethod::default())
+    }
+
+    /// Like [`new()`](Self::new), but adapts between different white points
+    /// with `cat` instead of the default [`Sharp`](CatMethod::Sharp) method,
+    /// e.g. to match other software using Bradford or XYZ scaling.
+    pub fn with_cat(src: ColorSpace, dst: ColorSpace, cat: CatMethod) -> Self {
         let src_transform = if !src.is_linear() {
-            ColorTransform::new(src.transform_function(), None)
+            ColorTransform::with_white_point(src.transform_function(), None, src.white_point())
         } else {
             None
         };
@@ -124,7 +507,7 @@ impl ColorConversion {
         let src_linear = ColorSpace::linear(src.primaries(), src.white_point());
         let dst_linear = ColorSpace::linear(dst.primaries(), dst.white_point());
 
-        let linear_transform = LinearColorConversion::new(src_linear, dst_linear);
+        let linear_transform = LinearColorConversion::with_cat(src_linear, dst_linear, cat);
         let linear_transform = if linear_transform.mat == Mat3::IDENTITY {
             None
         } else {
@@ -132,7 +515,7 @@ impl ColorConversion {
         };
 
         let dst_transform = if !dst.is_linear() {
-            ColorTransform::new(None, dst.transform_function())
+            ColorTransform::with_white_point(None, dst.transform_function(), dst.white_point())
         } else {
             None
         };
@@ -146,8 +529,140 @@ impl ColorConversion {
         }
     }
 
+    /// Creates a conversion which is guaranteed to not clamp values at any
+    /// point in the pipeline, for HDR and scene-linear work.
+    ///
+    /// Returns [`ColorError::ClampingTransform`] if the transform function of
+    /// either space clamps, see [`TransformFn::clamps()`].
+    pub fn unbounded(src: ColorSpace, dst: ColorSpace) -> Result<Self, ColorError> {
+        for space in [src, dst] {
+            if let Some(transform_fn) = space.transform_function()
+                && transform_fn.clamps()
+            {
+                return Err(ColorError::ClampingTransform(transform_fn));
+            }
+        }
+        Ok(Self::new(src, dst))
+    }
+
+    /// Creates a conversion which preserves the luminance (CIE Y) of colors
+    /// exactly, instead of converting them colorimetrically.
+    ///
+    /// When the white points of `src` and `dst` differ, the chromatic
+    /// adaptation changes the luminance of colors away from the neutral axis
+    /// slightly. This conversion corrects the linear part of the conversion so
+    /// that CIE Y computed in `dst` equals CIE Y computed in `src`, while
+    /// neutral colors are still mapped onto the neutral axis of `dst`. This is
+    /// a deliberate deviation from the standard conversion for workflows which
+    /// require identical luminance.
+    pub fn luminance_preserving(src: ColorSpace, dst: ColorSpace) -> Self {
+        let mut conversion = Self::new(src, dst);
+        let linear = conversion.linear_part();
+        // Adding `correction` to every row keeps neutrals neutral, as it sums
+        // to zero, and moves the luminance of each primary to that in `src`.
+        let correction = luminance_row(src) - linear.mat.transpose() * luminance_row(dst);
+        let mat = Mat3 {
+            x_axis: linear.mat.x_axis + Vec3::splat(correction.x),
+            y_axis: linear.mat.y_axis + Vec3::splat(correction.y),
+            z_axis: linear.mat.z_axis + Vec3::splat(correction.z),
+        };
+        conversion.linear_transform = if mat == Mat3::IDENTITY {
+            None
+        } else {
+            Some(LinearColorConversion { mat, ..linear })
+        };
+        conversion
+    }
+
+    /// Scales the linear part of the conversion so that linear 1.0 in the
+    /// source space corresponds to `src_nits` cd/m² and linear 1.0 in the
+    /// destination space to `dst_nits` cd/m², keeping absolute luminance
+    /// across the conversion instead of relative luminance.
+    ///
+    /// The [PQ](TransformFn::Pq) transform decodes to absolute luminance
+    /// already, so the reference luminance of PQ spaces is 1 cd/m². E.g.
+    /// `ColorConversion::new(ENCODED_BT_2100_PQ, BT_2020)
+    /// .with_reference_luminance(1.0, 100.0)` maps 100 cd/m² to 1.0, for a
+    /// scene-linear space with a 100 cd/m² SDR reference white.
+    pub fn with_reference_luminance(mut self, src_nits: Float, dst_nits: Float) -> Self {
+        let scale = src_nits / dst_nits;
+        if scale == 1.0 {
+            return self;
+        }
+        let linear = self.linear_part();
+        // Scaling the input of an affine transform only scales its matrix.
+        let mat = Mat3 {
+            x_axis: linear.mat.x_axis * scale,
+            y_axis: linear.mat.y_axis * scale,
+            z_axis: linear.mat.z_axis * scale,
+        };
+        self.linear_transform = Some(LinearColorConversion { mat, ..linear });
+        self
+    }
+
     pub fn invert(&self) -> Self {
-        ColorConversion::new(self.dst_space, self.src_space)
+        ColorConversion::with_cat(self.dst_space, self.src_space, self.linear_part().cat)
+    }
+
+    /// Returns the conversion from the destination to the source space, like
+    /// [`invert()`](Self::invert), but reuses the linear part of this
+    /// conversion by inverting its matrix instead of deriving it anew, and
+    /// swaps the roles of the transform functions.
+    ///
+    /// This is cheaper and also inverts adjusted matrices, e.g. of
+    /// [`luminance_preserving()`](Self::luminance_preserving) conversions.
+    pub fn inverse(&self) -> Self {
+        let (src, dst) = (self.dst_space, self.src_space);
+        Self {
+            src_space: src,
+            dst_space: dst,
+            src_transform: if src.is_linear() {
+                None
+            } else {
+                ColorTransform::with_white_point(src.transform_function(), None, src.white_point())
+            },
+            linear_transform: self.linear_transform.map(|linear| linear.inverse()),
+            dst_transform: if dst.is_linear() {
+                None
+            } else {
+                ColorTransform::with_white_point(None, dst.transform_function(), dst.white_point())
+            },
+        }
+    }
+
+    /// Fuses this conversion with `next` into a single conversion from the
+    /// source space of `self` to the destination space of `next`, with one
+    /// matrix multiply, see [`LinearColorConversion::then()`].
+    ///
+    /// Returns `None` if the destination space of `self` is not the source
+    /// space of `next`, or if it is non-linear, as encoding into it and
+    /// decoding from it again can't be collapsed, e.g. because the transform
+    /// function clamps.
+    pub fn then(&self, next: &ColorConversion) -> Option<Self> {
+        if self.dst_space != next.src_space || !self.dst_space.is_linear() {
+            return None;
+        }
+        let linear = self.linear_or_identity().then(&next.linear_or_identity());
+        Some(Self {
+            src_space: self.src_space,
+            dst_space: next.dst_space,
+            src_transform: self.src_transform,
+            linear_transform: (linear.mat != Mat3::IDENTITY || linear.offset.is_some())
+                .then_some(linear),
+            dst_transform: next.dst_transform,
+        })
+    }
+
+    /// Like [`linear_part()`](Self::linear_part), but between the linear
+    /// spaces of the source and destination space.
+    fn linear_or_identity(&self) -> LinearColorConversion {
+        self.linear_transform.unwrap_or_else(|| {
+            LinearColorConversion::from_matrix(
+                ColorSpace::linear(self.src_space.primaries(), self.src_space.white_point()),
+                ColorSpace::linear(self.dst_space.primaries(), self.dst_space.white_point()),
+                Mat3::IDENTITY,
+            )
+        })
     }
 
     pub fn is_linear(&self) -> bool {
@@ -162,10 +677,17 @@ impl ColorConversion {
                 input_space: self.src_space,
                 output_space: self.dst_space,
                 mat: Mat3::IDENTITY,
+                offset: None,
+                cat: CatMethod::default(),
             }
         }
     }
 
+    /// The linear part of the conversion, or `None` if it is the identity.
+    pub fn linear_transform(&self) -> Option<LinearColorConversion> {
+        self.linear_transform
+    }
+
     pub fn src_transform(&self) -> Option<ColorTransform> {
         self.src_transform
     }
@@ -188,6 +710,29 @@ impl ColorConversion {
         self.src_space
     }
 
+    /// Returns the steps of the conversion in the order they are applied,
+    /// e.g. to generate shader code for it.
+    ///
+    /// Identity matrices are skipped, as are the decoding and encoding steps
+    /// when both spaces are the same, so converting a space into itself has
+    /// no operations unless its luminance is scaled with
+    /// [`with_reference_luminance()`](Self::with_reference_luminance).
+    pub fn operations(&self) -> impl Iterator<Item = ConversionOp> {
+        let linear = self
+            .linear_transform
+            .map(|linear| ConversionOp::LinearMatrix(linear.mat));
+        let operations = if self.src_space == self.dst_space && linear.is_none() {
+            [None; 3]
+        } else {
+            [
+                self.src_transform_fn().map(ConversionOp::InverseTransform),
+                linear,
+                self.dst_transform_fn().map(ConversionOp::Transform),
+            ]
+        };
+        operations.into_iter().flatten()
+    }
+
     pub fn dst_space(&self) -> ColorSpace {
         self.dst_space
     }
@@ -197,6 +742,27 @@ impl ColorConversion {
         *color = self.convert(vec3).into();
     }
 
+    /// Converts the interleaved color components in `src`, e.g. `[r, g, b, r,
+    /// g, b, ...]`, into `dst` without allocating.
+    ///
+    /// Returns [`ColorError::MismatchedBufferLengths`] if the lengths of
+    /// `src` and `dst` differ and [`ColorError::IncompleteBuffer`] if they are
+    /// not a multiple of 3, without writing to `dst`.
+    pub fn convert_into_raw(&self, src: &[Float], dst: &mut [Float]) -> Result<(), ColorError> {
+        if src.len() != dst.len() {
+            return Err(ColorError::MismatchedBufferLengths(src.len(), dst.len()));
+        }
+        if !src.len().is_multiple_of(3) {
+            return Err(ColorError::IncompleteBuffer(src.len()));
+        }
+        for (src, dst) in src.chunks_exact(3).zip(dst.chunks_exact_mut(3)) {
+            let color: [Float; 3] = self.convert(Vec3::from_slice(src)).into();
+            dst.copy_from_slice(&color);
+        }
+        Ok(())
+    }
+
+    #[must_use]
     pub fn apply_src_transform(&self, color: Vec3) -> Vec3 {
         if let Some(src_transform) = self.src_transform.as_ref() {
             src_transform.apply(color, self.src_space.white_point())
@@ -205,6 +771,7 @@ impl ColorConversion {
         }
     }
 
+    #[must_use]
     pub fn apply_linear_part(&self, color: Vec3) -> Vec3 {
         if let Some(transform) = self.linear_transform.as_ref() {
             transform.convert(color)
@@ -213,6 +780,7 @@ impl ColorConversion {
         }
     }
 
+    #[must_use]
     pub fn apply_dst_transform(&self, color: Vec3) -> Vec3 {
         if let Some(dst_transform) = self.dst_transform.as_ref() {
             dst_transform.apply(color, self.dst_space.white_point())
@@ -221,10 +789,734 @@ impl ColorConversion {
         }
     }
 
+    /// Like [`convert()`](Self::convert), but validates that `color` is in
+    /// the encoded domain of the source transform function and that the value
+    /// passed to the destination transform function is in its linear domain.
+    ///
+    /// Returns [`ColorError::OutOfDomain`] for values outside the domains, see
+    /// [`TransformFn::linear_domain()`] and [`TransformFn::encoded_domain()`].
+    pub fn convert_strict(&self, mut color: Vec3) -> Result<Vec3, ColorError> {
+        if let Some(transform_fn) = self.src_transform_fn()
+            && let Some(domain) = transform_fn.encoded_domain()
+            && !domain.contains(color)
+        {
+            return Err(ColorError::OutOfDomain(transform_fn));
+        }
+        color = self.apply_src_transform(color);
+        color = self.apply_linear_part(color);
+        if let Some(transform_fn) = self.dst_transform_fn()
+            && let Some(domain) = transform_fn.linear_domain()
+            && !domain.contains(color)
+        {
+            return Err(ColorError::OutOfDomain(transform_fn));
+        }
+        Ok(self.apply_dst_transform(color))
+    }
+
+    /// Like [`convert()`](Self::convert), but checks the result of each stage
+    /// and returns a [`ConversionError`] naming the first one that produced a
+    /// NaN or infinite component, e.g. due to degenerate custom primaries.
+    ///
+    /// This is meant for development, as it is slower than `convert()`.
+    pub fn convert_checked(&self, color: Vec3) -> Result<Vec3, ConversionError> {
+        let check = |value: Vec3, stage| {
+            let components = [value.x, value.y, value.z];
+            if components.iter().any(|c| c.is_nan()) {
+                Err(ConversionError::Nan {
+                    stage,
+                    input: color,
+                })
+            } else if components.iter().any(|c| c.is_infinite()) {
+                Err(ConversionError::Infinite {
+                    stage,
+                    input: color,
+                })
+            } else {
+                Ok(value)
+            }
+        };
+        let value = check(color, ConversionStage::Input)?;
+        let value = check(
+            self.apply_src_transform(value),
+            ConversionStage::SrcTransform,
+        )?;
+        let value = check(
+            self.apply_linear_part(value),
+            ConversionStage::LinearTransform,
+        )?;
+        check(
+            self.apply_dst_transform(value),
+            ConversionStage::DstTransform,
+        )
+    }
+
+    #[must_use]
     pub fn convert(&self, mut color: Vec3) -> Vec3 {
         color = self.apply_src_transform(color);
         color = self.apply_linear_part(color);
         color = self.apply_dst_transform(color);
         color
     }
+
+    /// Converts all `values` in place, like calling [`convert()`](Self::convert)
+    /// on each of them.
+    ///
+    /// Each step of the conversion runs over the whole slice in turn, so the
+    /// steps which a conversion skips are only checked once, and converting
+    /// between linear spaces is a plain loop of matrix multiplications.
+    pub fn convert_slice(&self, values: &mut [Vec3]) {
+        if let Some(src_transform) = self.src_transform {
+            let white_point = self.src_space.white_point();
+            for value in values.iter_mut() {
+                *value = src_transform.apply(*value, white_point);
+            }
+        }
+        if let Some(linear_transform) = self.linear_transform {
+            for value in values.iter_mut() {
+                *value = linear_transform.convert(*value);
+            }
+        }
+        if let Some(dst_transform) = self.dst_transform {
+            let white_point = self.dst_space.white_point();
+            for value in values.iter_mut() {
+                *value = dst_transform.apply(*value, white_point);
+            }
+        }
+    }
+
+    /// Converts `src` into `dst` like [`convert_slice()`](Self::convert_slice).
+    ///
+    /// Returns [`ColorError::MismatchedBufferLengths`] if the lengths of `src`
+    /// and `dst` differ, without writing to `dst`.
+    pub fn convert_slice_copy(&self, src: &[Vec3], dst: &mut [Vec3]) -> Result<(), ColorError> {
+        if src.len() != dst.len() {
+            return Err(ColorError::MismatchedBufferLengths(src.len(), dst.len()));
+        }
+        dst.copy_from_slice(src);
+        self.convert_slice(dst);
+        Ok(())
+    }
+}
+
+/// Converts every color in `colors` to every space in `spaces`, returning one
+/// row per color with one converted color per target space.
+///
+/// Each distinct conversion is built only once and reused for all colors
+/// sharing the same source space, so `colors` may mix source spaces.
+#[cfg(feature = "std")]
+pub fn convert_matrix(colors: &[Color], spaces: &[ColorSpace]) -> Vec<Vec<Color>> {
+    let mut rows = vec![Vec::with_capacity(spaces.len()); colors.len()];
+    let mut conversions: Vec<ColorConversion> = Vec::new();
+    for &dst in spaces {
+        conversions.clear();
+        for (color, row) in colors.iter().zip(&mut rows) {
+            let conversion = match conversions.iter().find(|c| c.src_space() == color.space) {
+                Some(conversion) => conversion,
+                None => {
+                    conversions.push(ColorConversion::new(color.space, dst));
+                    conversions.last().unwrap()
+                }
+            };
+            row.push(Color {
+                value: conversion.convert(color.value),
+                space: dst,
+            });
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Color;
+    use crate::details::color::WhitePoint;
+    use crate::spaces;
+
+    #[test]
+    fn checked_conversions_report_non_finite_stages() {
+        let srgb = ColorConversion::new(spaces::ENCODED_SRGB, spaces::BT_2020);
+        let value = Vec3::new(0.2, 0.5, 0.8);
+        assert_eq!(srgb.convert_checked(value), Ok(srgb.convert(value)));
+
+        let nan = Vec3::new(Float::NAN, 0.5, 0.8);
+        let error = srgb.convert_checked(nan).unwrap_err();
+        assert!(matches!(
+            error,
+            ConversionError::Nan {
+                stage: ConversionStage::Input,
+                ..
+            }
+        ));
+        assert!(error.input().x.is_nan());
+
+        // Collinear primaries have no inverse matrix.
+        let degenerate = ColorSpace::linear(
+            RgbPrimaries::Custom([[0.2, 0.2], [0.4, 0.4], [0.6, 0.6]]),
+            super::super::color::WhitePoint::D65,
+        );
+        let error = ColorConversion::new(spaces::LINEAR_SRGB, degenerate)
+            .convert_checked(value)
+            .unwrap_err();
+        assert_eq!(error.stage(), ConversionStage::LinearTransform);
+        assert_eq!(error.input(), value);
+
+        let huge = ColorConversion::new(spaces::ENCODED_SRGB, spaces::LINEAR_SRGB)
+            .convert_checked(Vec3::new(Float::MAX, 0.0, 0.0));
+        assert!(matches!(
+            huge,
+            Err(ConversionError::Infinite {
+                stage: ConversionStage::SrcTransform,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn reference_luminance() {
+        let to_scene = ColorConversion::new(spaces::ENCODED_BT_2100_PQ, spaces::BT_2020)
+            .with_reference_luminance(1.0, 100.0);
+        let pq_100_nits = crate::details::transform::st_2084_pq_eotf_inverse_float(100.0);
+        let scene = to_scene.convert(Vec3::splat(pq_100_nits));
+        assert!(scene.abs_diff_eq(Vec3::ONE, 1e-3), "{scene:?}");
+
+        let to_pq = to_scene.inverse();
+        let encoded = to_pq.convert(Vec3::splat(1.0));
+        assert!(
+            encoded.abs_diff_eq(Vec3::splat(pq_100_nits), 1e-4),
+            "{encoded:?}"
+        );
+
+        // Also scales conversions with a matrix, and in `convert_slice()`.
+        let sdr_to_hdr = ColorConversion::new(spaces::LINEAR_SRGB, spaces::BT_2020)
+            .with_reference_luminance(100.0, 10000.0);
+        let expected = ColorConversion::new(spaces::LINEAR_SRGB, spaces::BT_2020)
+            .convert(Vec3::new(0.2, 0.4, 0.6))
+            * 0.01;
+        let mut values = [Vec3::new(0.2, 0.4, 0.6)];
+        sdr_to_hdr.convert_slice(&mut values);
+        assert!(values[0].abs_diff_eq(expected, 1e-6), "{values:?}");
+
+        let unchanged = ColorConversion::new(spaces::LINEAR_SRGB, spaces::LINEAR_SRGB)
+            .with_reference_luminance(100.0, 100.0);
+        assert!(unchanged.linear_transform().is_none());
+        let scaled = ColorConversion::new(spaces::LINEAR_SRGB, spaces::LINEAR_SRGB)
+            .with_reference_luminance(100.0, 200.0);
+        let mut operations = scaled.operations();
+        let half = Mat3::from_diagonal(Vec3::splat(0.5));
+        assert_eq!(operations.next(), Some(ConversionOp::LinearMatrix(half)));
+        assert_eq!(operations.next(), None);
+    }
+
+    #[test]
+    fn convert_slice_matches_convert() {
+        let values = [
+            Vec3::new(0.1, 0.2, 0.3),
+            Vec3::new(1.0, 0.5, 0.0),
+            Vec3::new(0.9, 0.9, 0.9),
+        ];
+        for (src, dst) in [
+            (spaces::ENCODED_SRGB, spaces::OK_LCH),
+            (spaces::LINEAR_SRGB, spaces::ACES_CG),
+            (spaces::ACES_CCT, spaces::ACES_CCT),
+        ] {
+            let conversion = ColorConversion::new(src, dst);
+            let mut converted = values;
+            conversion.convert_slice(&mut converted);
+            let mut copied = [Vec3::splat(0.0); 3];
+            conversion.convert_slice_copy(&values, &mut copied).unwrap();
+            for ((value, converted), copied) in values.iter().zip(converted).zip(copied) {
+                let expected = conversion.convert(*value);
+                assert!(
+                    converted.abs_diff_eq(expected, 1e-6),
+                    "{converted:?} != {expected:?}"
+                );
+                assert_eq!(copied, converted);
+            }
+        }
+        let conversion = ColorConversion::new(spaces::LINEAR_SRGB, spaces::ACES_CG);
+        assert_eq!(
+            conversion.convert_slice_copy(&values, &mut [Vec3::splat(0.0); 2]),
+            Err(ColorError::MismatchedBufferLengths(3, 2))
+        );
+    }
+
+    #[test]
+    fn operations_in_application_order() {
+        let conversion = ColorConversion::new(spaces::ENCODED_SRGB, spaces::OK_LAB);
+        let mut operations = conversion.operations();
+        assert_eq!(
+            operations.next(),
+            Some(ConversionOp::InverseTransform(TransformFn::Srgb))
+        );
+        assert_eq!(
+            operations.next(),
+            Some(ConversionOp::LinearMatrix(
+                conversion.linear_part().matrix()
+            ))
+        );
+        assert_eq!(
+            operations.next(),
+            Some(ConversionOp::Transform(TransformFn::OkLab))
+        );
+        assert_eq!(operations.next(), None);
+
+        let decode = ColorConversion::new(spaces::ENCODED_SRGB, spaces::BT_709);
+        let mut operations = decode.operations();
+        assert_eq!(
+            operations.next(),
+            Some(ConversionOp::InverseTransform(TransformFn::Srgb))
+        );
+        assert_eq!(operations.next(), None);
+
+        let identity = ColorConversion::new(spaces::ENCODED_SRGB, spaces::ENCODED_SRGB);
+        assert_eq!(identity.operations().count(), 0);
+    }
+
+    #[test]
+    fn decompose_identity() {
+        let conversion = LinearColorConversion::new(spaces::LINEAR_SRGB, spaces::LINEAR_SRGB);
+        let decomposition = conversion.decompose();
+        assert!((decomposition.determinant - 1.0).abs() < 1e-6);
+        assert!(decomposition.is_diagonal(1e-6));
+        assert!(
+            decomposition
+                .scale
+                .abs_diff_eq(Vec3::new(1.0, 1.0, 1.0), 1e-6)
+        );
+    }
+
+    #[test]
+    fn decompose_srgb_to_xyz() {
+        let conversion = LinearColorConversion::new(spaces::LINEAR_SRGB, spaces::CIE_XYZ);
+        let decomposition = conversion.decompose();
+        let expected = 0.2070726;
+        assert!(
+            (decomposition.determinant - expected).abs() < 1e-5,
+            "{} != {expected}",
+            decomposition.determinant
+        );
+        assert!(!decomposition.is_diagonal(1e-3));
+    }
+
+    #[test]
+    fn then_cancels_round_trip_adaptation() {
+        let to_d50 = LinearColorConversion::new(spaces::LINEAR_SRGB, spaces::PRO_PHOTO);
+        let to_d65 = LinearColorConversion::new(spaces::PRO_PHOTO, spaces::LINEAR_SRGB);
+        let round_trip = to_d50.then(&to_d65);
+        assert_eq!(round_trip.input_space(), spaces::LINEAR_SRGB);
+        assert_eq!(round_trip.output_space(), spaces::LINEAR_SRGB);
+        assert!(
+            round_trip.matrix().abs_diff_eq(Mat3::IDENTITY, 1e-6),
+            "{:?}",
+            round_trip.matrix()
+        );
+    }
+
+    #[test]
+    fn then_keeps_adjusted_matrices() {
+        let to_aces = ColorConversion::luminance_preserving(spaces::LINEAR_SRGB, spaces::ACES_CG)
+            .linear_part();
+        let back = LinearColorConversion::new(spaces::ACES_CG, spaces::LINEAR_SRGB);
+        let round_trip = to_aces.then(&back);
+        assert_eq!(round_trip.matrix(), back.matrix() * to_aces.matrix());
+        assert_ne!(round_trip.matrix(), Mat3::IDENTITY);
+
+        let nudged = LinearColorConversion::from_matrix(
+            spaces::LINEAR_SRGB,
+            spaces::LINEAR_SRGB,
+            Mat3::from_cols(
+                Vec3::new(1.00001, 0.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+                Vec3::new(0.0, 0.0, 1.0),
+            ),
+        );
+        let identity = LinearColorConversion::new(spaces::LINEAR_SRGB, spaces::LINEAR_SRGB);
+        assert_eq!(nudged.then(&identity).matrix(), nudged.matrix());
+    }
+
+    #[test]
+    fn then_merges_stacked_adaptations() {
+        let to_d50 = LinearColorConversion::new(spaces::LINEAR_SRGB, spaces::PRO_PHOTO);
+        let to_d60 = LinearColorConversion::new(spaces::PRO_PHOTO, spaces::ACES_CG);
+        let direct = LinearColorConversion::new(spaces::LINEAR_SRGB, spaces::ACES_CG);
+        assert_eq!(to_d50.then(&to_d60), direct);
+    }
+
+    #[test]
+    fn unbounded_rejects_clamping_transforms() {
+        assert_eq!(
+            ColorConversion::unbounded(spaces::ACES_CG, spaces::ENCODED_BT_2100_PQ),
+            Err(ColorError::ClampingTransform(TransformFn::Pq))
+        );
+        let conversion = ColorConversion::unbounded(spaces::ACES_CG, spaces::LINEAR_SRGB).unwrap();
+        let hdr = Vec3::new(12.0, -0.5, 3.0);
+        let round_trip = conversion.invert().convert(conversion.convert(hdr));
+        assert!(
+            round_trip.abs_diff_eq(hdr, 1e-4),
+            "{round_trip:?} != {hdr:?}"
+        );
+    }
+
+    #[test]
+    fn convert_strict_validates_domains() {
+        let to_pq = ColorConversion::new(spaces::BT_2020, spaces::ENCODED_BT_2100_PQ);
+        let nits = Vec3::new(100.0, 250.0, 0.0);
+        let encoded = to_pq.convert_strict(nits).unwrap();
+        assert!(encoded.abs_diff_eq(to_pq.convert(nits), 1e-6));
+        assert_eq!(
+            to_pq.convert_strict(Vec3::new(100.0, -1.0, 0.0)),
+            Err(ColorError::OutOfDomain(TransformFn::Pq))
+        );
+
+        let from_pq = to_pq.invert();
+        assert!(from_pq.convert_strict(encoded).is_ok());
+        assert_eq!(
+            from_pq.convert_strict(Vec3::new(0.5, 1.5, 0.5)),
+            Err(ColorError::OutOfDomain(TransformFn::Pq))
+        );
+
+        let unrestricted = ColorConversion::new(spaces::LINEAR_SRGB, spaces::ENCODED_SRGB);
+        assert!(
+            unrestricted
+                .convert_strict(Vec3::new(-1.0, 2.0, 0.5))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn luminance_preserving_conversion() {
+        let luminance = |space: ColorSpace, rgb: Vec3| luminance_row(space).dot(rgb);
+        for dst in [spaces::BT_2020, spaces::ACES_CG, spaces::PRO_PHOTO] {
+            let conversion = ColorConversion::luminance_preserving(spaces::ENCODED_SRGB, dst);
+            for srgb in [
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(0.2, 0.7, 0.4),
+                Vec3::new(0.05, 0.1, 0.9),
+            ] {
+                let linear =
+                    ColorConversion::new(spaces::ENCODED_SRGB, spaces::LINEAR_SRGB).convert(srgb);
+                let expected = luminance(spaces::LINEAR_SRGB, linear);
+                let actual = luminance(dst, conversion.convert(srgb));
+                assert!((actual - expected).abs() < 1e-7, "{actual} != {expected}");
+            }
+            let white = conversion.convert(Vec3::splat(1.0));
+            assert!(white.abs_diff_eq(Vec3::splat(1.0), 1e-6), "{white:?}");
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "tracing", feature = "std"))]
+    fn on_demand_conversion_emits_event() {
+        use std::sync::{Arc, Mutex};
+        use tracing::{
+            Event, Metadata, Subscriber,
+            field::{Field, Visit},
+            span,
+        };
+
+        #[derive(Default)]
+        struct Messages(Arc<Mutex<Vec<String>>>);
+
+        impl Visit for &Messages {
+            fn record_debug(&mut self, field: &Field, value: &dyn core::fmt::Debug) {
+                if field.name() == "message" {
+                    self.0.lock().unwrap().push(format!("{value:?}"));
+                }
+            }
+        }
+
+        impl Subscriber for Messages {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+                span::Id::from_u64(1)
+            }
+            fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+            fn event(&self, event: &Event<'_>) {
+                event.record(&mut &*self);
+            }
+            fn enter(&self, _: &span::Id) {}
+            fn exit(&self, _: &span::Id) {}
+        }
+
+        let subscriber = Messages::default();
+        let messages = subscriber.0.clone();
+        let custom = ColorSpace::linear(
+            RgbPrimaries::from_rgb_xy([0.7, 0.3], [0.2, 0.7], [0.1, 0.1]),
+            super::super::color::WhitePoint::D65,
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            LinearColorConversion::new(spaces::LINEAR_SRGB, custom);
+        });
+        assert_eq!(
+            *messages.lock().unwrap(),
+            ["computing conversion matrix on demand"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn convert_matrix_matches_individual_conversions() {
+        let colors = [
+            Color::srgb(0.8, 0.2, 0.1),
+            Color::new(0.5, 0.3, 0.9, spaces::ACES_CG),
+            Color::srgb(0.1, 0.6, 0.4),
+        ];
+        let targets = [spaces::OK_LAB, spaces::ENCODED_DISPLAY_P3];
+        let rows = convert_matrix(&colors, &targets);
+        assert_eq!(rows.len(), colors.len());
+        for (color, row) in colors.iter().zip(&rows) {
+            assert_eq!(row.len(), targets.len());
+            for (converted, &target) in row.iter().zip(&targets) {
+                let expected = color.to(target);
+                assert_eq!(converted.space, target);
+                assert!(
+                    converted.value.abs_diff_eq(expected.value, 1e-6),
+                    "{:?} != {:?}",
+                    converted.value,
+                    expected.value
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn matrix_f64_keeps_adjusted_matrices() {
+        let scaled = ColorConversion::new(spaces::LINEAR_SRGB, spaces::BT_2020)
+            .with_reference_luminance(100.0, 203.0)
+            .linear_part();
+        let ycbcr = LinearColorConversion::from_matrix(
+            spaces::LINEAR_SRGB,
+            spaces::LINEAR_SRGB,
+            Mat3::from_cols_array(&[0.5, 0.1, 0.0, 0.2, 0.6, 0.0, 0.0, 0.0, 0.7]),
+        );
+        for linear in [scaled, ycbcr] {
+            let matrix = linear.matrix_f64();
+            let mat = linear.matrix();
+            for (col, expected) in matrix.iter().zip([mat.x_axis, mat.y_axis, mat.z_axis]) {
+                assert_eq!(*col, widen(&expected.into()), "{matrix:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn matrix_f64_matches_published_srgb() {
+        // http://www.brucelindbloom.com/index.html?Eqn_RGB_XYZ_Matrix.html
+        #[rustfmt::skip]
+        let published = [
+            [0.4124564, 0.2126729, 0.0193339],
+            [0.3575761, 0.7151522, 0.1191920],
+            [0.1804375, 0.0721750, 0.9503041],
+        ];
+        let matrix = LinearColorConversion::new(spaces::LINEAR_SRGB, spaces::CIE_XYZ).matrix_f64();
+        for (col, published) in matrix.iter().zip(published) {
+            for (value, published) in col.iter().zip(published) {
+                assert!((value - published).abs() < 1e-7, "{matrix:?}");
+            }
+        }
+
+        let adapted = LinearColorConversion::new(spaces::LINEAR_SRGB, spaces::ACES_CG);
+        let matrix = adapted.matrix_f64();
+        let mat = adapted.matrix();
+        for (col, expected) in matrix.iter().zip([mat.x_axis, mat.y_axis, mat.z_axis]) {
+            let expected = widen(&[expected.x, expected.y, expected.z]);
+            for (value, expected) in col.iter().zip(expected) {
+                assert!((value - expected).abs() < 1e-4, "{matrix:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_round_trips() {
+        let value = Vec3::new(0.8, 0.3, 0.1);
+        for (src, dst) in [
+            (spaces::LINEAR_SRGB, spaces::ACES_CG),
+            (spaces::ENCODED_SRGB, spaces::ACES_CCT),
+            (spaces::ENCODED_DISPLAY_P3, spaces::OK_LAB),
+            (spaces::ENCODED_SRGB, spaces::ENCODED_SRGB),
+        ] {
+            let conversion = ColorConversion::new(src, dst);
+            let inverse = conversion.inverse();
+            assert_eq!(inverse.src_space(), dst);
+            assert_eq!(inverse.dst_space(), src);
+            let round_trip = inverse.convert(conversion.convert(value));
+            assert!(round_trip.abs_diff_eq(value, 1e-5), "{src:?} -> {dst:?}");
+            let converted = conversion.convert(value);
+            assert!(
+                inverse
+                    .convert(converted)
+                    .abs_diff_eq(conversion.invert().convert(converted), 1e-5)
+            );
+        }
+
+        let preserving =
+            ColorConversion::luminance_preserving(spaces::LINEAR_SRGB, spaces::PRO_PHOTO);
+        let round_trip = preserving.inverse().convert(preserving.convert(value));
+        assert!(round_trip.abs_diff_eq(value, 1e-5), "{round_trip:?}");
+    }
+
+    #[test]
+    fn limited_range_ycbcr_offset() {
+        // BT.709 R'G'B' to 8-bit limited-range Y'CbCr, normalized to [0, 1].
+        let (kr, kb) = (0.2126, 0.0722);
+        let kg = 1.0 - kr - kb;
+        let (luma, chroma) = (219.0 / 255.0, 224.0 / 255.0);
+        let cb = chroma / (2.0 * (1.0 - kb));
+        let cr = chroma / (2.0 * (1.0 - kr));
+        let mat = Mat3::from_cols(
+            Vec3::new(luma * kr, -cb * kr, cr * (1.0 - kr)),
+            Vec3::new(luma * kg, -cb * kg, -cr * kg),
+            Vec3::new(luma * kb, cb * (1.0 - kb), -cr * kb),
+        );
+        let full_range = LinearColorConversion::from_matrix(spaces::BT_709, spaces::BT_709, mat);
+        assert_eq!(full_range.offset(), None);
+        let offset = Vec3::new(16.0, 128.0, 128.0) / 255.0;
+        let limited = full_range.with_offset(offset);
+        assert_eq!(limited.offset(), Some(offset));
+
+        let black = limited.convert(Vec3::ZERO);
+        assert!(black.abs_diff_eq(offset, 1e-6), "{black:?}");
+        let white = limited.convert(Vec3::ONE);
+        let expected = Vec3::new(235.0, 128.0, 128.0) / 255.0;
+        assert!(
+            white.abs_diff_eq(expected, 1e-5),
+            "{white:?} != {expected:?}"
+        );
+        let red = limited.convert(Vec3::new(1.0, 0.0, 0.0)) * 255.0;
+        assert!(
+            red.abs_diff_eq(Vec3::new(63.0, 102.0, 240.0), 0.5),
+            "{red:?}"
+        );
+
+        let value = Vec3::new(0.8, 0.3, 0.1);
+        let round_trip = limited.inverse().convert(limited.convert(value));
+        assert!(round_trip.abs_diff_eq(value, 1e-5), "{round_trip:?}");
+        let twice = limited.then(&limited.inverse());
+        assert!(twice.convert(value).abs_diff_eq(value, 1e-5));
+        assert!(twice.offset().unwrap().abs_diff_eq(Vec3::ZERO, 1e-6));
+        assert_eq!(full_range.with_offset(Vec3::ZERO).offset(), None);
+    }
+
+    #[cfg(feature = "color-matrices")]
+    #[test]
+    fn const_conversion_matrix_macro() {
+        const SRGB_TO_ACES_CG: Mat3 = crate::const_conversion_matrix!(
+            RgbPrimaries::Bt709,
+            WhitePoint::D65,
+            RgbPrimaries::AcesAp1,
+            WhitePoint::D60,
+        );
+        let conversion = LinearColorConversion::new(spaces::LINEAR_SRGB, spaces::ACES_CG);
+        assert_eq!(SRGB_TO_ACES_CG, conversion.matrix());
+        const IDENTITY: Mat3 = crate::const_conversion_matrix!(
+            RgbPrimaries::Bt709,
+            WhitePoint::D65,
+            RgbPrimaries::Bt709,
+            WhitePoint::D65,
+        );
+        assert_eq!(IDENTITY, Mat3::IDENTITY);
+        let identity = LinearColorConversion::new(spaces::LINEAR_SRGB, spaces::LINEAR_SRGB);
+        assert_eq!(identity.matrix(), Mat3::IDENTITY);
+    }
+
+    #[test]
+    fn conversion_with_cat() {
+        let d50 = spaces::LINEAR_SRGB.with_whitepoint(WhitePoint::D50);
+        let sharp = ColorConversion::new(spaces::LINEAR_SRGB, d50);
+        let bradford = ColorConversion::with_cat(spaces::LINEAR_SRGB, d50, CatMethod::Bradford);
+        let red = Vec3::new(1.0, 0.0, 0.0);
+        assert!(!sharp.convert(red).abs_diff_eq(bradford.convert(red), 1e-4));
+        assert!(bradford.convert(Vec3::ONE).abs_diff_eq(Vec3::ONE, 1e-5));
+        let back = bradford.invert();
+        assert_eq!(back.linear_part().cat(), CatMethod::Bradford);
+        assert!(back.convert(bradford.convert(red)).abs_diff_eq(red, 1e-5));
+
+        let linear = bradford.linear_part();
+        let matrix = linear.matrix_f64();
+        let mat = linear.matrix();
+        for (col, expected) in matrix.iter().zip([mat.x_axis, mat.y_axis, mat.z_axis]) {
+            let expected = widen(&[expected.x, expected.y, expected.z]);
+            for (value, expected) in col.iter().zip(expected) {
+                assert!((value - expected).abs() < 1e-5, "{matrix:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn convert_into_raw_matches_color_conversion() {
+        let conversion = ColorConversion::new(spaces::ENCODED_SRGB, spaces::OK_LAB);
+        let src = [0.8, 0.2, 0.1, 0.0, 0.5, 1.0];
+        let mut dst = [0.0; 6];
+        conversion.convert_into_raw(&src, &mut dst).unwrap();
+        for (src, dst) in src.chunks_exact(3).zip(dst.chunks_exact(3)) {
+            let expected = Color::srgb(src[0], src[1], src[2]).to(spaces::OK_LAB).value;
+            assert!(Vec3::from_slice(dst).abs_diff_eq(expected, 1e-6), "{dst:?}");
+        }
+
+        assert_eq!(
+            conversion.convert_into_raw(&src, &mut [0.0; 3]),
+            Err(ColorError::MismatchedBufferLengths(6, 3))
+        );
+        assert_eq!(
+            conversion.convert_into_raw(&[0.0; 4], &mut [0.0; 4]),
+            Err(ColorError::IncompleteBuffer(4))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn then_requires_matching_junction() {
+        let a = LinearColorConversion::new(spaces::LINEAR_SRGB, spaces::PRO_PHOTO);
+        let b = LinearColorConversion::new(spaces::ACES_CG, spaces::LINEAR_SRGB);
+        a.then(&b);
+    }
+
+    #[test]
+    fn conversions_compare_their_linear_transform() {
+        let (src, dst) = (spaces::ENCODED_SRGB, spaces::ACES_CG);
+        let conversion = ColorConversion::new(src, dst);
+        assert_eq!(conversion, ColorConversion::new(src, dst));
+        assert_ne!(conversion, ColorConversion::luminance_preserving(src, dst));
+        assert_ne!(
+            conversion,
+            ColorConversion::with_cat(src, dst, CatMethod::Bradford)
+        );
+        assert_ne!(
+            conversion,
+            conversion.with_reference_luminance(100.0, 203.0)
+        );
+    }
+
+    #[test]
+    fn fused_color_conversions() {
+        let to_linear = ColorConversion::new(spaces::ENCODED_SRGB, spaces::ACES_CG);
+        let to_encoded = ColorConversion::new(spaces::ACES_CG, spaces::ENCODED_BT_2020);
+        let fused = to_linear.then(&to_encoded).unwrap();
+        let direct = ColorConversion::new(spaces::ENCODED_SRGB, spaces::ENCODED_BT_2020);
+        assert_eq!(fused.src_space(), direct.src_space());
+        assert_eq!(fused.dst_space(), direct.dst_space());
+        let (mat, expected) = (fused.linear_part().mat, direct.linear_part().mat);
+        assert!(mat.abs_diff_eq(expected, 1e-5), "{mat:?} != {expected:?}");
+        for value in [Vec3::new(0.8, 0.2, 0.1), Vec3::new(0.05, 0.5, 1.0)] {
+            let expected = to_encoded.convert(to_linear.convert(value));
+            let fused_value = fused.convert(value);
+            assert!(
+                fused_value.abs_diff_eq(expected, 1e-5),
+                "{fused_value:?} != {expected:?}"
+            );
+        }
+
+        // Round trips through a linear space collapse to nothing.
+        let there = ColorConversion::new(spaces::ENCODED_SRGB, spaces::LINEAR_SRGB);
+        let round_trip = there.then(&there.inverse()).unwrap();
+        assert_eq!(round_trip.operations().next(), None);
+
+        // A non-linear junction or mismatched spaces don't fuse.
+        let encode = ColorConversion::new(spaces::LINEAR_SRGB, spaces::ENCODED_SRGB);
+        assert!(encode.then(&encode.inverse()).is_none());
+        assert!(to_linear.then(&to_linear).is_none());
+    }
 }
@@ -0,0 +1,311 @@
+//! Parsing and emitting of CSS Color 4 functional notations.
+//!
+//! Supported are `oklab()`, `oklch()`, `lab()`, `lch()` and `color()` with the
+//! `srgb`, `srgb-linear`, `display-p3`, `xyz-d65` and `xyz-d50` spaces, and
+//! parsing `hwb()`. CSS
+//! defines `lab()` and `lch()` relative to a D50 white point, see
+//! [`CSS_LAB`] and [`CSS_LCH`].
+use super::{
+    color::{Color, ColorError, ColorSpace, RgbPrimaries, TransformFn, WhitePoint},
+    transform::hwb_to_rgb,
+};
+use crate::{Float, Vec3, spaces};
+
+/// CIELAB with a D50 white point, as used by CSS `lab()`.
+pub const CSS_LAB: ColorSpace = ColorSpace::new(
+    RgbPrimaries::CieXyz,
+    WhitePoint::D50,
+    Some(TransformFn::CieLab),
+);
+
+/// CIELCh with a D50 white point, as used by CSS `lch()`.
+pub const CSS_LCH: ColorSpace = ColorSpace::new(
+    RgbPrimaries::CieXyz,
+    WhitePoint::D50,
+    Some(TransformFn::CieLch),
+);
+
+/// CIE XYZ with a D50 white point, as used by CSS `color(xyz-d50 ...)`.
+pub const CSS_XYZ_D50: ColorSpace = ColorSpace::new(RgbPrimaries::CieXyz, WhitePoint::D50, None);
+
+/// The spaces supported by CSS `color()`, by their CSS name.
+const CSS_COLOR_SPACES: [(&str, ColorSpace); 5] = [
+    ("srgb", spaces::ENCODED_SRGB),
+    ("srgb-linear", spaces::LINEAR_SRGB),
+    ("display-p3", spaces::ENCODED_DISPLAY_P3),
+    ("xyz-d65", spaces::CIE_XYZ),
+    ("xyz-d50", CSS_XYZ_D50),
+];
+
+/// Parses a component which is a number or a percentage of `percent_scale`.
+/// `none` marks a powerless component and is treated as zero.
+fn parse_number(token: &str, percent_scale: Float) -> Result<Float, ColorError> {
+    if token.eq_ignore_ascii_case("none") {
+        return Ok(0.0);
+    }
+    let (number, scale) = match token.strip_suffix('%') {
+        Some(number) => (number, percent_scale / 100.0),
+        None => (token, 1.0),
+    };
+    let number: Float = number.parse().map_err(|_| ColorError::InvalidCss)?;
+    Ok(number * scale)
+}
+
+/// Parses a hue angle into degrees.
+fn parse_hue(token: &str) -> Result<Float, ColorError> {
+    if token.eq_ignore_ascii_case("none") {
+        return Ok(0.0);
+    }
+    let units: [(&str, Float); 4] = [
+        ("deg", 1.0),
+        ("grad", 0.9),
+        ("rad", 180.0 / crate::PI),
+        ("turn", 360.0),
+    ];
+    let (number, scale) = units
+        .iter()
+        .find_map(|(unit, scale)| token.strip_suffix(unit).map(|number| (number, *scale)))
+        .unwrap_or((token, 1.0));
+    let number: Float = number.parse().map_err(|_| ColorError::InvalidCss)?;
+    Ok(number * scale)
+}
+
+impl Color {
+    /// Parses a CSS Color 4 functional notation, see the
+    /// [`css`](crate::details::css) module for the supported notations.
+    ///
+    /// Percentages are resolved against the reference ranges of the CSS
+    /// specification, e.g. `50%` lightness in `lab()` is `50`, in `oklab()` it
+    /// is `0.5`. The `none` keyword is treated as zero. Alpha (`/ alpha`) is
+    /// accepted but discarded, as [`Color`] has no alpha.
+    pub fn from_css(css: &str) -> Result<Color, ColorError> {
+        let css = css.trim();
+        let (name, arguments) = css
+            .strip_suffix(')')
+            .and_then(|css| css.split_once('('))
+            .ok_or(ColorError::InvalidCss)?;
+        let components = match arguments.split_once('/') {
+            Some((components, alpha)) => {
+                parse_number(alpha.trim(), 1.0)?;
+                components
+            }
+            None => arguments,
+        };
+        let mut tokens = components.split_whitespace();
+        let space = if name.trim().eq_ignore_ascii_case("color") {
+            let space_name = tokens.next().ok_or(ColorError::InvalidCss)?;
+            let (_, space) = CSS_COLOR_SPACES
+                .iter()
+                .find(|(css_name, _)| css_name.eq_ignore_ascii_case(space_name))
+                .ok_or(ColorError::InvalidCss)?;
+            Some(*space)
+        } else {
+            None
+        };
+        let mut next = || tokens.next().ok_or(ColorError::InvalidCss);
+        let (a, b, c) = (next()?, next()?, next()?);
+        if tokens.next().is_some() {
+            return Err(ColorError::InvalidCss);
+        }
+
+        if let Some(space) = space {
+            return Ok(Color::new(
+                parse_number(a, 1.0)?,
+                parse_number(b, 1.0)?,
+                parse_number(c, 1.0)?,
+                space,
+            ));
+        }
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("oklab") {
+            Ok(Color::new(
+                parse_number(a, 1.0)?,
+                parse_number(b, 0.4)?,
+                parse_number(c, 0.4)?,
+                spaces::OK_LAB,
+            ))
+        } else if name.eq_ignore_ascii_case("oklch") {
+            Ok(Color::new(
+                parse_number(a, 1.0)?,
+                parse_number(b, 0.4)?,
+                parse_hue(c)?.to_radians(),
+                spaces::OK_LCH,
+            ))
+        } else if name.eq_ignore_ascii_case("lab") {
+            Ok(Color::new(
+                parse_number(a, 100.0)?,
+                parse_number(b, 125.0)?,
+                parse_number(c, 125.0)?,
+                CSS_LAB,
+            ))
+        } else if name.eq_ignore_ascii_case("lch") {
+            Ok(Color::new(
+                parse_number(a, 100.0)?,
+                parse_number(b, 150.0)?,
+                parse_hue(c)?,
+                CSS_LCH,
+            ))
+        } else if name.eq_ignore_ascii_case("hwb") {
+            // Whiteness and blackness are percentages, or numbers in [0, 100].
+            let hwb = Vec3::new(
+                parse_hue(a)?,
+                parse_number(b, 100.0)? / 100.0,
+                parse_number(c, 100.0)? / 100.0,
+            );
+            Ok(Color {
+                value: hwb_to_rgb(hwb, WhitePoint::D65),
+                space: spaces::ENCODED_SRGB,
+            })
+        } else {
+            Err(ColorError::InvalidCss)
+        }
+    }
+
+    /// Emits the color in the CSS notation matching its space.
+    ///
+    /// Colors in [`OK_LAB`](spaces::OK_LAB), [`OK_LCH`](spaces::OK_LCH),
+    /// [`CSS_LAB`] and [`CSS_LCH`] use `oklab()`, `oklch()`, `lab()` and
+    /// `lch()`, colors in one of the spaces supported by `color()` use that.
+    /// Colors in any other space are converted to Oklab.
+    #[cfg(feature = "std")]
+    pub fn to_css(&self) -> String {
+        let [x, y, z] = <[Float; 3]>::from(self.value);
+        if self.space == spaces::OK_LAB {
+            format!(
+                "oklab({} {} {})",
+                css_number(x),
+                css_number(y),
+                css_number(z)
+            )
+        } else if self.space == spaces::OK_LCH {
+            let hue = z.to_degrees().rem_euclid(360.0);
+            format!(
+                "oklch({} {} {})",
+                css_number(x),
+                css_number(y),
+                css_number(hue)
+            )
+        } else if self.space == CSS_LAB {
+            format!("lab({} {} {})", css_number(x), css_number(y), css_number(z))
+        } else if self.space == CSS_LCH {
+            let hue = z.rem_euclid(360.0);
+            format!(
+                "lch({} {} {})",
+                css_number(x),
+                css_number(y),
+                css_number(hue)
+            )
+        } else if let Some((name, _)) = CSS_COLOR_SPACES
+            .iter()
+            .find(|(_, space)| *space == self.space)
+        {
+            format!(
+                "color({name} {} {} {})",
+                css_number(x),
+                css_number(y),
+                css_number(z)
+            )
+        } else {
+            self.to(spaces::OK_LAB).to_css()
+        }
+    }
+}
+
+/// The CSS name of `space` for interpolating in it, e.g. in
+/// `linear-gradient(in oklch, ...)`, if CSS supports it.
+#[cfg(feature = "std")]
+pub(crate) fn css_interpolation_space(space: ColorSpace) -> Option<&'static str> {
+    [
+        ("oklab", spaces::OK_LAB),
+        ("oklch", spaces::OK_LCH),
+        ("lab", CSS_LAB),
+        ("lch", CSS_LCH),
+    ]
+    .into_iter()
+    .chain(CSS_COLOR_SPACES)
+    .find(|(_, css_space)| *css_space == space)
+    .map(|(name, _)| name)
+}
+
+/// Formats `value` with at most five decimals, without trailing zeros.
+#[cfg(feature = "std")]
+pub(crate) fn css_number(value: Float) -> String {
+    let formatted = format!("{value:.5}");
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+    if trimmed == "-0" {
+        "0".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Vec3;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn oklch_round_trip() {
+        let color = Color::from_css("oklch(0.7 0.15 30)").unwrap();
+        assert_eq!(color.space, spaces::OK_LCH);
+        assert!((color.value.z - 30.0_f64.to_radians() as Float).abs() < 1e-6);
+        assert_eq!(color.to_css(), "oklch(0.7 0.15 30)");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn lab_with_percentage_lightness() {
+        let color = Color::from_css("lab(50% 40 59.5)").unwrap();
+        assert_eq!(color.space, CSS_LAB);
+        assert!(color.value.abs_diff_eq(Vec3::new(50.0, 40.0, 59.5), 1e-6));
+        assert_eq!(color.to_css(), "lab(50 40 59.5)");
+
+        let lch = color.to(CSS_LCH);
+        let expected = Color::from_css("lch(50 71.7 56.09)").unwrap();
+        assert!(lch.value.abs_diff_eq(expected.value, 1e-2), "{lch:?}");
+    }
+
+    #[test]
+    fn powerless_components_and_units() {
+        let color = Color::from_css("OKLCH(50% none none / 0.5)").unwrap();
+        assert!(color.value.abs_diff_eq(Vec3::new(0.5, 0.0, 0.0), 1e-6));
+
+        let turn = Color::from_css("lch(50 10 0.5turn)").unwrap();
+        assert!((turn.value.z - 180.0).abs() < 1e-4);
+
+        let oklab = Color::from_css("oklab(40% 100% -50%)").unwrap();
+        assert!(oklab.value.abs_diff_eq(Vec3::new(0.4, 0.4, -0.2), 1e-6));
+
+        let p3 = Color::from_css("color(display-p3 1 0.5 0)").unwrap();
+        assert_eq!(p3.space, spaces::ENCODED_DISPLAY_P3);
+    }
+
+    #[test]
+    fn hwb() {
+        let orange = Color::from_css("hwb(30 20% 10%)").unwrap();
+        assert_eq!(orange.space, spaces::ENCODED_SRGB);
+        assert!(orange.value.abs_diff_eq(Vec3::new(0.9, 0.55, 0.2), 1e-6));
+        let numbers = Color::from_css("hwb(390deg 20 10)").unwrap();
+        assert!(numbers.value.abs_diff_eq(orange.value, 1e-5));
+        let gray = Color::from_css("hwb(120 60% 60%)").unwrap();
+        assert!(gray.value.abs_diff_eq(Vec3::splat(0.5), 1e-6));
+    }
+
+    #[test]
+    fn invalid_css() {
+        for css in [
+            "oklch(0.7 0.15)",
+            "oklch(0.7 0.15 30 1)",
+            "hsl(0 0% 0%)",
+            "lab(50% 40 59.5",
+            "color(rec2020 1 0 0)",
+            "oklab(a b c)",
+        ] {
+            assert_eq!(
+                Color::from_css(css).map(|c| c.value),
+                Err(ColorError::InvalidCss)
+            );
+        }
+    }
+}
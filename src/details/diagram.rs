@@ -0,0 +1,85 @@
+//! Coordinates of colors in chromaticity diagrams, e.g. to plot them.
+use super::color::Color;
+use crate::{Float, Vec3};
+
+/// A chromaticity diagram, see [`Color::to_diagram_coords()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagramType {
+    /// The CIE 1931 xy diagram.
+    Cie1931Xy,
+    /// The CIE 1960 uv diagram, used for correlated color temperatures.
+    Cie1960Uv,
+    /// The CIE 1976 u'v' diagram, which is more perceptually uniform than xy.
+    Cie1976Uv,
+}
+
+impl DiagramType {
+    /// Returns the coordinates of the CIE XYZ tristimulus values `xyz` in the
+    /// diagram, or `None` if they have no chromaticity, i.e. sum to 0.
+    pub fn coords(self, xyz: Vec3) -> Option<[Float; 2]> {
+        let [x, y, z] = [xyz.x, xyz.y, xyz.z];
+        let denominator = match self {
+            DiagramType::Cie1931Xy => x + y + z,
+            DiagramType::Cie1960Uv | DiagramType::Cie1976Uv => x + 15.0 * y + 3.0 * z,
+        };
+        if denominator == 0.0 {
+            return None;
+        }
+        Some(match self {
+            DiagramType::Cie1931Xy => [x / denominator, y / denominator],
+            DiagramType::Cie1960Uv => [4.0 * x / denominator, 6.0 * y / denominator],
+            DiagramType::Cie1976Uv => [4.0 * x / denominator, 9.0 * y / denominator],
+        })
+    }
+}
+
+impl Color {
+    /// Returns the chromaticity coordinates of the color in `diagram`,
+    /// relative to the white point of its space, i.e. without chromatic
+    /// adaptation.
+    ///
+    /// Black has no chromaticity and returns the coordinates of the white
+    /// point.
+    pub fn to_diagram_coords(&self, diagram: DiagramType) -> [Float; 2] {
+        let white_point = self.space().white_point().xyz();
+        diagram
+            .coords(self.xyz())
+            .or_else(|| diagram.coords(white_point))
+            .expect("white points have a chromaticity")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::details::color::{WhitePoint, color_spaces};
+
+    #[test]
+    fn srgb_primaries_in_xy_diagram() {
+        let expected = [[0.64, 0.33], [0.30, 0.60], [0.15, 0.06]];
+        for (primary, [x, y]) in color_spaces::LINEAR_SRGB
+            .primary_colors()
+            .iter()
+            .zip(expected)
+        {
+            let [px, py] = primary.to_diagram_coords(DiagramType::Cie1931Xy);
+            assert!((px - x).abs() < 1e-4 && (py - y).abs() < 1e-4, "{px} {py}");
+        }
+
+        let white = Color::srgb(1.0, 1.0, 1.0);
+        let [u, v] = white.to_diagram_coords(DiagramType::Cie1976Uv);
+        assert!(
+            (u - 0.1978).abs() < 1e-4 && (v - 0.4683).abs() < 1e-4,
+            "{u} {v}"
+        );
+        let [u, v] = white.to_diagram_coords(DiagramType::Cie1960Uv);
+        assert!(
+            (u - 0.1978).abs() < 1e-4 && (v - 0.3122).abs() < 1e-4,
+            "{u} {v}"
+        );
+
+        let black = Color::srgb(0.0, 0.0, 0.0).to_diagram_coords(DiagramType::Cie1931Xy);
+        let [x, y] = WhitePoint::D65.xy();
+        assert!((black[0] - x).abs() < 1e-6 && (black[1] - y).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,302 @@
+//! Color difference metrics.
+use super::{
+    color::{Color, ColorError, WhitePoint, WhitePointPolicy, color_spaces},
+    transform::xyz_to_cie_lab,
+};
+use crate::{Float, PI, Vec3};
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+use num_traits::Float as _;
+
+/// Weights of the Oklab axes in [`oklab_distance()`](Color::oklab_distance).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LabWeights {
+    /// Weight of the lightness axis.
+    pub l: Float,
+    /// Weight of the green–red axis.
+    pub a: Float,
+    /// Weight of the blue–yellow axis.
+    pub b: Float,
+}
+
+impl LabWeights {
+    /// Weights all axes equally, giving the Euclidean distance.
+    pub const UNIFORM: Self = Self::new(1.0, 1.0, 1.0);
+
+    pub const fn new(l: Float, a: Float, b: Float) -> Self {
+        Self { l, a, b }
+    }
+}
+
+impl Default for LabWeights {
+    fn default() -> Self {
+        Self::UNIFORM
+    }
+}
+
+/// A color difference metric, for [`Color::delta_e()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DeltaE {
+    /// The Euclidean distance in CIELAB.
+    Cie76,
+    /// The CIE94 difference in CIELAB with the graphic arts weights, taking
+    /// the first color as the reference.
+    Cie94,
+    /// The CIEDE2000 difference in CIELAB, see
+    /// [`delta_e_2000()`](Color::delta_e_2000).
+    Ciede2000,
+    /// The Euclidean distance in Oklab.
+    OklabEuclidean,
+}
+
+/// The kL, K1 and K2 parameters of CIE94 for graphic arts.
+const CIE94_GRAPHIC_ARTS: [Float; 3] = [1.0, 0.045, 0.015];
+
+/// 25⁷, which the CIEDE2000 chroma terms compare against.
+const CHROMA_25_POW_7: Float = 6_103_515_625.0;
+
+/// Returns the CIEDE2000 color difference between two CIELAB colors, per
+/// Sharma et al. (2005), "The CIEDE2000 color-difference formula:
+/// Implementation notes, supplementary test data, and mathematical
+/// observations".
+#[allow(non_snake_case)]
+fn ciede2000(lab1: Vec3, lab2: Vec3) -> Float {
+    let degrees = |radians: Float| radians * (180.0 / PI);
+    let radians = |degrees: Float| degrees * (PI / 180.0);
+    let hue = |b: Float, a: Float| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            let h = degrees(b.atan2(a));
+            if h < 0.0 { h + 360.0 } else { h }
+        }
+    };
+
+    let C1 = (lab1.y * lab1.y + lab1.z * lab1.z).sqrt();
+    let C2 = (lab2.y * lab2.y + lab2.z * lab2.z).sqrt();
+    let C_mean7 = ((C1 + C2) * 0.5).powi(7);
+    let G = 0.5 * (1.0 - (C_mean7 / (C_mean7 + CHROMA_25_POW_7)).sqrt());
+    let a1 = (1.0 + G) * lab1.y;
+    let a2 = (1.0 + G) * lab2.y;
+    let C1 = (a1 * a1 + lab1.z * lab1.z).sqrt();
+    let C2 = (a2 * a2 + lab2.z * lab2.z).sqrt();
+    let h1 = hue(lab1.z, a1);
+    let h2 = hue(lab2.z, a2);
+
+    let dL = lab2.x - lab1.x;
+    let dC = C2 - C1;
+    let dh = if C1 * C2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= 180.0 {
+        h2 - h1
+    } else if h2 - h1 > 180.0 {
+        h2 - h1 - 360.0
+    } else {
+        h2 - h1 + 360.0
+    };
+    let dH = 2.0 * (C1 * C2).sqrt() * radians(dh * 0.5).sin();
+
+    let L_mean = (lab1.x + lab2.x) * 0.5;
+    let C_mean = (C1 + C2) * 0.5;
+    let h_mean = if C1 * C2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) * 0.5
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) * 0.5
+    } else {
+        (h1 + h2 - 360.0) * 0.5
+    };
+    let T = 1.0 - 0.17 * radians(h_mean - 30.0).cos()
+        + 0.24 * radians(2.0 * h_mean).cos()
+        + 0.32 * radians(3.0 * h_mean + 6.0).cos()
+        - 0.20 * radians(4.0 * h_mean - 63.0).cos();
+    let d_theta = 30.0 * (-((h_mean - 275.0) / 25.0).powi(2)).exp();
+    let C_mean7 = C_mean.powi(7);
+    let R_C = 2.0 * (C_mean7 / (C_mean7 + CHROMA_25_POW_7)).sqrt();
+    let L_offset = (L_mean - 50.0).powi(2);
+    let S_L = 1.0 + 0.015 * L_offset / (20.0 + L_offset).sqrt();
+    let S_C = 1.0 + 0.045 * C_mean;
+    let S_H = 1.0 + 0.015 * C_mean * T;
+    let R_T = -radians(2.0 * d_theta).sin() * R_C;
+
+    let (l, c, h) = (dL / S_L, dC / S_C, dH / S_H);
+    (l * l + c * c + h * h + R_T * c * h).sqrt()
+}
+
+/// Returns the CIE94 color difference of the CIELAB color `lab2` from the
+/// reference `lab1`.
+fn cie94(lab1: Vec3, lab2: Vec3) -> Float {
+    let [k_l, k_1, k_2] = CIE94_GRAPHIC_ARTS;
+    let c1 = (lab1.y * lab1.y + lab1.z * lab1.z).sqrt();
+    let c2 = (lab2.y * lab2.y + lab2.z * lab2.z).sqrt();
+    let d = lab1 - lab2;
+    let dc = c1 - c2;
+    // Rounding can make the squared hue difference slightly negative.
+    let dh_squared = (d.y * d.y + d.z * d.z - dc * dc).max(0.0);
+    let l = d.x / k_l;
+    let c = dc / (1.0 + k_1 * c1);
+    let s_h = 1.0 + k_2 * c1;
+    (l * l + c * c + dh_squared / (s_h * s_h)).sqrt()
+}
+
+/// Returns the color in CIELAB relative to D65.
+fn cie_lab(color: &Color) -> Vec3 {
+    xyz_to_cie_lab(color.to(color_spaces::CIE_XYZ).value, WhitePoint::D65)
+}
+
+impl Color {
+    /// Returns the color difference between this color and `other` with the
+    /// given `metric`.
+    ///
+    /// The CIE metrics compare both colors in CIELAB relative to D65, adapting
+    /// them with the default chromatic adaptation of [`Color::to()`] where
+    /// needed.
+    pub fn delta_e(&self, other: &Color, metric: DeltaE) -> Float {
+        match metric {
+            DeltaE::Cie76 => {
+                let d = cie_lab(self) - cie_lab(other);
+                d.dot(d).sqrt()
+            }
+            DeltaE::Cie94 => cie94(cie_lab(self), cie_lab(other)),
+            DeltaE::Ciede2000 => ciede2000(cie_lab(self), cie_lab(other)),
+            DeltaE::OklabEuclidean => self.oklab_distance(other, LabWeights::UNIFORM),
+        }
+    }
+
+    /// Returns the CIEDE2000 color difference ΔE₀₀ between this color and
+    /// `other`.
+    ///
+    /// Both colors are compared in CIELAB relative to D65, adapting them with
+    /// the default chromatic adaptation of [`Color::to()`] where needed. A ΔE₀₀
+    /// of about 1 is a just noticeable difference.
+    pub fn delta_e_2000(&self, other: &Color) -> Float {
+        ciede2000(cie_lab(self), cie_lab(other))
+    }
+
+    /// Like [`delta_e_2000()`](Self::delta_e_2000), but with
+    /// [`WhitePointPolicy::Strict`] fails if the colors have different white
+    /// points instead of adapting them.
+    pub fn delta_e_2000_checked(
+        &self,
+        other: &Color,
+        policy: WhitePointPolicy,
+    ) -> Result<Float, ColorError> {
+        policy.check(self, other)?;
+        Ok(self.delta_e_2000(other))
+    }
+
+    /// Returns the weighted Euclidean distance between this color and `other`
+    /// in Oklab.
+    ///
+    /// Each axis difference is multiplied by its weight before taking the
+    /// distance, so e.g. a lightness weight below 1 matches colors mostly by
+    /// hue and chroma. With [`LabWeights::UNIFORM`] this is the plain Oklab
+    /// distance, roughly 0.02 for a just noticeable difference.
+    pub fn oklab_distance(&self, other: &Color, weights: LabWeights) -> Float {
+        let a = self.to(color_spaces::OK_LAB).value;
+        let b = other.to(color_spaces::OK_LAB).value;
+        let dl = weights.l * (a.x - b.x);
+        let da = weights.a * (a.y - b.y);
+        let db = weights.b * (a.z - b.z);
+        (dl * dl + da * da + db * db).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn zero_lightness_weight_ignores_lightness() {
+        let light = Color::new(0.8, 0.1, 1.0, color_spaces::OK_LCH);
+        let dark = Color::new(0.4, 0.1, 1.0, color_spaces::OK_LCH);
+        let uniform = light.oklab_distance(&dark, LabWeights::default());
+        assert!((uniform - 0.4).abs() < 1e-3, "{uniform} != 0.4");
+        let chromatic = light.oklab_distance(&dark, LabWeights::new(0.0, 1.0, 1.0));
+        assert!(chromatic < 1e-4, "{chromatic} != 0");
+    }
+
+    #[test]
+    fn ciede2000_matches_sharma_test_data() {
+        for (lab1, lab2, expected) in [
+            (
+                Vec3::new(50.0, 2.6772, -79.7751),
+                Vec3::new(50.0, 0.0, -82.7485),
+                2.0425,
+            ),
+            (
+                Vec3::new(50.0, 3.1571, -77.2803),
+                Vec3::new(50.0, 0.0, -82.7485),
+                2.8615,
+            ),
+            (
+                Vec3::new(50.0, 0.0, 0.0),
+                Vec3::new(50.0, -1.0, 2.0),
+                2.3669,
+            ),
+            (
+                Vec3::new(50.0, 2.5, 0.0),
+                Vec3::new(73.0, 25.0, -18.0),
+                27.1492,
+            ),
+            (
+                Vec3::new(2.0776, 0.0795, -1.135),
+                Vec3::new(0.9033, -0.0636, -0.5514),
+                0.9082,
+            ),
+        ] {
+            let difference = ciede2000(lab1, lab2);
+            assert!(
+                (difference - expected).abs() < 1e-3,
+                "{difference} != {expected}"
+            );
+            assert!((ciede2000(lab2, lab1) - expected).abs() < 1e-3);
+        }
+        let color = Color::srgb(0.2, 0.5, 0.7);
+        assert!(color.delta_e_2000(&color) < 1e-3);
+        let d50 = color.to(color_spaces::PRO_PHOTO);
+        assert!(d50.delta_e_2000(&color) < 1e-2);
+        assert!(
+            d50.delta_e_2000_checked(&color, WhitePointPolicy::Strict)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn delta_e_metrics() {
+        let lab1 = Vec3::new(50.0, 2.5, 0.0);
+        let lab2 = Vec3::new(73.0, 25.0, -18.0);
+        let difference = cie94(lab1, lab2);
+        assert!(
+            (difference - 34.6892).abs() < 1e-3,
+            "{difference} != 34.6892"
+        );
+
+        let lab = |value: Vec3| {
+            Color::new(
+                value.x,
+                value.y,
+                value.z,
+                color_spaces::CIE_XYZ.to_cie_lab(),
+            )
+        };
+        let (a, b) = (lab(lab1), lab(lab2));
+        for (metric, expected) in [
+            (DeltaE::Cie76, 36.8680),
+            (DeltaE::Cie94, 34.6892),
+            (DeltaE::Ciede2000, 27.1492),
+        ] {
+            let difference = a.delta_e(&b, metric);
+            assert!(
+                (difference - expected).abs() < 1e-2,
+                "{metric:?}: {difference} != {expected}"
+            );
+        }
+        assert_eq!(
+            a.delta_e(&b, DeltaE::OklabEuclidean),
+            a.oklab_distance(&b, LabWeights::UNIFORM)
+        );
+    }
+}
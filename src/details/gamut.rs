@@ -0,0 +1,833 @@
+//! Gamut queries and gamut mapping.
+//!
+//! The gamut of an RGB [`ColorSpace`] is the unit cube of its linear RGB
+//! values. Color spaces which are not defined over RGB primaries (such as
+//! Oklab or CIELAB, which use [`RgbPrimaries::CieXyz`]) have no bounded gamut
+//! of their own, so the functions in this module use linear sRGB instead.
+use super::{
+    color::{Color, ColorSpace, RgbPrimaries, WhitePoint, color_spaces},
+    conversion::LinearColorConversion,
+};
+use crate::{ColorConversion, Float, Mat3, Vec3};
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+use num_traits::Float as _;
+
+/// Epsilon used when checking whether linear RGB values lie inside the unit
+/// cube.
+pub(crate) const GAMUT_EPSILON: Float = 1e-6;
+
+/// Epsilon of [`Color::in_gamut()`] and `assert_in_gamut!` for linear RGB
+/// components outside of [0, 1].
+pub const IN_GAMUT_EPSILON: Float = 1e-4;
+
+/// Epsilon used by [`ColorSpace::adaptation_report()`] when checking whether
+/// the corners of the source gamut lie inside the destination gamut, which
+/// allows for the rounding of the conversion matrix.
+const CLIPPING_EPSILON: Float = 1e-4;
+
+/// Exponent of the compression curve used by
+/// [`Color::gamut_compress_oklch()`], as in the ACES reference gamut
+/// compression.
+const COMPRESSION_POWER: Float = 1.2;
+
+/// Threshold of [`GamutStrategy::Compress`], as a fraction of the chroma of
+/// the gamut boundary.
+const STRATEGY_COMPRESS_THRESHOLD: Float = 0.8;
+/// Iterations of the searches for the cusp and the gamut boundary in
+/// [`GamutStrategy::Cusp`].
+const CUSP_SEARCH_ITERATIONS: usize = 32;
+
+/// Distances from the achromatic axis at which the ACES reference gamut
+/// compression starts compressing, for the cyan, magenta and yellow
+/// directions.
+const ACES_RGC_THRESHOLD: [Float; 3] = [0.815, 0.803, 0.880];
+/// Distances from the achromatic axis which the ACES reference gamut
+/// compression maps onto the AP1 gamut boundary, for the cyan, magenta and
+/// yellow directions.
+const ACES_RGC_LIMIT: [Float; 3] = [1.147, 1.264, 1.312];
+
+/// Returns the linear RGB space whose unit cube is the gamut of `space`.
+pub(crate) fn rgb_gamut_space(space: ColorSpace) -> ColorSpace {
+    if space.primaries() == RgbPrimaries::CieXyz {
+        color_spaces::LINEAR_SRGB
+    } else {
+        space.as_linear()
+    }
+}
+
+/// Whether all components of `rgb` lie in `[0, 1]`, within `epsilon`.
+pub(crate) fn in_unit_cube(rgb: Vec3, epsilon: Float) -> bool {
+    [rgb.x, rgb.y, rgb.z]
+        .iter()
+        .all(|c| *c >= -epsilon && *c <= 1.0 + epsilon)
+}
+
+/// Finds the largest Oklch chroma at the given `lightness` and `hue` that
+/// stays inside the gamut, using a binary search.
+///
+/// `to_rgb` must convert from [`OK_LCH`](color_spaces::OK_LCH) to the linear
+/// RGB space defining the gamut.
+pub(crate) fn max_oklch_chroma(to_rgb: &ColorConversion, lightness: Float, hue: Float) -> Float {
+    if !(0.0..=1.0).contains(&lightness) {
+        return 0.0;
+    }
+    let inside = |chroma: Float| {
+        in_unit_cube(
+            to_rgb.convert(Vec3::new(lightness, chroma, hue)),
+            GAMUT_EPSILON,
+        )
+    };
+    let mut low = 0.0;
+    let mut high = 0.5;
+    // Wide gamuts can exceed the initial upper bound.
+    while inside(high) && high < 4.0 {
+        low = high;
+        high *= 2.0;
+    }
+    for _ in 0..32 {
+        let mid = 0.5 * (low + high);
+        if inside(mid) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+/// Smoothly compresses a `distance` from the achromatic axis, relative to the
+/// gamut boundary at `1`, so that distances from `threshold` to infinity map
+/// to `threshold` to `1`.
+///
+/// This is the compression curve of the ACES reference gamut compression with
+/// the limit moved to infinity. It has a slope of 1 at `threshold`, so there
+/// is no visible knee where the compression starts.
+fn compress_distance(distance: Float, threshold: Float) -> Float {
+    if distance < threshold {
+        return distance;
+    }
+    let range = 1.0 - threshold;
+    let x = (distance - threshold) / range;
+    threshold + range * x / (1.0 + x.powf(COMPRESSION_POWER)).powf(1.0 / COMPRESSION_POWER)
+}
+
+/// Compresses a `distance` from the achromatic axis so that `limit` maps to
+/// the gamut boundary at `1`, as in the ACES reference gamut compression.
+fn compress_distance_to_limit(distance: Float, threshold: Float, limit: Float) -> Float {
+    if distance < threshold {
+        return distance;
+    }
+    let scale = (limit - threshold)
+        / (((1.0 - threshold) / (limit - threshold)).powf(-COMPRESSION_POWER) - 1.0)
+            .powf(1.0 / COMPRESSION_POWER);
+    let x = (distance - threshold) / scale;
+    threshold + scale * x / (1.0 + x.powf(COMPRESSION_POWER)).powf(1.0 / COMPRESSION_POWER)
+}
+
+/// Returns the Oklch lightness at which the gamut of `to_rgb` has its largest
+/// chroma for `hue`, i.e. the lightness of the cusp, using a ternary search.
+fn cusp_lightness(to_rgb: &ColorConversion, hue: Float) -> Float {
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..CUSP_SEARCH_ITERATIONS {
+        let a = low + (high - low) / 3.0;
+        let b = high - (high - low) / 3.0;
+        if max_oklch_chroma(to_rgb, a, hue) < max_oklch_chroma(to_rgb, b, hue) {
+            low = a;
+        } else {
+            high = b;
+        }
+    }
+    (low + high) * 0.5
+}
+
+/// A way of mapping colors into a gamut, for [`Color::gamut_map()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamutStrategy {
+    /// Clamps the linear RGB components to [0, 1], which can shift hue and
+    /// lightness.
+    Clamp,
+    /// Reduces the Oklch chroma to the gamut boundary, keeping lightness and
+    /// hue.
+    ChromaClip,
+    /// Moves the color towards the gray at the lightness of the gamut cusp of
+    /// its hue until it reaches the gamut boundary, keeping hue. This trades
+    /// some lightness for keeping more chroma than [`ChromaClip`] for very
+    /// light or dark colors.
+    ///
+    /// [`ChromaClip`]: GamutStrategy::ChromaClip
+    Cusp,
+    /// Smoothly compresses the Oklch chroma with
+    /// [`Color::gamut_compress_oklch()`], starting at 80% of the boundary
+    /// chroma.
+    Compress,
+}
+
+impl GamutStrategy {
+    /// All strategies, in declaration order.
+    pub const ALL: [GamutStrategy; 4] = [
+        GamutStrategy::Clamp,
+        GamutStrategy::ChromaClip,
+        GamutStrategy::Cusp,
+        GamutStrategy::Compress,
+    ];
+}
+
+/// How [`Color::to_clamped()`] maps out-of-gamut colors into the destination
+/// gamut.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamutClip {
+    /// Clamps the linear RGB components to [0, 1].
+    Clamp,
+    /// Keeps the Oklch lightness and hue and reduces the chroma to the largest
+    /// one in gamut, found with a binary search.
+    ProjectToL,
+    /// Mixes the color with the gray of the same luminance, just enough to
+    /// bring it into gamut. This keeps the luminance for colors with a
+    /// luminance in [0, 1].
+    Desaturate,
+}
+
+/// Maps `color` into the gamut of `target` with each [`GamutStrategy`], and
+/// returns the results with their CIEDE2000 difference from `color`, so the
+/// strategies can be compared.
+///
+/// The results are in the order of [`GamutStrategy::ALL`] and in the space of
+/// `color`.
+#[cfg(feature = "std")]
+pub fn compare_gamut_strategies(
+    color: &Color,
+    target: ColorSpace,
+) -> Vec<(GamutStrategy, Color, Float)> {
+    GamutStrategy::ALL
+        .iter()
+        .map(|strategy| {
+            let mapped = color.gamut_map(target, *strategy);
+            (*strategy, mapped, color.delta_e_2000(&mapped))
+        })
+        .collect()
+}
+
+/// Describes what a conversion between two color spaces entails, as returned
+/// by [`ColorSpace::adaptation_report()`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptationReport {
+    /// The source and destination white points, if the conversion includes a
+    /// chromatic adaptation between them.
+    pub chromatic_adaptation: Option<(WhitePoint, WhitePoint)>,
+    /// The matrix converting linear source values to linear destination
+    /// values, including the chromatic adaptation.
+    pub matrix: Mat3,
+    /// Whether some colors of the source gamut lie outside the destination
+    /// gamut, i.e. converting them would need gamut mapping or clipping.
+    pub clipping_likely: bool,
+}
+
+impl ColorSpace {
+    /// Returns diagnostic information about converting colors from this space
+    /// to `other`.
+    ///
+    /// Spaces without RGB primaries use linear sRGB as their gamut, see the
+    /// [module documentation](self). Conversions to such spaces never clip.
+    pub fn adaptation_report(&self, other: ColorSpace) -> AdaptationReport {
+        let chromatic_adaptation = (self.white_point() != other.white_point())
+            .then_some((self.white_point(), other.white_point()));
+        let matrix = LinearColorConversion::new(self.as_linear(), other.as_linear()).matrix();
+        let clipping_likely = other.primaries() != RgbPrimaries::CieXyz && {
+            let gamut = LinearColorConversion::new(rgb_gamut_space(*self), other.as_linear());
+            // The gamut is convex, so it contains the source gamut if it
+            // contains all corners of the source unit cube.
+            !(0..8).all(|corner| {
+                let rgb = Vec3::new(
+                    (corner & 1) as Float,
+                    ((corner >> 1) & 1) as Float,
+                    ((corner >> 2) & 1) as Float,
+                );
+                in_unit_cube(gamut.convert(rgb), CLIPPING_EPSILON)
+            })
+        };
+        AdaptationReport {
+            chromatic_adaptation,
+            matrix,
+            clipping_likely,
+        }
+    }
+}
+
+/// How much of one gamut another one covers in the CIE xy chromaticity
+/// plane, as returned by [`gamut_coverage()`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GamutCoverage {
+    /// The fraction of the outer gamut's area covered by the inner gamut, in
+    /// [0, 1].
+    pub coverage: Float,
+    /// The area of the inner gamut's triangle.
+    pub inner_area: Float,
+    /// The area of the outer gamut's triangle.
+    pub outer_area: Float,
+    /// The area of the intersection of both triangles.
+    pub intersection_area: Float,
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `c`, positive if it is
+/// counterclockwise.
+fn cross(a: [Float; 2], b: [Float; 2], c: [Float; 2]) -> Float {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// The distance of `point` to the segment from `a` to `b`.
+fn segment_distance(point: [Float; 2], a: [Float; 2], b: [Float; 2]) -> Float {
+    let (ab, ap) = (
+        [b[0] - a[0], b[1] - a[1]],
+        [point[0] - a[0], point[1] - a[1]],
+    );
+    let length = ab[0] * ab[0] + ab[1] * ab[1];
+    let t = if length > 0.0 {
+        ((ap[0] * ab[0] + ap[1] * ab[1]) / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (dx, dy) = (ap[0] - t * ab[0], ap[1] - t * ab[1]);
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// The area of the polygon `points` with the shoelace formula.
+fn polygon_area(points: &[[Float; 2]]) -> Float {
+    let mut twice_area = 0.0;
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        twice_area += a[0] * b[1] - b[0] * a[1];
+    }
+    0.5 * twice_area.abs()
+}
+
+impl RgbPrimaries {
+    /// The area of the triangle spanned by the primaries in the CIE xy
+    /// chromaticity plane.
+    pub fn gamut_area(&self) -> Float {
+        let [r, g, b] = *self.values();
+        0.5 * cross(r, g, b).abs()
+    }
+}
+
+/// Computes how much of the `outer` gamut the `inner` gamut covers, by
+/// intersecting their triangles in the CIE xy chromaticity plane.
+///
+/// E.g. `gamut_coverage(RgbPrimaries::P3, RgbPrimaries::Bt2020).coverage` is
+/// the fraction of BT.2020 a Display P3 display covers. `inner` may extend
+/// beyond `outer`, only the covered part counts. Degenerate `outer` gamuts
+/// have a coverage of 0.
+pub fn gamut_coverage(inner: RgbPrimaries, outer: RgbPrimaries) -> GamutCoverage {
+    let inner_area = inner.gamut_area();
+    let outer_area = outer.gamut_area();
+
+    // Clip the inner triangle with each edge of the outer one
+    // (Sutherland-Hodgman). Each edge adds at most one vertex.
+    let [r, g, b] = *outer.values();
+    let edges = if cross(r, g, b) >= 0.0 {
+        [(r, g), (g, b), (b, r)]
+    } else {
+        [(r, b), (b, g), (g, r)]
+    };
+    let mut polygon = [[0.0; 2]; 6];
+    polygon[..3].copy_from_slice(inner.values());
+    let mut len = 3;
+    for (start, end) in edges {
+        let mut clipped = [[0.0; 2]; 6];
+        let mut clipped_len = 0;
+        for i in 0..len {
+            let (current, next) = (polygon[i], polygon[(i + 1) % len]);
+            let (side_current, side_next) = (cross(start, end, current), cross(start, end, next));
+            if side_current >= 0.0 {
+                clipped[clipped_len] = current;
+                clipped_len += 1;
+            }
+            if (side_current >= 0.0) != (side_next >= 0.0) {
+                let t = side_current / (side_current - side_next);
+                clipped[clipped_len] = [
+                    current[0] + t * (next[0] - current[0]),
+                    current[1] + t * (next[1] - current[1]),
+                ];
+                clipped_len += 1;
+            }
+        }
+        polygon = clipped;
+        len = clipped_len;
+        if len == 0 {
+            break;
+        }
+    }
+
+    let intersection_area = if len < 3 {
+        0.0
+    } else {
+        polygon_area(&polygon[..len])
+    };
+    GamutCoverage {
+        coverage: if outer_area > 0.0 {
+            (intersection_area / outer_area).min(1.0)
+        } else {
+            0.0
+        },
+        inner_area,
+        outer_area,
+        intersection_area,
+    }
+}
+
+impl Color {
+    /// Whether the color is inside the gamut of `space`, i.e. its linear RGB
+    /// components in `space` are in [0, 1] within [`IN_GAMUT_EPSILON`].
+    ///
+    /// Spaces without RGB primaries, such as CIELAB, use linear sRGB as their
+    /// gamut, see the [module documentation](self). Unlike
+    /// [`to_clamped()`](Self::to_clamped), this doesn't change the color.
+    pub fn in_gamut(&self, space: ColorSpace) -> bool {
+        in_unit_cube(self.to(rgb_gamut_space(space)).value, IN_GAMUT_EPSILON)
+    }
+
+    /// Returns by how much each linear RGB component of the color in `space`
+    /// is outside of [0, 1]: negative below 0, positive above 1 and 0 for
+    /// components in range, e.g. for diagnosing out of gamut colors.
+    ///
+    /// Uses the gamut of `space` like [`in_gamut()`](Self::in_gamut).
+    pub fn gamut_excess(&self, space: ColorSpace) -> Vec3 {
+        let rgb = self.to(rgb_gamut_space(space)).value;
+        let excess = |c: Float| {
+            if c < 0.0 { c } else { (c - 1.0).max(0.0) }
+        };
+        Vec3::new(excess(rgb.x), excess(rgb.y), excess(rgb.z))
+    }
+
+    /// Maps the color into the gamut of `target` (linear sRGB for spaces
+    /// without RGB primaries) with the given `strategy`.
+    ///
+    /// The returned color is in the same space as `self`.
+    pub fn gamut_map(&self, target: ColorSpace, strategy: GamutStrategy) -> Color {
+        let gamut_space = rgb_gamut_space(target);
+        match strategy {
+            GamutStrategy::Clamp => {
+                let rgb = self.to(gamut_space).value;
+                Color::new(
+                    rgb.x.clamp(0.0, 1.0),
+                    rgb.y.clamp(0.0, 1.0),
+                    rgb.z.clamp(0.0, 1.0),
+                    gamut_space,
+                )
+                .to(self.space)
+            }
+            GamutStrategy::ChromaClip => {
+                let lch = self.to(color_spaces::OK_LCH).value;
+                let to_rgb = ColorConversion::new(color_spaces::OK_LCH, gamut_space);
+                let lightness = lch.x.clamp(0.0, 1.0);
+                let chroma = lch.y.min(max_oklch_chroma(&to_rgb, lightness, lch.z));
+                Color::new(lightness, chroma, lch.z, color_spaces::OK_LCH).to(self.space)
+            }
+            GamutStrategy::Cusp => {
+                let lch = self.to(color_spaces::OK_LCH).value;
+                let to_rgb = ColorConversion::new(color_spaces::OK_LCH, gamut_space);
+                if (0.0..=1.0).contains(&lch.x) && lch.y <= max_oklch_chroma(&to_rgb, lch.x, lch.z)
+                {
+                    return *self;
+                }
+                let anchor = cusp_lightness(&to_rgb, lch.z);
+                let point = |t: Float| (lch.x + (anchor - lch.x) * t, lch.y * (1.0 - t));
+                // The anchor is in gamut, so search for the first point on the
+                // way there which is.
+                let (mut outside, mut inside) = (0.0, 1.0);
+                for _ in 0..CUSP_SEARCH_ITERATIONS {
+                    let t = (outside + inside) * 0.5;
+                    let (lightness, chroma) = point(t);
+                    if chroma <= max_oklch_chroma(&to_rgb, lightness, lch.z) {
+                        inside = t;
+                    } else {
+                        outside = t;
+                    }
+                }
+                let (lightness, chroma) = point(inside);
+                Color::new(lightness, chroma, lch.z, color_spaces::OK_LCH).to(self.space)
+            }
+            GamutStrategy::Compress => {
+                self.gamut_compress_oklch(target, STRATEGY_COMPRESS_THRESHOLD)
+            }
+        }
+    }
+
+    /// Converts the color into `space` like [`to()`](Self::to), mapping colors
+    /// outside of the gamut of `space` (linear sRGB for spaces without RGB
+    /// primaries) into it with `method`, instead of returning components
+    /// outside of [0, 1].
+    pub fn to_clamped(&self, space: ColorSpace, method: GamutClip) -> Color {
+        let mapped = match method {
+            GamutClip::Clamp => self.gamut_map(space, GamutStrategy::Clamp),
+            GamutClip::ProjectToL => self.gamut_map(space, GamutStrategy::ChromaClip),
+            GamutClip::Desaturate => {
+                let gamut_space = rgb_gamut_space(space);
+                let linear = self.to(gamut_space);
+                let rgb = linear.value;
+                let luminance = linear.relative_luminance().clamp(0.0, 1.0);
+                // The largest fraction of the distance from the gray that
+                // keeps every channel in [0, 1].
+                let fraction = |channel: Float| {
+                    let offset = channel - luminance;
+                    if offset > 0.0 {
+                        (1.0 - luminance) / offset
+                    } else if offset < 0.0 {
+                        -luminance / offset
+                    } else {
+                        1.0
+                    }
+                };
+                let t = fraction(rgb.x)
+                    .min(fraction(rgb.y))
+                    .min(fraction(rgb.z))
+                    .clamp(0.0, 1.0);
+                let gray = Vec3::splat(luminance);
+                Color {
+                    value: gray + (rgb - gray) * t,
+                    space: gamut_space,
+                }
+            }
+        };
+        mapped.to(space)
+    }
+
+    /// Converts the color to [`OK_LCH`](color_spaces::OK_LCH) and returns it
+    /// with its signed distance to the boundary of the gamut of `target`
+    /// (linear sRGB for spaces without RGB primaries), e.g. for color pickers.
+    ///
+    /// The boundary is the cusp triangle of the hue of the color: in the
+    /// plane of Oklch lightness and chroma, the triangle from black over the
+    /// cusp, the most saturated color in gamut, to white. Its lower edge is
+    /// exact, its upper edge a close approximation of the gamut boundary. The
+    /// distance is the Euclidean distance in that plane to the nearest edge
+    /// from the cusp, negative inside the triangle, 0 on it and positive
+    /// outside, also for lightnesses outside of [0, 1].
+    pub fn to_oklch_with_gamut_info(&self, target: ColorSpace) -> (Color, Float) {
+        let lch = self.to(color_spaces::OK_LCH);
+        let to_rgb = ColorConversion::new(color_spaces::OK_LCH, rgb_gamut_space(target));
+        let cusp_lightness = cusp_lightness(&to_rgb, lch.value.z);
+        let cusp = [
+            cusp_lightness,
+            max_oklch_chroma(&to_rgb, cusp_lightness, lch.value.z),
+        ];
+        let point = [lch.value.x, lch.value.y];
+        let distance = segment_distance(point, [0.0, 0.0], cusp).min(segment_distance(
+            point,
+            [1.0, 0.0],
+            cusp,
+        ));
+        let inside = cross([0.0, 0.0], cusp, point) <= 0.0 && cross(cusp, [1.0, 0.0], point) <= 0.0;
+        (lch, if inside { -distance } else { distance })
+    }
+
+    /// Applies the ACES 1.3 reference gamut compression, which pulls colors
+    /// outside of the AP1 gamut back into it before rendering.
+    ///
+    /// The compression works on the distance of each ACEScg channel from the
+    /// achromatic axis (the largest channel), relative to the achromatic
+    /// value. Distances below a per-axis threshold are left unchanged, larger
+    /// distances are compressed so that the per-axis limit lands on the AP1
+    /// boundary.
+    ///
+    /// The returned color is in the same space as `self`.
+    pub fn aces_gamut_compress(&self) -> Color {
+        let rgb = self.to(color_spaces::ACES_CG).value;
+        let achromatic = rgb.x.max(rgb.y).max(rgb.z);
+        if achromatic == 0.0 {
+            return *self;
+        }
+        let compress = |channel: Float, axis: usize| {
+            let distance = (achromatic - channel) / achromatic.abs();
+            let distance = compress_distance_to_limit(
+                distance,
+                ACES_RGC_THRESHOLD[axis],
+                ACES_RGC_LIMIT[axis],
+            );
+            achromatic - distance * achromatic.abs()
+        };
+        Color::new(
+            compress(rgb.x, 0),
+            compress(rgb.y, 1),
+            compress(rgb.z, 2),
+            color_spaces::ACES_CG,
+        )
+        .to(self.space)
+    }
+
+    /// Smoothly compresses the Oklch chroma of the color into the gamut of
+    /// `target` (linear sRGB for spaces without RGB primaries), keeping its
+    /// Oklch lightness and hue.
+    ///
+    /// Colors whose chroma is below `threshold` times the chroma of the gamut
+    /// boundary (at the same lightness and hue) are left unchanged. Chroma
+    /// beyond that is rolled off towards the boundary, instead of being
+    /// clipped hard, which avoids banding in gradients crossing the gamut
+    /// boundary. `threshold` should be in `[0, 1)`.
+    ///
+    /// The returned color is in the same space as `self`.
+    pub fn gamut_compress_oklch(&self, target: ColorSpace, threshold: Float) -> Color {
+        let lch = self.to(color_spaces::OK_LCH).value;
+        let to_rgb = ColorConversion::new(color_spaces::OK_LCH, rgb_gamut_space(target));
+        let boundary = max_oklch_chroma(&to_rgb, lch.x, lch.z);
+        let chroma = if boundary > 0.0 {
+            let distance = lch.y / boundary;
+            if distance < threshold {
+                return *self;
+            }
+            compress_distance(distance, threshold) * boundary
+        } else {
+            0.0
+        };
+        Color::new(lch.x, chroma, lch.z, color_spaces::OK_LCH).to(self.space)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn adaptation_report() {
+        let report = color_spaces::ENCODED_SRGB.adaptation_report(color_spaces::PRO_PHOTO);
+        assert_eq!(
+            report.chromatic_adaptation,
+            Some((WhitePoint::D65, WhitePoint::D50))
+        );
+        assert!(!report.clipping_likely);
+        assert!(report.matrix.abs_diff_eq(
+            LinearColorConversion::new(color_spaces::LINEAR_SRGB, color_spaces::PRO_PHOTO).matrix(),
+            1e-6
+        ));
+
+        let report = color_spaces::BT_2020.adaptation_report(color_spaces::ENCODED_SRGB);
+        assert_eq!(report.chromatic_adaptation, None);
+        assert!(report.clipping_likely);
+        assert!(
+            !color_spaces::ENCODED_SRGB
+                .adaptation_report(color_spaces::OK_LAB)
+                .clipping_likely
+        );
+    }
+
+    #[test]
+    fn gamut_compression_keeps_colors_below_threshold() {
+        let color = Color::srgb(0.6, 0.5, 0.45);
+        let compressed = color.gamut_compress_oklch(color_spaces::ENCODED_SRGB, 0.8);
+        assert_eq!(compressed.value, color.value);
+    }
+
+    #[test]
+    fn gamut_compression_has_no_knee() {
+        let threshold = 0.8;
+        let step = 1e-3;
+        let mut previous = compress_distance(threshold, threshold);
+        for i in 1..2000 {
+            let distance = threshold + i as Float * step;
+            let compressed = compress_distance(distance, threshold);
+            let slope = (compressed - previous) / step;
+            // Starts with a slope of 1 and flattens out without jumps.
+            assert!(slope > 0.0 && slope <= 1.0 + 1e-3, "{distance}: {slope}");
+            if i == 1 {
+                assert!(slope > 0.99, "{slope}");
+            }
+            assert!(compressed < 1.0, "{distance}: {compressed}");
+            previous = compressed;
+        }
+    }
+
+    #[test]
+    fn gamut_checks_are_informational() {
+        let p3_green = Color::new(0.0, 1.0, 0.0, color_spaces::DISPLAY_P3);
+        assert!(p3_green.in_gamut(color_spaces::DISPLAY_P3));
+        assert!(p3_green.in_gamut(color_spaces::BT_2020));
+        assert!(!p3_green.in_gamut(color_spaces::ENCODED_SRGB));
+
+        let excess = p3_green.gamut_excess(color_spaces::LINEAR_SRGB);
+        let rgb = p3_green.to(color_spaces::LINEAR_SRGB).value;
+        assert!(excess.x < 0.0 && excess.z < 0.0, "{excess:?}");
+        assert_eq!(excess.x, rgb.x);
+        assert!((excess.y - (rgb.y - 1.0)).abs() < 1e-6, "{excess:?}");
+        assert_eq!(
+            Color::srgb(0.2, 0.5, 1.0).gamut_excess(color_spaces::ENCODED_SRGB),
+            Vec3::ZERO
+        );
+
+        // CIELAB has no gamut of its own and uses linear sRGB.
+        let lab = color_spaces::LINEAR_SRGB.to_cie_lab();
+        assert_eq!(
+            p3_green.in_gamut(lab),
+            p3_green.in_gamut(color_spaces::LINEAR_SRGB)
+        );
+    }
+
+    #[test]
+    fn gamut_areas_and_coverage() {
+        let srgb = RgbPrimaries::Bt709.gamut_area();
+        assert!((srgb - 0.1121).abs() < 1e-4, "{srgb}");
+        let bt2020 = RgbPrimaries::Bt2020.gamut_area();
+        assert!((bt2020 - 0.21196).abs() < 1e-4, "{bt2020}");
+
+        let srgb_in_bt2020 = gamut_coverage(RgbPrimaries::Bt709, RgbPrimaries::Bt2020);
+        assert!(
+            (srgb_in_bt2020.coverage - srgb / bt2020).abs() < 1e-5,
+            "{srgb_in_bt2020:?}"
+        );
+        assert_eq!(srgb_in_bt2020.inner_area, srgb);
+        assert_eq!(srgb_in_bt2020.outer_area, bt2020);
+
+        // Display P3 covers about 72% of BT.2020 in the xy diagram.
+        let p3 = gamut_coverage(RgbPrimaries::P3, RgbPrimaries::Bt2020);
+        assert!((p3.coverage - 0.72).abs() < 0.01, "{p3:?}");
+
+        // BT.2020 covers all of sRGB, and so does Adobe RGB, which shares the
+        // red and blue primaries of sRGB.
+        let covered = gamut_coverage(RgbPrimaries::Bt2020, RgbPrimaries::Bt709);
+        assert!((covered.coverage - 1.0).abs() < 1e-5, "{covered:?}");
+        let adobe = gamut_coverage(RgbPrimaries::Adobe1998, RgbPrimaries::Bt709);
+        assert!((adobe.intersection_area - srgb).abs() < 1e-5, "{adobe:?}");
+
+        let disjoint = RgbPrimaries::Custom([[0.7, 0.29], [0.68, 0.3], [0.69, 0.28]]);
+        assert_eq!(
+            gamut_coverage(disjoint, RgbPrimaries::Bt709).intersection_area,
+            0.0
+        );
+    }
+
+    #[test]
+    fn aces_gamut_compression() {
+        // Values from the reference implementation.
+        for (input, expected) in [
+            (Vec3::new(1.0, -0.1, 0.5), Vec3::new(1.0, 0.033483, 0.5)),
+            (Vec3::new(0.2, 0.8, -0.05), Vec3::new(0.2, 0.8, 0.023023)),
+            (
+                Vec3::new(-0.02, 0.1, 0.9),
+                Vec3::new(0.038964, 0.112518, 0.9),
+            ),
+            (Vec3::new(0.5, 0.4, 0.3), Vec3::new(0.5, 0.4, 0.3)),
+        ] {
+            let color = Color::new(input.x, input.y, input.z, color_spaces::ACES_CG);
+            let compressed = color.aces_gamut_compress().value;
+            assert!(
+                compressed.abs_diff_eq(expected, 1e-5),
+                "{compressed:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn aces_gamut_compression_maps_limits_onto_boundary() {
+        for axis in 0..3 {
+            let (threshold, limit) = (ACES_RGC_THRESHOLD[axis], ACES_RGC_LIMIT[axis]);
+            let compressed = compress_distance_to_limit(limit, threshold, limit);
+            assert!((compressed - 1.0).abs() < 1e-5, "{compressed}");
+            let below = threshold - 0.01;
+            assert_eq!(compress_distance_to_limit(below, threshold, limit), below);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn gamut_strategies_differ() {
+        let p3_green = Color::new(0.0, 1.0, 0.0, color_spaces::ENCODED_DISPLAY_P3);
+        let results = compare_gamut_strategies(&p3_green, color_spaces::LINEAR_SRGB);
+        assert_eq!(results.len(), GamutStrategy::ALL.len());
+        for (i, (strategy, color, delta_e)) in results.iter().enumerate() {
+            assert_eq!(*strategy, GamutStrategy::ALL[i]);
+            let rgb = color.to(color_spaces::LINEAR_SRGB).value;
+            assert!(in_unit_cube(rgb, 1e-3), "{strategy:?}: {rgb:?}");
+            assert!(*delta_e > 1.0, "{strategy:?}: {delta_e}");
+            for (other, other_color, other_delta_e) in &results[i + 1..] {
+                assert!(
+                    (delta_e - other_delta_e).abs() > 1e-2,
+                    "{strategy:?} and {other:?} give the same ΔE of {delta_e}: {:?}, {:?}",
+                    color.value,
+                    other_color.value
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn oklch_gamut_distance_sign() {
+        let (lch, inside) =
+            Color::srgb(0.5, 0.4, 0.3).to_oklch_with_gamut_info(color_spaces::LINEAR_SRGB);
+        assert_eq!(lch.space, color_spaces::OK_LCH);
+        assert!(inside < 0.0, "{inside}");
+
+        let p3_green = Color::new(0.0, 1.0, 0.0, color_spaces::ENCODED_DISPLAY_P3);
+        let (_, outside) = p3_green.to_oklch_with_gamut_info(color_spaces::LINEAR_SRGB);
+        assert!(outside > 0.0, "{outside}");
+        let (_, own_gamut) = p3_green.to_oklch_with_gamut_info(color_spaces::DISPLAY_P3);
+        assert!(own_gamut.abs() < 1e-3, "{own_gamut}");
+
+        // Scaling a primary moves it along the lower edge of the cusp
+        // triangle, from the cusp to black.
+        let dark_green = Color::new(0.0, 0.2, 0.0, color_spaces::DISPLAY_P3);
+        let (_, edge) = dark_green.to_oklch_with_gamut_info(color_spaces::DISPLAY_P3);
+        assert!(edge.abs() < 1e-3, "{edge}");
+        let (_, gray) =
+            Color::srgb(0.5, 0.5, 0.5).to_oklch_with_gamut_info(color_spaces::DISPLAY_P3);
+        assert!(gray < -0.05, "{gray}");
+        let too_bright = Color::new(1.2, 0.0, 0.0, color_spaces::OK_LAB);
+        let (_, outside) = too_bright.to_oklch_with_gamut_info(color_spaces::DISPLAY_P3);
+        assert!((outside - 0.2).abs() < 1e-4, "{outside}");
+    }
+
+    #[test]
+    fn clamped_conversions_are_in_gamut() {
+        let green = Color::new(0.1, 0.9, 0.05, color_spaces::BT_2020);
+        let srgb = color_spaces::ENCODED_SRGB;
+        assert!(!in_unit_cube(green.to(srgb).value, 1e-4));
+        let luminance = green.relative_luminance();
+        let lightness = green.to(color_spaces::OK_LCH).value.x;
+        for method in [
+            GamutClip::Clamp,
+            GamutClip::ProjectToL,
+            GamutClip::Desaturate,
+        ] {
+            let clamped = green.to_clamped(srgb, method);
+            assert_eq!(clamped.space, srgb);
+            assert!(in_unit_cube(clamped.value, 1e-4), "{method:?}: {clamped:?}");
+            match method {
+                GamutClip::ProjectToL => {
+                    let clamped_lightness = clamped.to(color_spaces::OK_LCH).value.x;
+                    assert!((clamped_lightness - lightness).abs() < 1e-3);
+                }
+                GamutClip::Desaturate => {
+                    assert!((clamped.relative_luminance() - luminance).abs() < 1e-4);
+                }
+                GamutClip::Clamp => {}
+            }
+        }
+    }
+
+    #[test]
+    fn gamut_map_keeps_colors_in_gamut() {
+        let color = Color::srgb(0.6, 0.5, 0.45);
+        for strategy in GamutStrategy::ALL {
+            let mapped = color.gamut_map(color_spaces::ENCODED_SRGB, strategy);
+            assert!(
+                mapped.value.abs_diff_eq(color.value, 1e-3),
+                "{strategy:?}: {:?}",
+                mapped.value
+            );
+        }
+    }
+
+    #[test]
+    fn gamut_compression_maps_into_gamut() {
+        let p3_green = Color::new(0.0, 1.0, 0.0, color_spaces::ENCODED_DISPLAY_P3);
+        let compressed = p3_green.gamut_compress_oklch(color_spaces::LINEAR_SRGB, 0.8);
+        assert_eq!(compressed.space, color_spaces::ENCODED_DISPLAY_P3);
+        let rgb = compressed.to(color_spaces::LINEAR_SRGB).value;
+        assert!(in_unit_cube(rgb, 1e-4), "{rgb:?}");
+
+        let lch = p3_green.to(color_spaces::OK_LCH).value;
+        let compressed_lch = compressed.to(color_spaces::OK_LCH).value;
+        assert!((lch.x - compressed_lch.x).abs() < 1e-4);
+        assert!((lch.z - compressed_lch.z).abs() < 1e-4);
+        assert!(compressed_lch.y < lch.y);
+    }
+}
@@ -1093,13 +1093,16 @@ pub const ADOBE_WIDE_D50_TO_ADOBE_1998_D65: Mat3 = Mat3::from_cols_array(&[
     -0.002173991233517858, -0.08223848261534872, 1.03664814908986,
 ]);
 
-pub fn const_conversion_matrix(
+/// Returns the bundled matrix converting from the source to the destination
+/// primaries and white point, if there is one. Identical primaries and white
+/// points give the identity.
+pub const fn const_conversion_matrix(
     src_primaries: RgbPrimaries,
     src_wp: WhitePoint,
     dst_primaries: RgbPrimaries,
     dst_wp: WhitePoint,
 ) -> Option<Mat3> {
-    if src_primaries == dst_primaries && src_wp == dst_wp {
+    if src_primaries.const_eq(&dst_primaries) && src_wp.const_eq(&dst_wp) {
         return Some(Mat3::IDENTITY);
     }
     match (src_primaries, src_wp, dst_primaries, dst_wp) {
@@ -0,0 +1,373 @@
+//! Gradients interpolating between colors.
+#[cfg(feature = "std")]
+use super::color::WhitePointPolicy;
+use super::color::{Color, ColorSpace, color_spaces};
+#[cfg(feature = "std")]
+use super::css::{css_interpolation_space, css_number};
+use crate::{Float, Vec3};
+
+/// A gradient through `N` color stops, stored in an array, so it can be used
+/// without an allocator.
+///
+/// Colors are interpolated in Oklab, which gives perceptually even
+/// transitions without the muddy midpoints of interpolating encoded RGB.
+#[derive(Debug, Copy, Clone)]
+pub struct ArrayGradient<const N: usize> {
+    /// Stop positions and the stop colors in Oklab.
+    stops: [(Float, Vec3); N],
+    /// Spaces the stop colors were given in.
+    #[cfg(feature = "std")]
+    stop_spaces: [ColorSpace; N],
+    space: ColorSpace,
+}
+
+impl<const N: usize> ArrayGradient<N> {
+    /// Creates a gradient from `(position, color)` pairs.
+    ///
+    /// Sampled colors are returned in the space of the first stop.
+    ///
+    /// # Panics
+    ///
+    /// If there are no stops or the positions are not in ascending order.
+    pub fn new(stops: [(Float, Color); N]) -> Self {
+        assert!(N > 0, "a gradient needs at least one stop");
+        assert!(
+            stops.windows(2).all(|pair| pair[0].0 <= pair[1].0),
+            "gradient stop positions must be in ascending order"
+        );
+        Self {
+            stops: stops.map(|(position, color)| (position, color.to(color_spaces::OK_LAB).value)),
+            #[cfg(feature = "std")]
+            stop_spaces: stops.map(|(_, color)| color.space),
+            space: stops[0].1.space,
+        }
+    }
+
+    /// Returns the color at `position`. Positions outside of the stops return
+    /// the color of the first or last stop.
+    pub fn sample(&self, position: Float) -> Color {
+        let value = sample_stops(&self.stops, position, |c0, c1, t| c0 + (c1 - c0) * t);
+        Color {
+            value,
+            space: color_spaces::OK_LAB,
+        }
+        .to(self.space)
+    }
+
+    /// Samples `n` colors evenly spaced from the first to the last stop, e.g.
+    /// for a lookup table. A single sample is taken halfway.
+    #[cfg(feature = "std")]
+    pub fn sample_n(&self, n: usize) -> Vec<Color> {
+        even_positions(self.stops[0].0, self.stops[N - 1].0, n)
+            .map(|position| self.sample(position))
+            .collect()
+    }
+
+    /// Samples `n` colors evenly spaced from the first to the last stop, as
+    /// 8-bit encoded sRGB, e.g. for a Matplotlib `ListedColormap` or a lookup
+    /// texture.
+    ///
+    /// Components outside of [0, 1] are clamped.
+    #[cfg(feature = "std")]
+    pub fn to_colormap(&self, n: usize) -> Vec<[u8; 3]> {
+        to_colormap(self.sample_n(n))
+    }
+
+    /// Emits the gradient as a CSS `linear-gradient()`, interpolating in
+    /// Oklab like the gradient does.
+    ///
+    /// Each stop is written in the notation of the space it was given in, as
+    /// with [`Color::to_css()`], at its position as a percentage, so positions
+    /// from 0 to 1 span the element.
+    #[cfg(feature = "std")]
+    pub fn to_css_gradient(&self) -> String {
+        let stops = self
+            .stops
+            .iter()
+            .zip(self.stop_spaces)
+            .map(|((position, value), space)| {
+                let color = Color {
+                    value: *value,
+                    space: color_spaces::OK_LAB,
+                };
+                (*position, color.to(space))
+            });
+        css_gradient("oklab", stops)
+    }
+}
+
+/// A gradient through any number of color stops, interpolated in a chosen
+/// space with [`Color::mix()`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct Gradient {
+    /// Stops sorted by position.
+    stops: Vec<(Float, Color)>,
+    space: ColorSpace,
+}
+
+#[cfg(feature = "std")]
+impl Gradient {
+    /// Creates a gradient from `(position, color)` pairs, which are sorted by
+    /// position, mixing colors in `space`, e.g.
+    /// [`OK_LCH`](color_spaces::OK_LCH) or [`OK_LAB`](color_spaces::OK_LAB).
+    ///
+    /// Sampled colors are returned in the space of the first stop after
+    /// sorting.
+    ///
+    /// # Panics
+    ///
+    /// If there are no stops.
+    pub fn new(mut stops: Vec<(Float, Color)>, space: ColorSpace) -> Self {
+        assert!(!stops.is_empty(), "a gradient needs at least one stop");
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { stops, space }
+    }
+
+    /// The stops of the gradient, sorted by position.
+    pub fn stops(&self) -> &[(Float, Color)] {
+        &self.stops
+    }
+
+    /// The space colors are mixed in.
+    pub fn space(&self) -> ColorSpace {
+        self.space
+    }
+
+    /// Returns the color at `position`. Positions outside of the stops return
+    /// the color of the first or last stop.
+    pub fn sample(&self, position: Float) -> Color {
+        sample_stops(&self.stops, position, |c0, c1, t| {
+            // Adapting white points never fails.
+            c0.mix(&c1, t, self.space, WhitePointPolicy::Adapt).unwrap()
+        })
+        .to(self.stops[0].1.space)
+    }
+
+    /// Samples `n` colors evenly spaced from the first to the last stop, e.g.
+    /// for a lookup table. A single sample is taken halfway.
+    pub fn sample_n(&self, n: usize) -> Vec<Color> {
+        even_positions(self.stops[0].0, self.stops[self.stops.len() - 1].0, n)
+            .map(|position| self.sample(position))
+            .collect()
+    }
+
+    /// Samples `n` colors evenly spaced from the first to the last stop, as
+    /// 8-bit encoded sRGB, e.g. for a Matplotlib `ListedColormap` or a lookup
+    /// texture.
+    ///
+    /// Components outside of [0, 1] are clamped.
+    pub fn to_colormap(&self, n: usize) -> Vec<[u8; 3]> {
+        to_colormap(self.sample_n(n))
+    }
+
+    /// Emits the gradient as a CSS `linear-gradient()`, interpolating in the
+    /// space of the gradient if CSS supports it and in Oklab otherwise.
+    ///
+    /// Each stop is written in the notation of its space, as with
+    /// [`Color::to_css()`], at its position as a percentage, so positions from
+    /// 0 to 1 span the element.
+    pub fn to_css_gradient(&self) -> String {
+        css_gradient(
+            css_interpolation_space(self.space).unwrap_or("oklab"),
+            self.stops.iter().copied(),
+        )
+    }
+}
+
+/// Returns the value at `position` between the sorted `stops`, interpolating
+/// neighboring stops with `mix`. Positions outside of the stops return the
+/// first or last stop.
+fn sample_stops<T: Copy>(
+    stops: &[(Float, T)],
+    position: Float,
+    mix: impl Fn(T, T, Float) -> T,
+) -> T {
+    match stops.iter().position(|(p, _)| *p > position) {
+        Some(0) => stops[0].1,
+        Some(i) => {
+            let (p0, c0) = stops[i - 1];
+            let (p1, c1) = stops[i];
+            mix(c0, c1, (position - p0) / (p1 - p0))
+        }
+        None => stops[stops.len() - 1].1,
+    }
+}
+
+/// `n` positions evenly spaced from `first` to `last`. A single position is
+/// halfway.
+#[cfg(feature = "std")]
+fn even_positions(first: Float, last: Float, n: usize) -> impl Iterator<Item = Float> {
+    (0..n).map(move |i| {
+        let t = if n > 1 {
+            i as Float / (n - 1) as Float
+        } else {
+            0.5
+        };
+        first + t * (last - first)
+    })
+}
+
+/// Encodes `colors` as 8-bit sRGB.
+#[cfg(feature = "std")]
+fn to_colormap(colors: Vec<Color>) -> Vec<[u8; 3]> {
+    colors
+        .into_iter()
+        .map(|color| {
+            let [r, g, b, _] = color.to_rgba8888(0).to_be_bytes();
+            [r, g, b]
+        })
+        .collect()
+}
+
+/// Formats a CSS `linear-gradient()` through the `(position, color)` stops,
+/// interpolating in the CSS space named `space`.
+#[cfg(feature = "std")]
+fn css_gradient(space: &str, stops: impl Iterator<Item = (Float, Color)>) -> String {
+    let stops: Vec<String> = stops
+        .map(|(position, color)| format!("{} {}%", color.to_css(), css_number(position * 100.0)))
+        .collect();
+    format!("linear-gradient(in {space}, {})", stops.join(", "))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::details::color::{RgbPrimaries, TransformFn, WhitePoint};
+
+    #[test]
+    fn array_gradient_samples_stops_and_midpoints() {
+        let gradient = ArrayGradient::<3>::new([
+            (0.0, Color::srgb(1.0, 0.0, 0.0)),
+            (0.5, Color::srgb(1.0, 1.0, 1.0)),
+            (1.0, Color::srgb(0.0, 0.0, 1.0)),
+        ]);
+        let red = gradient.sample(0.0);
+        assert_eq!(red.space, color_spaces::ENCODED_SRGB);
+        assert!(
+            red.value.abs_diff_eq(Vec3::new(1.0, 0.0, 0.0), 1e-4),
+            "{red:?}"
+        );
+        let white = gradient.sample(0.5).value;
+        assert!(white.abs_diff_eq(Vec3::splat(1.0), 1e-4), "{white:?}");
+        let blue = gradient.sample(2.0).value;
+        assert!(blue.abs_diff_eq(Vec3::new(0.0, 0.0, 1.0), 1e-4), "{blue:?}");
+
+        let quarter = gradient.sample(0.25).to(color_spaces::OK_LAB).value;
+        let expected = (Color::srgb(1.0, 0.0, 0.0).to(color_spaces::OK_LAB).value
+            + Color::srgb(1.0, 1.0, 1.0).to(color_spaces::OK_LAB).value)
+            * 0.5;
+        assert!(
+            quarter.abs_diff_eq(expected, 1e-4),
+            "{quarter:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn colormap_and_css_gradient() {
+        let gradient = ArrayGradient::<2>::new([
+            (0.0, Color::srgb(1.0, 0.0, 0.0)),
+            (1.0, Color::new(0.5, 0.1, 4.0, color_spaces::OK_LCH)),
+        ]);
+        let colormap = gradient.to_colormap(256);
+        assert_eq!(colormap.len(), 256);
+        assert_eq!(colormap[0], [255, 0, 0]);
+        let css = gradient.to_css_gradient();
+        let hue = css
+            .strip_prefix("linear-gradient(in oklab, color(srgb 1 0 0) 0%, oklch(0.5 0.1 ")
+            .and_then(|rest| rest.strip_suffix(") 100%)"))
+            .and_then(|hue| hue.parse::<Float>().ok());
+        assert!(
+            hue.is_some_and(|hue| (hue - 229.18312).abs() < 1e-3),
+            "{css}"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn gradient_sorts_stops_and_samples_evenly() {
+        let red = Color::srgb(1.0, 0.0, 0.0);
+        let white = Color::srgb(1.0, 1.0, 1.0);
+        let blue = Color::srgb(0.0, 0.0, 1.0).to(color_spaces::LINEAR_SRGB);
+        let gradient = Gradient::new(
+            vec![(1.0, blue), (0.0, red), (0.5, white)],
+            color_spaces::OK_LAB,
+        );
+        let positions: Vec<Float> = gradient.stops().iter().map(|(p, _)| *p).collect();
+        assert_eq!(positions, [0.0, 0.5, 1.0]);
+
+        let samples = gradient.sample_n(5);
+        assert_eq!(samples.len(), 5);
+        assert!(
+            samples
+                .iter()
+                .all(|c| c.space == color_spaces::ENCODED_SRGB)
+        );
+        for (sample, expected) in [(samples[0], red), (samples[2], white), (samples[4], blue)] {
+            let expected = expected.to(color_spaces::ENCODED_SRGB).value;
+            assert!(
+                sample.value.abs_diff_eq(expected, 1e-4),
+                "{sample:?} != {expected:?}"
+            );
+        }
+        let quarter = samples[1].value;
+        let expected = red
+            .mix(&white, 0.5, color_spaces::OK_LAB, WhitePointPolicy::Adapt)
+            .unwrap()
+            .value;
+        assert!(quarter.abs_diff_eq(expected, 1e-5), "{quarter:?}");
+
+        // Positions outside of the stops clamp to the end stops.
+        assert!(gradient.sample(-1.0).value.abs_diff_eq(red.value, 1e-6));
+        assert!(
+            gradient
+                .sample(3.0)
+                .value
+                .abs_diff_eq(samples[4].value, 1e-6)
+        );
+        assert_eq!(gradient.sample_n(1)[0].value, gradient.sample(0.5).value);
+        assert!(gradient.sample_n(0).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn gradient_colormap_and_css_gradient() {
+        let red = Color::srgb(1.0, 0.0, 0.0);
+        let blue = Color::srgb(0.0, 0.0, 1.0);
+        let gradient = Gradient::new(vec![(0.0, red), (1.0, blue)], color_spaces::OK_LCH);
+        let colormap = gradient.to_colormap(3);
+        assert_eq!(colormap[0], [255, 0, 0]);
+        assert_eq!(colormap[2], [0, 0, 255]);
+        let [r, g, b, _] = gradient.sample(0.5).to_rgba8888(0).to_be_bytes();
+        assert_eq!(colormap[1], [r, g, b]);
+        assert_eq!(
+            gradient.to_css_gradient(),
+            "linear-gradient(in oklch, color(srgb 1 0 0) 0%, color(srgb 0 0 1) 100%)"
+        );
+
+        // Spaces CSS cannot interpolate in fall back to Oklab.
+        let hsl = ColorSpace::new(RgbPrimaries::Bt709, WhitePoint::D65, Some(TransformFn::Hsl));
+        let gradient = Gradient::new(vec![(0.0, red), (1.0, blue)], hsl);
+        assert!(
+            gradient
+                .to_css_gradient()
+                .starts_with("linear-gradient(in oklab, ")
+        );
+
+        // Both gradients sample one color halfway.
+        let array = ArrayGradient::<2>::new([(0.0, red), (1.0, blue)]);
+        let oklab = Gradient::new(vec![(0.0, red), (1.0, blue)], color_spaces::OK_LAB);
+        let (a, b) = (array.sample_n(1)[0].value, oklab.sample_n(1)[0].value);
+        assert!(a.abs_diff_eq(b, 1e-5), "{a:?} != {b:?}");
+    }
+
+    #[test]
+    #[should_panic]
+    fn array_gradient_requires_ascending_stops() {
+        ArrayGradient::new([
+            (1.0, Color::srgb(1.0, 0.0, 0.0)),
+            (0.0, Color::srgb(0.0, 0.0, 1.0)),
+        ]);
+    }
+}
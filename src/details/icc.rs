@@ -0,0 +1,126 @@
+//! Color spaces from the colorant and white point tags of ICC profiles.
+//!
+//! Matrix/TRC ICC profiles describe their linear space by the `rXYZ`, `gXYZ`
+//! and `bXYZ` colorant tags, which are the XYZ values of the primaries
+//! chromatically adapted to the D50 illuminant of the profile connection
+//! space (PCS), usually with the Bradford transform. Parsing profiles is out
+//! of scope, this module only converts between the tag values and
+//! [`ColorSpace`]s.
+use super::{
+    cat::LmsConeSpace,
+    color::{ColorSpace, RgbPrimaries, WhitePoint},
+    xyz::rgb_to_xyz,
+};
+use crate::{Float, Vec3};
+
+/// The D50 illuminant of the ICC profile connection space, as encoded in
+/// profiles. It differs from [`WhitePoint::D50`] in the fourth decimal.
+pub const ICC_PCS_ILLUMINANT: [Float; 3] = [0.9642, 1.0, 0.8249];
+
+/// Tolerance for snapping the tag values to known primaries and white points,
+/// which covers the rounding of the `s15Fixed16` numbers in profiles and of
+/// the adaptation matrix used to create them.
+const ICC_TOLERANCE: Float = 1e-3;
+
+impl ColorSpace {
+    /// Creates a linear color space from the `rXYZ`, `gXYZ` and `bXYZ`
+    /// colorant tags and the media white point of an ICC profile.
+    ///
+    /// The colorants are adapted back from the [PCS
+    /// illuminant](ICC_PCS_ILLUMINANT) to `white_xyz` with the Bradford
+    /// transform, as ICC profiles adapt them the other way. `white_xyz` is
+    /// the actual white point of the space: the `wtpt` tag of version 2
+    /// profiles, or the `wtpt` tag of version 4 profiles, which is always the
+    /// PCS illuminant, multiplied with the inverse of their `chad` tag.
+    ///
+    /// Primaries and white points matching known ones within the precision of
+    /// profiles are canonicalized, e.g. to [`RgbPrimaries::Bt709`] and
+    /// [`WhitePoint::D65`] for sRGB profiles.
+    pub fn from_icc_primaries(
+        red_xyz: [Float; 3],
+        green_xyz: [Float; 3],
+        blue_xyz: [Float; 3],
+        white_xyz: [Float; 3],
+    ) -> ColorSpace {
+        let white = Vec3::from(white_xyz) / white_xyz[1];
+        let cat = LmsConeSpace::Bradford
+            .chromatic_adaptation_transform(Vec3::from(ICC_PCS_ILLUMINANT), white);
+        let xy = |colorant: [Float; 3]| {
+            let xyz = cat * Vec3::from(colorant);
+            let sum = xyz.x + xyz.y + xyz.z;
+            [xyz.x / sum, xyz.y / sum]
+        };
+
+        let mut primaries = RgbPrimaries::Custom([xy(red_xyz), xy(green_xyz), xy(blue_xyz)]);
+        let _ = primaries.canonicalize_with_tol(ICC_TOLERANCE);
+        let mut white_point = WhitePoint::Custom(white.into());
+        let _ = white_point.canonicalize_with_tol(ICC_TOLERANCE);
+        ColorSpace::linear(primaries, white_point)
+    }
+
+    /// Returns the `rXYZ`, `gXYZ` and `bXYZ` colorant tag values for an ICC
+    /// profile of this space's linear space, adapted to the [PCS
+    /// illuminant](ICC_PCS_ILLUMINANT) with the Bradford transform, and its
+    /// white point XYZ with Y = 1, i.e. the inverse of
+    /// [`from_icc_primaries()`](Self::from_icc_primaries).
+    pub fn to_icc_primaries(&self) -> [[Float; 3]; 4] {
+        let white = self.white_point().xyz();
+        let cat = LmsConeSpace::Bradford
+            .chromatic_adaptation_transform(white, Vec3::from(ICC_PCS_ILLUMINANT));
+        let to_pcs = cat * rgb_to_xyz(self.primaries().values(), self.white_point().values());
+        [
+            to_pcs.x_axis.into(),
+            to_pcs.y_axis.into(),
+            to_pcs.z_axis.into(),
+            white.into(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::spaces;
+
+    #[test]
+    fn srgb_icc_profile_tags() {
+        // The tags of the widespread sRGB IEC61966-2.1 version 2 profile.
+        let space = ColorSpace::from_icc_primaries(
+            [0.436066, 0.222488, 0.013916],
+            [0.385147, 0.716873, 0.097076],
+            [0.143066, 0.060608, 0.714096],
+            [0.950455, 1.0, 1.08905],
+        );
+        assert_eq!(space.primaries(), RgbPrimaries::Bt709);
+        assert_eq!(space.white_point(), WhitePoint::D65);
+        assert!(space.is_linear());
+    }
+
+    #[test]
+    fn icc_primaries_round_trip() {
+        for space in [spaces::LINEAR_SRGB, spaces::ACES_CG, spaces::BT_2020] {
+            let [red, green, blue, white] = space.to_icc_primaries();
+            // The colorants of an ICC profile add up to the PCS illuminant.
+            let sum = Vec3::from(red) + Vec3::from(green) + Vec3::from(blue);
+            assert!(
+                sum.abs_diff_eq(Vec3::from(ICC_PCS_ILLUMINANT), 1e-4),
+                "{space:?}: {sum:?}"
+            );
+            let back = ColorSpace::from_icc_primaries(red, green, blue, white);
+            assert_eq!(back.primaries(), space.primaries(), "{space:?}");
+            assert_eq!(back.white_point(), space.white_point(), "{space:?}");
+        }
+
+        let custom = ColorSpace::linear(
+            RgbPrimaries::Custom([[0.7, 0.29], [0.2, 0.72], [0.14, 0.05]]),
+            WhitePoint::D65,
+        );
+        let [red, green, blue, white] = custom.to_icc_primaries();
+        let back = ColorSpace::from_icc_primaries(red, green, blue, white);
+        let (values, expected) = (back.primaries(), custom.primaries());
+        let (values, expected) = (values.values(), expected.values());
+        for (value, expected) in values.as_flattened().iter().zip(expected.as_flattened()) {
+            assert!((value - expected).abs() < 1e-5, "{back:?}");
+        }
+    }
+}
@@ -0,0 +1,282 @@
+//! Lookup tables baked from conversions, for applying them on the GPU or in
+//! other software.
+//!
+//! 3D LUTs sample the unit input range on grid corners, i.e. the first and
+//! last samples are at exactly 0 and 1, and sample `i` of `size` is at
+//! `i / (size - 1)`, as expected by the `.cube` format and most LUT tools.
+//! Inputs outside of [0, 1] are not covered, and outputs are not clamped. 1D
+//! LUTs sample a given input domain the same way.
+//!
+//! Steep transfer functions like PQ or S-Log3 are poorly represented by 3D
+//! LUTs, so pipelines usually decode them with a 1D shaper LUT first, see
+//! [`TransformFn::bake_lut_1d()`], followed by the matrix and a 3D LUT of the
+//! remaining conversion.
+use super::{
+    color::{TransformFn, WhitePoint},
+    conversion::ColorConversion,
+    transform::ColorTransform,
+};
+use crate::{Float, Vec3};
+use std::io::{self, Write};
+
+/// Returns the input position of sample `i` of a LUT with `size` samples.
+fn grid_position(i: usize, size: usize) -> Float {
+    i as Float / (size - 1) as Float
+}
+
+impl ColorConversion {
+    /// Samples the conversion on a `size`³ grid over the unit input cube.
+    ///
+    /// The values are ordered with red changing fastest, then green, then
+    /// blue, so the output for the input `(r, g, b)` at grid indices
+    /// `(i, j, k)` is at index `i + j * size + k * size²`. This matches the
+    /// order of `.cube` files and of 3D textures with red along x.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is less than 2.
+    pub fn bake_lut_3d(&self, size: usize) -> Vec<Vec3> {
+        assert!(size >= 2, "a LUT needs at least 2 samples per axis");
+        let mut lut = Vec::with_capacity(size * size * size);
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    lut.push(self.convert(Vec3::new(
+                        grid_position(r, size),
+                        grid_position(g, size),
+                        grid_position(b, size),
+                    )));
+                }
+            }
+        }
+        lut
+    }
+
+    /// Writes the conversion as an Adobe/IRIDAS `.cube` 3D LUT with `size`
+    /// samples per axis, see [`bake_lut_3d()`](Self::bake_lut_3d).
+    ///
+    /// The `.cube` format allows sizes of 2 to 256, other sizes return an
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) error without writing
+    /// anything.
+    pub fn write_cube(&self, mut w: impl Write, size: usize) -> io::Result<()> {
+        if !(2..=256).contains(&size) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("`.cube` LUT sizes are 2 to 256, got {size}"),
+            ));
+        }
+        let lut = self.bake_lut_3d(size);
+        writeln!(
+            w,
+            "TITLE \"{:?} to {:?}\"",
+            self.src_space(),
+            self.dst_space()
+        )?;
+        writeln!(w, "LUT_3D_SIZE {size}")?;
+        writeln!(w, "DOMAIN_MIN 0.0 0.0 0.0")?;
+        writeln!(w, "DOMAIN_MAX 1.0 1.0 1.0")?;
+        for value in lut {
+            writeln!(w, "{:.6} {:.6} {:.6}", value.x, value.y, value.z)?;
+        }
+        Ok(())
+    }
+}
+
+impl TransformFn {
+    /// Whether the transform function applies the same curve to each
+    /// component independently, so it can be baked into a 1D LUT.
+    fn is_per_channel(&self) -> bool {
+        matches!(
+            self,
+            Self::Srgb
+                | Self::Bt601
+                | Self::Pq
+                | Self::AcesCc
+                | Self::AcesCct
+                | Self::Bt2020_10Bit
+                | Self::Bt2020_12Bit
+                | Self::SLog3
+                | Self::Hlg
+                | Self::DciP3
+                | Self::Gamma(_)
+        )
+    }
+
+    /// Samples the curve of the transform function at `size` inputs over
+    /// [0, 1], e.g. for a shaper LUT, or returns `None` if the transform
+    /// function doesn't act on each component independently, like Oklab or
+    /// HSL.
+    ///
+    /// By default the decoding curve is sampled, e.g. the sRGB or PQ EOTF,
+    /// listing linear values for evenly spaced encoded values, and `inverse`
+    /// samples the encoding curve instead. Note that [PQ](Self::Pq) decodes to
+    /// cd/m², so its encoding curve over [0, 1] only covers up to 1 cd/m², see
+    /// [`bake_lut_1d_with_domain()`](Self::bake_lut_1d_with_domain) for other
+    /// input ranges.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is less than 2.
+    pub fn bake_lut_1d(&self, size: usize, inverse: bool) -> Option<Vec<Float>> {
+        self.bake_lut_1d_with_domain(size, inverse, [0.0, 1.0])
+    }
+
+    /// Like [`bake_lut_1d()`](Self::bake_lut_1d), but samples `size` evenly
+    /// spaced inputs over `domain`, e.g. `[0.0, 10000.0]` to encode the full
+    /// range of [PQ](Self::Pq).
+    ///
+    /// # Panics
+    ///
+    /// If `size` is less than 2.
+    pub fn bake_lut_1d_with_domain(
+        &self,
+        size: usize,
+        inverse: bool,
+        domain: [Float; 2],
+    ) -> Option<Vec<Float>> {
+        assert!(size >= 2, "a LUT needs at least 2 samples");
+        if !self.is_per_channel() {
+            return None;
+        }
+        let transform = if inverse {
+            ColorTransform::new(None, Some(*self))
+        } else {
+            ColorTransform::new(Some(*self), None)
+        }?;
+        let [min, max] = domain;
+        Some(
+            (0..size)
+                .map(|i| {
+                    let input = Vec3::splat(min + (max - min) * grid_position(i, size));
+                    transform.apply(input, WhitePoint::D65).x
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Writes the samples of a 1D LUT over [0, 1], e.g. from
+/// [`TransformFn::bake_lut_1d()`], as a single component Sony Pictures
+/// Imageworks `.spi1d` file, as read by OpenColorIO.
+pub fn write_spi1d(w: impl Write, lut: &[Float]) -> io::Result<()> {
+    write_spi1d_with_domain(w, lut, [0.0, 1.0])
+}
+
+/// Like [`write_spi1d()`], for the samples of a 1D LUT over `domain`, e.g.
+/// from [`TransformFn::bake_lut_1d_with_domain()`].
+pub fn write_spi1d_with_domain(
+    mut w: impl Write,
+    lut: &[Float],
+    domain: [Float; 2],
+) -> io::Result<()> {
+    writeln!(w, "Version 1")?;
+    writeln!(w, "From {:?} {:?}", domain[0], domain[1])?;
+    writeln!(w, "Length {}", lut.len())?;
+    writeln!(w, "Components 1")?;
+    writeln!(w, "{{")?;
+    for value in lut {
+        writeln!(w, "    {value:.6}")?;
+    }
+    writeln!(w, "}}")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Color, spaces};
+
+    #[test]
+    fn lut_3d_order_and_grid_corners() {
+        let conversion = ColorConversion::new(spaces::ENCODED_SRGB, spaces::ACES_CG);
+        let lut = conversion.bake_lut_3d(3);
+        assert_eq!(lut.len(), 27);
+        for (index, input) in [
+            (0, Vec3::splat(0.0)),
+            (1, Vec3::new(0.5, 0.0, 0.0)),
+            (3, Vec3::new(0.0, 0.5, 0.0)),
+            (9, Vec3::new(0.0, 0.0, 0.5)),
+            (26, Vec3::splat(1.0)),
+        ] {
+            let expected = conversion.convert(input);
+            assert_eq!(lut[index], expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn write_cube_file() {
+        let conversion = ColorConversion::new(spaces::LINEAR_SRGB, spaces::LINEAR_SRGB);
+        let mut cube = Vec::new();
+        conversion.write_cube(&mut cube, 2).unwrap();
+        let cube = String::from_utf8(cube).unwrap();
+        let mut lines = cube.lines().skip(1);
+        assert_eq!(lines.next(), Some("LUT_3D_SIZE 2"));
+        assert_eq!(lines.next(), Some("DOMAIN_MIN 0.0 0.0 0.0"));
+        assert_eq!(lines.next(), Some("DOMAIN_MAX 1.0 1.0 1.0"));
+        assert_eq!(lines.next(), Some("0.000000 0.000000 0.000000"));
+        assert_eq!(lines.next(), Some("1.000000 0.000000 0.000000"));
+        assert_eq!(lines.last(), Some("1.000000 1.000000 1.000000"));
+
+        for size in [0, 1, 257] {
+            let mut cube = Vec::new();
+            let error = conversion.write_cube(&mut cube, size).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+            assert!(cube.is_empty());
+        }
+    }
+
+    #[test]
+    fn lut_1d_curves() {
+        let decode = TransformFn::Srgb.bake_lut_1d(5, false).unwrap();
+        let encode = TransformFn::Srgb.bake_lut_1d(5, true).unwrap();
+        assert_eq!(decode.len(), 5);
+        for (i, (decoded, encoded)) in decode.iter().zip(&encode).enumerate() {
+            let input = i as Float / 4.0;
+            let expected = Color::new(input, input, input, spaces::LINEAR_SRGB)
+                .to(spaces::ENCODED_SRGB)
+                .value
+                .x;
+            assert!((encoded - expected).abs() < 1e-6, "{encoded} != {expected}");
+            assert!(decoded <= &input, "{decoded} > {input}");
+        }
+        assert!((decode[4] - 1.0).abs() < 1e-6);
+
+        let gamma = TransformFn::gamma(2.2).bake_lut_1d(3, false).unwrap();
+        let expected = (0.5 as Float).powf(2.2);
+        assert!((gamma[1] - expected).abs() < 1e-6, "{gamma:?}");
+
+        // PQ encodes up to 10000 cd/m².
+        let pq = TransformFn::Pq
+            .bake_lut_1d_with_domain(3, true, [0.0, 10000.0])
+            .unwrap();
+        assert!(pq[0].abs() < 1e-6 && (pq[2] - 1.0).abs() < 1e-4, "{pq:?}");
+
+        assert_eq!(TransformFn::OkLab.bake_lut_1d(16, false), None);
+        assert_eq!(TransformFn::Hsl.bake_lut_1d(16, true), None);
+    }
+
+    #[test]
+    fn write_spi1d_file() {
+        let lut = TransformFn::gamma(2.0).bake_lut_1d(3, false).unwrap();
+        let mut spi1d = Vec::new();
+        write_spi1d(&mut spi1d, &lut).unwrap();
+        assert_eq!(
+            String::from_utf8(spi1d).unwrap(),
+            "Version 1\nFrom 0.0 1.0\nLength 3\nComponents 1\n{\n    0.000000\n    \
+             0.250000\n    1.000000\n}\n"
+        );
+
+        let lut = TransformFn::Pq
+            .bake_lut_1d_with_domain(2, true, [0.0, 10000.0])
+            .unwrap();
+        let mut spi1d = Vec::new();
+        write_spi1d_with_domain(&mut spi1d, &lut, [0.0, 10000.0]).unwrap();
+        let spi1d = String::from_utf8(spi1d).unwrap();
+        assert_eq!(spi1d.lines().nth(1), Some("From 0.0 10000.0"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn lut_3d_requires_two_samples() {
+        ColorConversion::new(spaces::ENCODED_SRGB, spaces::ACES_CG).bake_lut_3d(1);
+    }
+}
@@ -25,7 +25,7 @@ mod math {
     pub use glam::f64::DVec3 as Vec3;
 
     #[cfg(all(not(feature = "std"), feature = "libm"))]
-    use num_traits::Float;
+    use num_traits::Float as _;
 
     impl super::Cuberoot for Vec3 {
         #[inline]
@@ -40,20 +40,30 @@ mod math {
 mod math {
     use crate::Float;
     #[cfg(all(not(feature = "std"), feature = "libm"))]
-    use core::ops::{Add, Div, Mul, MulAssign, Sub};
+    use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Sub};
     #[cfg(all(not(feature = "std"), feature = "libm"))]
-    use num_traits::Float;
+    use num_traits::Float as _;
+    #[cfg(feature = "serde")]
+    use serde::{Deserialize, Serialize};
     #[cfg(all(not(feature = "libm"), feature = "std"))]
-    use std::ops::{Add, Div, Mul, MulAssign, Sub};
+    use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Sub};
 
     #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     #[derive(Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
     pub struct Vec3 {
         pub x: Float,
         pub y: Float,
         pub z: Float,
     }
 
+    // SAFETY: `Vec3` is `repr(C)` with three `Float`s, so it has no padding,
+    // and any bit pattern is valid. `glam`'s `Vec3` has the same layout.
+    #[cfg(feature = "bytemuck")]
+    unsafe impl bytemuck::Zeroable for Vec3 {}
+    #[cfg(feature = "bytemuck")]
+    unsafe impl bytemuck::Pod for Vec3 {}
+
     pub struct BVec3 {
         pub x: bool,
         pub y: bool,
@@ -61,6 +71,9 @@ mod math {
     }
 
     impl Vec3 {
+        pub const ZERO: Self = Self::splat(0.0);
+        pub const ONE: Self = Self::splat(1.0);
+
         pub const fn new(x: Float, y: Float, z: Float) -> Self {
             Self { x, y, z }
         }
@@ -116,6 +129,10 @@ mod math {
             self.x * other.x + self.y * other.y + self.z * other.z
         }
 
+        pub fn is_finite(self) -> bool {
+            self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+        }
+
         pub fn abs_diff_eq(self, other: Self, max_abs_diff: Float) -> bool {
             (self.x - other.x).abs() <= max_abs_diff
                 && (self.y - other.y).abs() <= max_abs_diff
@@ -142,6 +159,12 @@ mod math {
         }
     }
 
+    impl AddAssign for Vec3 {
+        fn add_assign(&mut self, other: Self) {
+            *self = *self + other
+        }
+    }
+
     impl Sub for Vec3 {
         type Output = Self;
 
@@ -231,6 +254,22 @@ mod math {
             z_axis: Vec3::new(0.0, 0.0, 1.0),
         };
 
+        pub const fn from_cols(x_axis: Vec3, y_axis: Vec3, z_axis: Vec3) -> Self {
+            Self {
+                x_axis,
+                y_axis,
+                z_axis,
+            }
+        }
+
+        pub const fn from_diagonal(diagonal: Vec3) -> Self {
+            Self {
+                x_axis: Vec3::new(diagonal.x, 0.0, 0.0),
+                y_axis: Vec3::new(0.0, diagonal.y, 0.0),
+                z_axis: Vec3::new(0.0, 0.0, diagonal.z),
+            }
+        }
+
         pub const fn from_cols_array(m: &[Float; 9]) -> Self {
             Self {
                 x_axis: Vec3::new(m[0], m[1], m[2]),
@@ -247,6 +286,20 @@ mod math {
             }
         }
 
+        pub fn abs_diff_eq(&self, other: Self, max_abs_diff: Float) -> bool {
+            self.x_axis.abs_diff_eq(other.x_axis, max_abs_diff)
+                && self.y_axis.abs_diff_eq(other.y_axis, max_abs_diff)
+                && self.z_axis.abs_diff_eq(other.z_axis, max_abs_diff)
+        }
+
+        pub fn determinant(&self) -> Float {
+            self.z_axis.dot(Vec3::new(
+                self.x_axis.y * self.y_axis.z - self.x_axis.z * self.y_axis.y,
+                self.x_axis.z * self.y_axis.x - self.x_axis.x * self.y_axis.z,
+                self.x_axis.x * self.y_axis.y - self.x_axis.y * self.y_axis.x,
+            ))
+        }
+
         pub fn inverse(&self) -> Self {
             let m00 = self.x_axis.x;
             let m01 = self.y_axis.x;
@@ -329,6 +382,13 @@ mod math {
         }
     }
 
+    /// Formats like `glam`'s `Vec3`, i.e. `[x, y, z]`.
+    impl core::fmt::Display for Vec3 {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "[{}, {}, {}]", self.x, self.y, self.z)
+        }
+    }
+
     impl From<[Float; 3]> for Vec3 {
         fn from(values: [Float; 3]) -> Self {
             Self {
@@ -0,0 +1,49 @@
+//! Helpers for generating mipmaps.
+use super::color::{Color, ColorSpace};
+use crate::Vec3;
+
+/// Averages a 2×2 block of colors in linear light and returns the average in
+/// `out_space`, the core operation of generating a mipmap level.
+///
+/// The colors are converted into the linear form of `out_space` before
+/// averaging. Averaging encoded values instead, e.g. sRGB, darkens the mipmap
+/// levels, as the average of encoded values is darker than the encoded average
+/// of light.
+pub fn downsample_2x2(block: [Color; 4], out_space: ColorSpace) -> Color {
+    let linear_space = out_space.as_linear();
+    let sum = block.iter().fold(Vec3::splat(0.0), |sum, color| {
+        sum + color.to(linear_space).value
+    });
+    Color {
+        value: sum * 0.25,
+        space: linear_space,
+    }
+    .to(out_space)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::spaces;
+
+    #[test]
+    fn checkerboard_averages_to_linear_mid_gray() {
+        let black = Color::srgb(0.0, 0.0, 0.0);
+        let white = Color::srgb(1.0, 1.0, 1.0);
+        let block = [black, white, white, black];
+
+        let linear = downsample_2x2(block, spaces::LINEAR_SRGB);
+        assert!(
+            linear.value.abs_diff_eq(Vec3::splat(0.5), 1e-5),
+            "{linear:?}"
+        );
+
+        // Encoded, linear mid-gray is about 0.735 rather than 0.5.
+        let encoded = downsample_2x2(block, spaces::ENCODED_SRGB);
+        assert_eq!(encoded.space, spaces::ENCODED_SRGB);
+        assert!(
+            encoded.value.abs_diff_eq(Vec3::splat(0.735), 1e-3),
+            "{encoded:?}"
+        );
+    }
+}
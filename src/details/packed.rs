@@ -0,0 +1,348 @@
+//! Packing of colors into integer pixel formats, and plain data views of
+//! colors for uploading them to the GPU.
+//!
+//! With the `bytemuck` feature, [`ColorValue`] and [`Vec3`] implement
+//! `bytemuck::Pod` and `bytemuck::Zeroable`, so slices of them can be cast to
+//! `&[u8]` with e.g. `bytemuck::cast_slice()`. [`Color`] itself is not
+//! castable, as its [`ColorSpace`] is an enum with payloads; convert colors to
+//! one space and collect their [`ColorValue`]s instead.
+use super::color::{Color, ColorSpace, TransformFn, color_spaces};
+use crate::{Float, Vec3};
+
+/// Clamps `value` to [0, 1] and quantizes it to an integer in [0, `max`].
+fn quantize(value: Float, max: u32) -> u32 {
+    (value.clamp(0.0, 1.0) * max as Float + 0.5) as u32
+}
+
+/// The range of integer codes used by [`Color::quantize()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuantRange {
+    /// Uses all codes, `0..=2^bits - 1`.
+    Full,
+    /// The limited (studio or legal) range of video, which maps [0, 1] to
+    /// `16..=235` and chroma in [-0.5, 0.5] to `16..=240` for 8 bits, scaled
+    /// by `2^(bits - 8)` for more bits.
+    Limited,
+}
+
+/// Whether component `index` of colors in `space` is a color difference
+/// centered at 0, like Cb and Cr.
+fn is_chroma(space: ColorSpace, index: usize) -> bool {
+    index > 0
+        && matches!(
+            space.transform_function(),
+            Some(TransformFn::YCbCr(_) | TransformFn::IctCpPq | TransformFn::IctCpHlg)
+        )
+}
+
+/// Returns the scale and offset mapping a component to its integer code.
+fn quantization(bits: u8, range: QuantRange, chroma: bool) -> (Float, Float) {
+    match range {
+        QuantRange::Full => {
+            assert!((1..=16).contains(&bits), "{bits} bits are not in 1..=16");
+            let max = ((1u32 << bits) - 1) as Float;
+            let offset = if chroma {
+                (1u32 << (bits - 1)) as Float
+            } else {
+                0.0
+            };
+            (max, offset)
+        }
+        QuantRange::Limited => {
+            assert!((8..=16).contains(&bits), "{bits} bits are not in 8..=16");
+            let step = (1u32 << (bits - 8)) as Float;
+            if chroma {
+                (224.0 * step, 128.0 * step)
+            } else {
+                (219.0 * step, 16.0 * step)
+            }
+        }
+    }
+}
+
+impl Color {
+    /// Quantizes the components of the color in its own space to `bits`-bit
+    /// integer codes in `range`, e.g. for writing Y'CbCr or R'G'B' video.
+    ///
+    /// Components are clamped to [0, 1], or [-0.5, 0.5] for the chroma
+    /// components of Y'CbCr and ICtCp spaces, and rounded to the nearest code,
+    /// rounding halves up. Limited range codes therefore never leave the
+    /// nominal range, e.g. `16..=235` for 8-bit luma. Like in BT.2100, full
+    /// range chroma is centered at `2^(bits - 1)` and scaled by `2^bits - 1`,
+    /// so -0.5 maps to 1 and 0.5 to the largest code.
+    ///
+    /// # Panics
+    ///
+    /// If `bits` is not in `1..=16` for full or `8..=16` for limited range.
+    pub fn quantize(&self, bits: u8, range: QuantRange) -> [u16; 3] {
+        let value: [Float; 3] = self.value.into();
+        core::array::from_fn(|index| {
+            let chroma = is_chroma(self.space, index);
+            let (scale, offset) = quantization(bits, range, chroma);
+            let (min, max) = if chroma { (-0.5, 0.5) } else { (0.0, 1.0) };
+            let code = value[index].clamp(min, max) * scale + offset + 0.5;
+            // Full range chroma of 0.5 reaches one code past the maximum.
+            code.min(((1u32 << bits) - 1) as Float) as u16
+        })
+    }
+
+    /// Creates a color in `space` from `bits`-bit integer codes in `range`,
+    /// the inverse of [`quantize()`](Self::quantize).
+    ///
+    /// Codes outside of the nominal range, e.g. below 16 in limited range,
+    /// give components outside of [0, 1], or [-0.5, 0.5] for chroma.
+    ///
+    /// # Panics
+    ///
+    /// Like [`quantize()`](Self::quantize).
+    pub fn from_quantized(
+        codes: [u16; 3],
+        bits: u8,
+        range: QuantRange,
+        space: ColorSpace,
+    ) -> Color {
+        let [x, y, z] = core::array::from_fn(|index| {
+            let (scale, offset) = quantization(bits, range, is_chroma(space, index));
+            (codes[index] as Float - offset) / scale
+        });
+        Color {
+            value: Vec3::new(x, y, z),
+            space,
+        }
+    }
+
+    /// Converts the color to encoded sRGB and packs it with `alpha` into a
+    /// `u32`, with red in the most and alpha in the least significant byte,
+    /// i.e. `0xRRGGBBAA`.
+    ///
+    /// Components outside of [0, 1] are clamped.
+    pub fn to_rgba8888(&self, alpha: u8) -> u32 {
+        let rgb = self.to(color_spaces::ENCODED_SRGB).value;
+        quantize(rgb.x, 0xFF) << 24
+            | quantize(rgb.y, 0xFF) << 16
+            | quantize(rgb.z, 0xFF) << 8
+            | alpha as u32
+    }
+
+    /// Converts the color to encoded sRGB and packs it into a `u16` with 5 bits
+    /// of red in the most significant bits, 6 bits of green and 5 bits of blue
+    /// in the least significant bits.
+    ///
+    /// Components outside of [0, 1] are clamped.
+    pub fn to_rgb565(&self) -> u16 {
+        let rgb = self.to(color_spaces::ENCODED_SRGB).value;
+        (quantize(rgb.x, 0x1F) << 11 | quantize(rgb.y, 0x3F) << 5 | quantize(rgb.z, 0x1F)) as u16
+    }
+}
+
+#[cfg(feature = "egui")]
+impl Color {
+    /// Converts the color to encoded sRGB and packs it with `alpha` into an
+    /// egui [`Color32`](ecolor::Color32).
+    ///
+    /// The components and `alpha` are clamped to [0, 1] and quantized to
+    /// `u8`, which egui then premultiplies with
+    /// [`Color32::from_rgba_unmultiplied()`](ecolor::Color32::from_rgba_unmultiplied).
+    pub fn to_color32(&self, alpha: Float) -> ecolor::Color32 {
+        let rgb = self.to(color_spaces::ENCODED_SRGB).value;
+        let [r, g, b, a] = [rgb.x, rgb.y, rgb.z, alpha].map(|value| quantize(value, 0xFF) as u8);
+        ecolor::Color32::from_rgba_unmultiplied(r, g, b, a)
+    }
+
+    /// Unpacks an egui [`Color32`](ecolor::Color32) into an encoded sRGB
+    /// color and its alpha.
+    ///
+    /// The color is unpremultiplied with
+    /// [`Color32::to_srgba_unmultiplied()`](ecolor::Color32::to_srgba_unmultiplied),
+    /// so packing it again with [`to_color32()`](Color::to_color32) returns the
+    /// same `Color32`. Fully transparent colors unpack to black.
+    pub fn from_color32(color: ecolor::Color32) -> (Color, Float) {
+        let [r, g, b, a] = color
+            .to_srgba_unmultiplied()
+            .map(|value| value as Float / 255.0);
+        (Color::srgb(r, g, b), a)
+    }
+}
+
+/// The components of a [`Color`] without its [`ColorSpace`], laid out as
+/// three consecutive [`Float`]s like `[Float; 3]` or a `vec3` in a shader.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorValue {
+    pub x: Float,
+    pub y: Float,
+    pub z: Float,
+}
+
+impl ColorValue {
+    pub const fn new(x: Float, y: Float, z: Float) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Interprets the components as a color in `space`.
+    pub fn to_color(self, space: ColorSpace) -> Color {
+        Color::new(self.x, self.y, self.z, space)
+    }
+}
+
+// SAFETY: `ColorValue` is `repr(C)` with three fields of the same float type,
+// so it has no padding, and any bit pattern, including all zeros, is valid.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for ColorValue {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for ColorValue {}
+
+impl From<Color> for ColorValue {
+    fn from(color: Color) -> Self {
+        color.to_value()
+    }
+}
+
+impl From<Vec3> for ColorValue {
+    fn from(value: Vec3) -> Self {
+        Self::new(value.x, value.y, value.z)
+    }
+}
+
+impl From<ColorValue> for Vec3 {
+    fn from(value: ColorValue) -> Self {
+        Vec3::new(value.x, value.y, value.z)
+    }
+}
+
+impl Color {
+    /// Returns the components of the color as plain data, dropping its space.
+    ///
+    /// Convert the color with [`to()`](Color::to) first to get the components
+    /// in another space.
+    pub fn to_value(&self) -> ColorValue {
+        self.value.into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pack_rgba8888() {
+        assert_eq!(Color::srgb(1.0, 1.0, 1.0).to_rgba8888(0xFF), 0xFFFFFFFF);
+        assert_eq!(Color::srgb(1.0, 0.0, 0.0).to_rgba8888(0x80), 0xFF000080);
+        assert_eq!(Color::srgb(0.0, 2.0, -1.0).to_rgba8888(0), 0x00FF0000);
+        assert_eq!(
+            Color::new(1.0, 1.0, 1.0, color_spaces::LINEAR_SRGB).to_rgba8888(0xFF),
+            0xFFFFFFFF
+        );
+    }
+
+    #[test]
+    fn pack_rgb565() {
+        assert_eq!(Color::srgb(1.0, 1.0, 1.0).to_rgb565(), 0xFFFF);
+        assert_eq!(Color::srgb(1.0, 0.0, 0.0).to_rgb565(), 0xF800);
+        assert_eq!(Color::srgb(0.0, 1.0, 0.0).to_rgb565(), 0x07E0);
+        assert_eq!(Color::srgb(0.0, 0.0, 1.0).to_rgb565(), 0x001F);
+        assert_eq!(Color::srgb(0.0, 0.0, 0.0).to_rgb565(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "egui")]
+    fn color32_round_trip() {
+        let opaque = Color::srgb(1.0, 0.5, 0.0).to_color32(1.0);
+        assert_eq!(opaque, ecolor::Color32::from_rgb(255, 128, 0));
+        let (color, alpha) = Color::from_color32(opaque);
+        assert_eq!(color.value, Color::srgb(1.0, 128.0 / 255.0, 0.0).value);
+        assert_eq!(alpha, 1.0);
+
+        for alpha in [0.0, 0.1, 0.5, 0.9, 1.0] {
+            for rgb in [[1.0, 0.5, 0.0], [0.2, 0.4, 0.6], [0.0, 0.0, 0.0]] {
+                let packed = Color::srgb(rgb[0], rgb[1], rgb[2]).to_color32(alpha);
+                assert_eq!(packed.a(), quantize(alpha, 0xFF) as u8);
+                let (color, unpacked_alpha) = Color::from_color32(packed);
+                assert_eq!(unpacked_alpha, packed.a() as Float / 255.0);
+                assert_eq!(color.to_color32(unpacked_alpha), packed, "{rgb:?} {alpha}");
+            }
+        }
+    }
+
+    #[test]
+    fn quantize_extremes() {
+        let ycbcr = color_spaces::YCBCR_BT709;
+        let cases = [
+            (Vec3::new(0.0, -0.5, -0.5), [16, 16, 16], [0, 1, 1]),
+            (Vec3::new(1.0, 0.5, 0.5), [235, 240, 240], [255, 255, 255]),
+            (Vec3::new(0.5, 0.0, 0.0), [126, 128, 128], [128, 128, 128]),
+            (Vec3::new(2.0, -1.0, 1.0), [235, 16, 240], [255, 1, 255]),
+        ];
+        for (value, limited, full) in cases {
+            let color = Color::new(value.x, value.y, value.z, ycbcr);
+            assert_eq!(color.quantize(8, QuantRange::Limited), limited, "{value:?}");
+            assert_eq!(color.quantize(8, QuantRange::Full), full, "{value:?}");
+        }
+        let white = Color::new(1.0, 0.0, 0.0, ycbcr);
+        assert_eq!(white.quantize(10, QuantRange::Limited), [940, 512, 512]);
+        assert_eq!(white.quantize(10, QuantRange::Full), [1023, 512, 512]);
+
+        let rgb = Color::srgb(1.0, 0.0, 0.5);
+        assert_eq!(rgb.quantize(8, QuantRange::Limited), [235, 16, 126]);
+        assert_eq!(rgb.quantize(8, QuantRange::Full), [255, 0, 128]);
+        assert_eq!(rgb.quantize(16, QuantRange::Full), [65535, 0, 32768]);
+    }
+
+    #[test]
+    fn quantized_round_trip() {
+        let ycbcr = color_spaces::YCBCR_BT709;
+        let black = Color::from_quantized([16, 128, 128], 8, QuantRange::Limited, ycbcr);
+        assert_eq!(black.value, Vec3::new(0.0, 0.0, 0.0));
+        let white = Color::from_quantized([940, 512, 512], 10, QuantRange::Limited, ycbcr);
+        assert_eq!(white.value, Vec3::new(1.0, 0.0, 0.0));
+        let super_white = Color::from_quantized([255, 128, 128], 8, QuantRange::Limited, ycbcr);
+        assert!(super_white.value.x > 1.0);
+
+        for range in [QuantRange::Full, QuantRange::Limited] {
+            for bits in [8, 10, 12] {
+                for codes in [[16, 16, 16], [100, 50, 200], [235, 240, 240]] {
+                    let codes = codes.map(|code: u16| code << (bits - 8));
+                    let color = Color::from_quantized(codes, bits, range, ycbcr);
+                    assert_eq!(color.quantize(bits, range), codes, "{range:?} {bits}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn color_values() {
+        let color = Color::srgb(0.25, 0.5, 0.75);
+        let value = ColorValue::from(color);
+        assert_eq!(value, ColorValue::new(0.25, 0.5, 0.75));
+        assert_eq!(Vec3::from(value), color.value);
+        let back = value.to_color(color_spaces::ENCODED_SRGB);
+        assert_eq!(back.value, color.value);
+        assert_eq!(back.space, color.space);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn cast_color_values_to_bytes() {
+        let values = [
+            Color::srgb(1.0, 0.0, 0.0).to_value(),
+            Color::srgb(0.0, 0.5, 1.0).to_value(),
+        ];
+        let floats: &[Float] = bytemuck::cast_slice(&values);
+        assert_eq!(floats, [1.0, 0.0, 0.0, 0.0, 0.5, 1.0]);
+        let bytes: &[u8] = bytemuck::cast_slice(&values);
+        assert_eq!(bytes.len(), 6 * core::mem::size_of::<Float>());
+        let vectors: &[Vec3] = bytemuck::cast_slice(&values);
+        assert_eq!(vectors[1], Vec3::new(0.0, 0.5, 1.0));
+        assert_eq!(
+            <ColorValue as bytemuck::Zeroable>::zeroed(),
+            ColorValue::default()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn limited_range_needs_8_bits() {
+        Color::srgb(0.5, 0.5, 0.5).quantize(6, QuantRange::Limited);
+    }
+}
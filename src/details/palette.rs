@@ -0,0 +1,301 @@
+//! Generation of palettes from colors.
+use super::{
+    color::{Color, ColorSpace, color_spaces},
+    gamut::{max_oklch_chroma, rgb_gamut_space},
+};
+use crate::{ColorConversion, Float, PI, Vec3};
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+use num_traits::Float as _;
+
+/// Lightness of the darkest entry of a [tonal palette](Color::tonal_palette).
+const TONAL_PALETTE_MIN_LIGHTNESS: Float = 0.05;
+/// Lightness of the brightest entry of a [tonal palette](Color::tonal_palette).
+const TONAL_PALETTE_MAX_LIGHTNESS: Float = 0.97;
+
+/// Number of clusters [`generate_theme()`] groups the colors into.
+const THEME_CLUSTERS: usize = 4;
+/// Number of k-means iterations of [`generate_theme()`].
+const THEME_ITERATIONS: usize = 10;
+/// Added to the mean Oklch chroma of a cluster when weighting it, so that
+/// clusters of achromatic colors are still ranked by population.
+const THEME_CHROMA_BIAS: Float = 0.02;
+
+/// Generates the entries of a tonal palette of `count` entries of `color`.
+struct TonalPalette {
+    chroma: Float,
+    hue: Float,
+    count: usize,
+    to_rgb: ColorConversion,
+    space: ColorSpace,
+}
+
+impl TonalPalette {
+    fn new(color: &Color, count: usize) -> Self {
+        let lch = color.to(color_spaces::OK_LCH);
+        Self {
+            chroma: lch.value.y,
+            hue: lch.value.z,
+            count,
+            to_rgb: ColorConversion::new(color_spaces::OK_LCH, rgb_gamut_space(color.space)),
+            space: color.space,
+        }
+    }
+
+    fn entry(&self, i: usize) -> Color {
+        let t = if self.count > 1 {
+            i as Float / (self.count - 1) as Float
+        } else {
+            0.5
+        };
+        let lightness = TONAL_PALETTE_MIN_LIGHTNESS
+            + t * (TONAL_PALETTE_MAX_LIGHTNESS - TONAL_PALETTE_MIN_LIGHTNESS);
+        let chroma = self
+            .chroma
+            .min(max_oklch_chroma(&self.to_rgb, lightness, self.hue));
+        Color::new(lightness, chroma, self.hue, color_spaces::OK_LCH).to(self.space)
+    }
+}
+
+impl Color {
+    /// Creates `count` tints and shades of this color, similar to Material
+    /// Design's tonal palettes.
+    ///
+    /// The entries are evenly spaced in Oklab lightness, from near-black to
+    /// near-white, ordered from dark to light. Hue and chroma of `self` are
+    /// kept, except where the chroma does not fit the gamut of this color's
+    /// space (linear sRGB for spaces without RGB primaries) at a given
+    /// lightness, in which case it is reduced to the largest chroma that does.
+    ///
+    /// The returned colors are in the same space as `self`.
+    #[cfg(feature = "std")]
+    pub fn tonal_palette(&self, count: usize) -> Vec<Color> {
+        let palette = TonalPalette::new(self, count);
+        (0..count).map(|i| palette.entry(i)).collect()
+    }
+
+    /// Like [`tonal_palette()`](Self::tonal_palette), but with a capacity
+    /// fixed at compile time, so it can be used without an allocator.
+    pub fn tonal_palette_array<const N: usize>(&self) -> [Color; N] {
+        let palette = TonalPalette::new(self, N);
+        core::array::from_fn(|i| palette.entry(i))
+    }
+}
+
+/// How [`generate_theme()`] derives the other colors of a [`Theme`] from its
+/// primary color, as hue rotations in Oklch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HarmonyScheme {
+    /// The secondary color is a neighbor of the primary hue, the accent its
+    /// opposite.
+    Complementary,
+    /// The secondary and accent colors are neighbors of the primary hue, 30°
+    /// to either side.
+    Analogous,
+    /// The three colors are evenly spaced around the hue circle.
+    Triadic,
+    /// The secondary and accent colors are 30° to either side of the
+    /// opposite of the primary hue.
+    SplitComplementary,
+}
+
+impl HarmonyScheme {
+    /// Returns the hue rotations in degrees of the secondary and accent
+    /// colors, relative to the primary color.
+    pub fn hue_offsets(self) -> [Float; 2] {
+        match self {
+            HarmonyScheme::Complementary => [30.0, 180.0],
+            HarmonyScheme::Analogous => [-30.0, 30.0],
+            HarmonyScheme::Triadic => [120.0, 240.0],
+            HarmonyScheme::SplitComplementary => [150.0, 210.0],
+        }
+    }
+}
+
+/// Named color roles of a UI theme, as created by [`generate_theme()`].
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Theme {
+    pub primary: Color,
+    pub secondary: Color,
+    pub accent: Color,
+}
+
+/// Creates a [`Theme`] from the colors of an image, e.g. its pixels.
+///
+/// The colors are grouped with k-means clustering in Oklab. The primary color
+/// is the mean of the cluster with the largest population weighted by its
+/// mean Oklch chroma, so that a few vivid colors can win against a large dull
+/// background. The secondary and accent colors have the lightness and chroma
+/// of the primary color, with the hue rotated according to `scheme`. As in
+/// [tonal palettes](Color::tonal_palette), the chroma of each color is reduced
+/// where it does not fit the gamut.
+///
+/// The returned colors are in the space of the first color. The clustering is
+/// deterministic, so equal inputs always give equal themes.
+///
+/// # Panics
+///
+/// Panics if `colors` is empty.
+pub fn generate_theme(colors: &[Color], scheme: HarmonyScheme) -> Theme {
+    assert!(!colors.is_empty(), "cannot generate a theme without colors");
+    let lab = |color: &Color| color.to(color_spaces::OK_LAB).value;
+
+    // Farthest-point initialization, which is deterministic and spreads the
+    // initial centroids over the colors.
+    let mut centroids = [lab(&colors[0]); THEME_CLUSTERS];
+    for k in 1..THEME_CLUSTERS {
+        let mut farthest = (0.0, centroids[0]);
+        for color in colors {
+            let value = lab(color);
+            let distance = nearest_centroid(&centroids[..k], value).1;
+            if distance > farthest.0 {
+                farthest = (distance, value);
+            }
+        }
+        centroids[k] = farthest.1;
+    }
+
+    let mut counts = [0usize; THEME_CLUSTERS];
+    let mut chromas = [0.0; THEME_CLUSTERS];
+    for _ in 0..THEME_ITERATIONS {
+        let mut sums = [Vec3::splat(0.0); THEME_CLUSTERS];
+        counts = [0; THEME_CLUSTERS];
+        chromas = [0.0; THEME_CLUSTERS];
+        for color in colors {
+            let value = lab(color);
+            let k = nearest_centroid(&centroids, value).0;
+            sums[k] += value;
+            counts[k] += 1;
+            chromas[k] += (value.y * value.y + value.z * value.z).sqrt();
+        }
+        for k in 0..THEME_CLUSTERS {
+            if counts[k] > 0 {
+                centroids[k] = sums[k] * (1.0 / counts[k] as Float);
+            }
+        }
+    }
+
+    let mut dominant = 0;
+    let mut best_score = -1.0;
+    for k in 0..THEME_CLUSTERS {
+        let score = chromas[k] + THEME_CHROMA_BIAS * counts[k] as Float;
+        if counts[k] > 0 && score > best_score {
+            best_score = score;
+            dominant = k;
+        }
+    }
+
+    let space = colors[0].space;
+    let lch = Color::new(
+        centroids[dominant].x,
+        centroids[dominant].y,
+        centroids[dominant].z,
+        color_spaces::OK_LAB,
+    )
+    .to(color_spaces::OK_LCH)
+    .value;
+    let to_rgb = ColorConversion::new(color_spaces::OK_LCH, rgb_gamut_space(space));
+    let [lightness, chroma, hue] = [lch.x, lch.y, lch.z];
+    let rotated = |degrees: Float| {
+        let hue = hue + degrees * (PI / 180.0);
+        let chroma = chroma.min(max_oklch_chroma(&to_rgb, lightness, hue));
+        Color::new(lightness, chroma, hue, color_spaces::OK_LCH).to(space)
+    };
+    let [secondary, accent] = scheme.hue_offsets();
+    Theme {
+        primary: rotated(0.0),
+        secondary: rotated(secondary),
+        accent: rotated(accent),
+    }
+}
+
+/// Returns the index of and squared distance to the centroid nearest to
+/// `value`.
+fn nearest_centroid(centroids: &[Vec3], value: Vec3) -> (usize, Float) {
+    let mut nearest = (0, Float::MAX);
+    for (k, centroid) in centroids.iter().enumerate() {
+        let difference = value - *centroid;
+        let distance = difference.dot(difference);
+        if distance < nearest.1 {
+            nearest = (k, distance);
+        }
+    }
+    nearest
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::details::gamut::in_unit_cube;
+    use crate::spaces;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn tonal_palette_is_monotonic_and_in_gamut() {
+        let brand = Color::srgb(0.1, 0.4, 0.9);
+        let palette = brand.tonal_palette(11);
+        assert_eq!(palette.len(), 11);
+
+        let mut previous_lightness = -1.0;
+        for color in &palette {
+            assert_eq!(color.space, spaces::ENCODED_SRGB);
+            let lightness = color.to(spaces::OK_LAB).value.x;
+            assert!(
+                lightness > previous_lightness,
+                "{lightness} <= {previous_lightness}"
+            );
+            previous_lightness = lightness;
+            assert!(
+                in_unit_cube(color.value, 1e-4),
+                "{:?} is not in the sRGB gamut",
+                color.value
+            );
+        }
+    }
+
+    #[test]
+    fn tonal_palette_array_matches_tonal_palette() {
+        let brand = Color::srgb(0.1, 0.4, 0.9);
+        let palette: [Color; 5] = brand.tonal_palette_array();
+        #[cfg(feature = "std")]
+        for (a, b) in palette.iter().zip(brand.tonal_palette(5)) {
+            assert_eq!(a.value, b.value);
+        }
+        assert!(palette[0].to(spaces::OK_LAB).value.x < palette[4].to(spaces::OK_LAB).value.x);
+    }
+
+    #[test]
+    fn theme_of_mostly_blue_image_is_blue() {
+        let mut pixels = [Color::srgb(0.1, 0.2, 0.8); 20];
+        for (i, pixel) in pixels.iter_mut().enumerate() {
+            let shade = i as Float / 40.0;
+            if i % 5 == 0 {
+                *pixel = Color::srgb(0.8 + shade, 0.3, 0.1);
+            } else if i % 7 == 0 {
+                *pixel = Color::srgb(0.5, 0.5, 0.5);
+            } else {
+                *pixel = Color::srgb(0.1, 0.2 + shade, 0.8 - shade);
+            }
+        }
+        let theme = generate_theme(&pixels, HarmonyScheme::Triadic);
+        assert_eq!(theme.primary.space, spaces::ENCODED_SRGB);
+        let primary = theme.primary.value;
+        assert!(
+            primary.z > primary.x && primary.z > primary.y,
+            "{primary:?} is not blue"
+        );
+
+        let hue = |color: &Color| color.to(spaces::OK_LCH).value.z;
+        let difference = hue(&theme.secondary) - hue(&theme.primary);
+        let rotation = 2.0 * PI / 3.0;
+        assert!(
+            (difference.cos() - rotation.cos()).abs() < 1e-2
+                && (difference.sin() - rotation.sin()).abs() < 1e-2,
+            "{difference} is not a rotation by 120°"
+        );
+        for color in [theme.primary, theme.secondary, theme.accent] {
+            assert!(in_unit_cube(color.value, 1e-4), "{:?}", color.value);
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Reference dataset of known color conversions, to verify the conversions of
+//! `kolor` against.
+//!
+//! The samples are curated from published references: the matrices and
+//! transfer functions of the specifications defining the color spaces (IEC
+//! 61966-2-1 for sRGB, SMPTE ST 2084 for PQ, the ACES S-2014-004 and
+//! S-2014-003 specifications), Björn Ottosson's Oklab test values and CIELAB
+//! values computed with [colour-science](https://www.colour-science.org).
+//!
+//! Each sample has its own tolerance, documenting how close `kolor` is
+//! expected to get. Conversions involving a chromatic adaptation have larger
+//! tolerances, as references commonly use the Bradford transform while
+//! `kolor` uses the [Sharp](super::cat::LmsConeSpace::Sharp) cone space.
+use super::color::{
+    Color, ColorSpace, RgbPrimaries, TransformFn, WhitePoint, color_spaces as spaces,
+};
+use crate::{Float, Vec3};
+
+/// A known conversion of `input` in `src` to `expected` in `dst`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ReferenceSample {
+    /// Where the reference value comes from.
+    pub source: &'static str,
+    pub src: ColorSpace,
+    pub dst: ColorSpace,
+    pub input: [Float; 3],
+    pub expected: [Float; 3],
+    /// The maximum absolute difference per component.
+    pub tolerance: Float,
+}
+
+/// A [`ReferenceSample`] which `kolor` converts differently than expected.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ReferenceMismatch {
+    pub sample: &'static ReferenceSample,
+    pub actual: Vec3,
+}
+
+impl core::fmt::Display for ReferenceMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}: {:?} -> {:?} converted {:?} to {:?}, expected {:?} ± {}",
+            self.sample.source,
+            self.sample.src,
+            self.sample.dst,
+            self.sample.input,
+            self.actual,
+            self.sample.expected,
+            self.sample.tolerance
+        )
+    }
+}
+
+const fn sample(
+    source: &'static str,
+    src: ColorSpace,
+    dst: ColorSpace,
+    input: [Float; 3],
+    expected: [Float; 3],
+    tolerance: Float,
+) -> ReferenceSample {
+    ReferenceSample {
+        source,
+        src,
+        dst,
+        input,
+        expected,
+        tolerance,
+    }
+}
+
+const LAB_D65: ColorSpace = ColorSpace::new(
+    RgbPrimaries::CieXyz,
+    WhitePoint::D65,
+    Some(TransformFn::CieLab),
+);
+
+/// The reference dataset.
+#[rustfmt::skip]
+pub const REFERENCE_SAMPLES: &[ReferenceSample] = &[
+    // sRGB transfer function.
+    sample("IEC 61966-2-1", spaces::ENCODED_SRGB, spaces::LINEAR_SRGB, [0.5, 0.04045, 1.0], [0.214041, 0.0031308, 1.0], 1e-5),
+    sample("IEC 61966-2-1", spaces::LINEAR_SRGB, spaces::ENCODED_SRGB, [0.18, 0.0031308, 0.0], [0.461356, 0.04045, 0.0], 1e-5),
+    // sRGB primaries to CIE XYZ.
+    sample("IEC 61966-2-1", spaces::LINEAR_SRGB, spaces::CIE_XYZ, [1.0, 0.0, 0.0], [0.4124, 0.2126, 0.0193], 1e-3),
+    sample("IEC 61966-2-1", spaces::LINEAR_SRGB, spaces::CIE_XYZ, [0.0, 1.0, 0.0], [0.3576, 0.7152, 0.1192], 1e-3),
+    sample("IEC 61966-2-1", spaces::LINEAR_SRGB, spaces::CIE_XYZ, [0.0, 0.0, 1.0], [0.1805, 0.0722, 0.9505], 1e-3),
+    // ACEScg to ACES2065-1, both D60.
+    sample("ACES S-2014-004", spaces::ACES_CG, spaces::ACES_2065_1, [1.0, 0.0, 0.0], [0.6954522, 0.0447946, -0.0055259], 2e-4),
+    sample("ACES S-2014-004", spaces::ACES_CG, spaces::ACES_2065_1, [0.0, 1.0, 0.0], [0.1406787, 0.8596711, 0.0040252], 2e-4),
+    sample("ACES S-2014-004", spaces::ACES_CG, spaces::ACES_2065_1, [0.0, 0.0, 1.0], [0.1638691, 0.0955343, 1.0015007], 2e-4),
+    // ACEScc and ACEScct.
+    sample("ACES S-2014-003", spaces::ACES_CG, spaces::ACES_CC, [0.18, 1.0, 0.0], [0.4135884, 0.5547945, -0.3584475], 1e-5),
+    sample("ACES S-2016-001", spaces::ACES_CG, spaces::ACES_CCT, [0.18, 1.0, 0.0], [0.4135884, 0.5547945, 0.0729055], 1e-5),
+    // Linear sRGB to ACEScg, D65 to D60.
+    sample("ACES (Bradford)", spaces::LINEAR_SRGB, spaces::ACES_CG, [1.0, 0.0, 0.0], [0.6131, 0.0702, 0.0206], 5e-3),
+    sample("ACES (Bradford)", spaces::LINEAR_SRGB, spaces::ACES_CG, [0.0, 1.0, 0.0], [0.3395, 0.9164, 0.1096], 5e-3),
+    sample("ACES (Bradford)", spaces::LINEAR_SRGB, spaces::ACES_CG, [0.0, 0.0, 1.0], [0.0474, 0.0134, 0.8698], 5e-3),
+    // Display P3 to sRGB, both D65.
+    sample("colour-science", spaces::ENCODED_DISPLAY_P3, spaces::LINEAR_SRGB, [0.0, 1.0, 0.0], [-0.2247, 1.0419, -0.0786], 1e-3),
+    // PQ.
+    sample("SMPTE ST 2084", spaces::BT_2020, spaces::ENCODED_BT_2100_PQ, [100.0, 1000.0, 10000.0], [0.508078, 0.751827, 1.0], 1e-4),
+    // Oklab.
+    sample("Ottosson", spaces::CIE_XYZ, spaces::OK_LAB, [0.950, 1.000, 1.089], [1.000, 0.000, 0.000], 2e-3),
+    sample("Ottosson", spaces::CIE_XYZ, spaces::OK_LAB, [1.000, 0.000, 0.000], [0.450, 1.236, -0.019], 2e-3),
+    sample("Ottosson", spaces::CIE_XYZ, spaces::OK_LAB, [0.000, 1.000, 0.000], [0.922, -0.671, 0.263], 2e-3),
+    sample("Ottosson", spaces::CIE_XYZ, spaces::OK_LAB, [0.000, 0.000, 1.000], [0.153, -1.415, -0.449], 2e-3),
+    // CIELAB, D65.
+    sample("colour-science", spaces::ENCODED_SRGB, LAB_D65, [1.0, 0.0, 0.0], [53.2408, 80.0925, 67.2032], 5e-2),
+    sample("colour-science", spaces::ENCODED_SRGB, LAB_D65, [0.0, 1.0, 0.0], [87.7347, -86.1827, 83.1793], 5e-2),
+    sample("colour-science", spaces::ENCODED_SRGB, LAB_D65, [0.0, 0.0, 1.0], [32.2970, 79.1875, -107.8602], 5e-2),
+    sample("colour-science", spaces::ENCODED_SRGB, LAB_D65, [1.0, 1.0, 1.0], [100.0, 0.0, 0.0], 5e-2),
+];
+
+/// Converts every sample of the [reference dataset](REFERENCE_SAMPLES) and
+/// returns the first one which doesn't match its expected value within its
+/// tolerance.
+pub fn verify_against_reference() -> Result<(), ReferenceMismatch> {
+    for sample in REFERENCE_SAMPLES {
+        let [x, y, z] = sample.input;
+        let actual = Color::new(x, y, z, sample.src).to(sample.dst).value;
+        if !actual.abs_diff_eq(Vec3::from(sample.expected), sample.tolerance) {
+            return Err(ReferenceMismatch { sample, actual });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn conversions_match_reference() {
+        if let Err(mismatch) = verify_against_reference() {
+            panic!("{mismatch}");
+        }
+    }
+}
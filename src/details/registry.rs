@@ -0,0 +1,233 @@
+//! Runtime registry of named white points, primaries and transform functions.
+//!
+//! Applications which define their own standard white points or primaries can
+//! register them here, so that [`WhitePoint::from_xy()`] and
+//! [`RgbPrimaries::from_rgb_xy()`] recognize them. Transform functions, e.g.
+//! camera log curves which `kolor` doesn't define, can be registered to be
+//! used in color spaces as [`TransformFn::Registered`]. The built-in white points
+//! and primaries always take precedence, registered values are only consulted
+//! when no built-in one matches. Recognized registered values are returned as
+//! `Custom` variants holding the exact registered values.
+//!
+//! The registry is global and can be used from multiple threads.
+use super::{
+    color::{RgbPrimaries, TransformFn, WhitePoint},
+    transform::{RegisteredTransform, RegisteredTransformFns},
+};
+use crate::{Float, Vec3};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+type Registry<T> = RwLock<Vec<(String, T)>>;
+
+static WHITE_POINTS: Registry<[Float; 3]> = RwLock::new(Vec::new());
+static PRIMARIES: Registry<[[Float; 2]; 3]> = RwLock::new(Vec::new());
+static TRANSFORMS: Registry<&'static RegisteredTransformFns> = RwLock::new(Vec::new());
+
+// Entries are plain values which can't be left half-written, so a poisoned
+// lock is still safe to use.
+fn read<T>(registry: &Registry<T>) -> RwLockReadGuard<'_, Vec<(String, T)>> {
+    registry
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn write<T>(registry: &Registry<T>) -> RwLockWriteGuard<'_, Vec<(String, T)>> {
+    registry
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn insert<T>(registry: &Registry<T>, name: &str, values: T) {
+    let mut entries = write(registry);
+    if let Some(entry) = entries.iter_mut().find(|(n, _)| n == name) {
+        entry.1 = values;
+    } else {
+        entries.push((name.to_string(), values));
+    }
+}
+
+/// Registers a white point given as CIE XYZ with Y=1.0 under `name`,
+/// replacing any white point previously registered under the same name, and
+/// returns the [`WhitePoint`] which detection returns for it.
+pub fn register_white_point(name: &str, xyz: [Float; 3]) -> WhitePoint {
+    insert(&WHITE_POINTS, name, xyz);
+    WhitePoint::Custom(xyz)
+}
+
+/// Registers primaries given as CIE xy chromaticity coordinates for R, G, B
+/// under `name`, replacing any primaries previously registered under the same
+/// name, and returns the [`RgbPrimaries`] which detection returns for them.
+pub fn register_primaries(name: &str, chromaticities: [[Float; 2]; 3]) -> RgbPrimaries {
+    insert(&PRIMARIES, name, chromaticities);
+    RgbPrimaries::Custom(chromaticities)
+}
+
+/// Registers a transform function with the function applying it, from linear
+/// to encoded values, and its inverse under `name`, and returns the
+/// [`TransformFn`] to use it in color spaces.
+///
+/// Registering a name again replaces the functions found by name, e.g. by
+/// [`TransformFn::by_name()`] or when deserializing, while `TransformFn`s
+/// returned before keep using the previous functions. The functions and name
+/// are kept for the rest of the program, so registering allocates a little
+/// memory which is never freed.
+pub fn register_transform(
+    name: &str,
+    transform: fn(Vec3, WhitePoint) -> Vec3,
+    inverse: fn(Vec3, WhitePoint) -> Vec3,
+) -> TransformFn {
+    let fns: &'static RegisteredTransformFns = Box::leak(Box::new(RegisteredTransformFns {
+        name: Box::leak(name.into()),
+        fns: (transform, inverse),
+    }));
+    insert(&TRANSFORMS, name, fns);
+    TransformFn::Registered(RegisteredTransform::new(fns))
+}
+
+/// Returns the transform function registered under `name`.
+pub fn registered_transform(name: &str) -> Option<TransformFn> {
+    read(&TRANSFORMS)
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, fns)| TransformFn::Registered(RegisteredTransform::new(fns)))
+}
+
+/// Returns the white point registered under `name`.
+pub fn registered_white_point(name: &str) -> Option<WhitePoint> {
+    read(&WHITE_POINTS)
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, xyz)| WhitePoint::Custom(*xyz))
+}
+
+/// Returns the primaries registered under `name`.
+pub fn registered_primaries(name: &str) -> Option<RgbPrimaries> {
+    read(&PRIMARIES)
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, chromaticities)| RgbPrimaries::Custom(*chromaticities))
+}
+
+/// Finds the first registered white point for which `matches` is true.
+pub(crate) fn find_white_point(matches: impl Fn(&[Float; 3]) -> bool) -> Option<[Float; 3]> {
+    read(&WHITE_POINTS)
+        .iter()
+        .find(|(_, xyz)| matches(xyz))
+        .map(|(_, xyz)| *xyz)
+}
+
+/// Finds the first registered primaries for which `matches` is true.
+pub(crate) fn find_primaries(
+    matches: impl Fn(&[[Float; 2]; 3]) -> bool,
+) -> Option<[[Float; 2]; 3]> {
+    read(&PRIMARIES)
+        .iter()
+        .find(|(_, chromaticities)| matches(chromaticities))
+        .map(|(_, chromaticities)| *chromaticities)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Color, spaces as color_spaces};
+
+    // The registry is shared by all tests, so each test registers values
+    // under its own name and compares with the returned values.
+    #[test]
+    fn from_xy_detects_registered_white_point() {
+        let (x, y) = (0.3200, 0.3400);
+        let xyz = [x / y, 1.0, (1.0 - x - y) / y];
+
+        let studio = register_white_point("from_xy studio", xyz);
+        assert_eq!(studio, WhitePoint::Custom(xyz));
+        assert!(registered_white_point("from_xy studio").is_some_and(|found| found == studio));
+        // Detection snaps to the registered values.
+        assert_eq!(WhitePoint::from_xy(0.32001, 0.33999), studio);
+    }
+
+    #[test]
+    fn built_in_white_points_take_precedence() {
+        register_white_point("almost d65", [0.95047, 1.0, 1.08883]);
+        assert!(matches!(
+            WhitePoint::from_xy(0.31271, 0.32902),
+            WhitePoint::D65
+        ));
+    }
+
+    #[test]
+    fn from_rgb_xy_detects_registered_primaries() {
+        let chromaticities = [[0.69, 0.30], [0.21, 0.72], [0.14, 0.05]];
+        let studio = register_primaries("from_rgb_xy studio", chromaticities);
+        assert_eq!(studio, RgbPrimaries::Custom(chromaticities));
+        assert!(registered_primaries("from_rgb_xy studio").is_some_and(|found| found == studio));
+        assert_eq!(
+            RgbPrimaries::from_rgb_xy([0.69002, 0.30], [0.21, 0.71999], [0.14, 0.05]),
+            studio
+        );
+        assert_eq!(registered_primaries("unknown"), None);
+    }
+
+    fn gamma_2_6(color: Vec3, _: WhitePoint) -> Vec3 {
+        color.powf(1.0 / 2.6)
+    }
+
+    fn gamma_2_6_inverse(color: Vec3, _: WhitePoint) -> Vec3 {
+        color.powf(2.6)
+    }
+
+    #[test]
+    fn registered_transform_in_color_space() {
+        let gamma = register_transform("studio gamma 2.6", gamma_2_6, gamma_2_6_inverse);
+        assert!(matches!(gamma, TransformFn::Registered(_)));
+        assert_eq!(TransformFn::by_name("studio gamma 2.6"), Some(gamma));
+        // Built-in names still resolve to the built-in transform functions.
+        assert_eq!(TransformFn::by_name("srgb"), Some(TransformFn::Srgb));
+
+        let space = color_spaces::DISPLAY_P3
+            .with_transform(TransformFn::by_name("studio gamma 2.6").unwrap());
+        let color = Color::new(0.25, 0.5, 1.0, color_spaces::DISPLAY_P3).to(space);
+        let expected = Vec3::new(0.25, 0.5, 1.0).powf(1.0 / 2.6);
+        assert!(color.value.abs_diff_eq(expected, 1e-6), "{color:?}");
+        let linear = color.to(color_spaces::DISPLAY_P3).value;
+        assert!(
+            linear.abs_diff_eq(Vec3::new(0.25, 0.5, 1.0), 1e-5),
+            "{linear:?}"
+        );
+    }
+
+    fn halve(color: Vec3, _: WhitePoint) -> Vec3 {
+        color * 0.5
+    }
+
+    fn double(color: Vec3, _: WhitePoint) -> Vec3 {
+        color * 2.0
+    }
+
+    #[test]
+    fn registering_again_keeps_earlier_handles() {
+        let gamma = register_transform("replaced gamma", gamma_2_6, gamma_2_6_inverse);
+        let halving = register_transform("replaced gamma", halve, double);
+        assert_ne!(gamma, halving);
+        assert_eq!(TransformFn::by_name("replaced gamma"), Some(halving));
+
+        let value = Vec3::new(0.25, 0.5, 1.0);
+        let encode = |transform_fn| {
+            Color::new(value.x, value.y, value.z, color_spaces::LINEAR_SRGB)
+                .to(color_spaces::LINEAR_SRGB.with_transform(transform_fn))
+                .value
+        };
+        assert!(encode(gamma).abs_diff_eq(value.powf(1.0 / 2.6), 1e-6));
+        assert_eq!(encode(halving), value * 0.5);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn registered_transforms_serialize_by_name() {
+        let halving = register_transform("serialized halving", halve, double);
+        let json = serde_json::to_string(&halving).unwrap();
+        assert_eq!(json, r#"{"Registered":"serialized halving"}"#);
+        let back: TransformFn = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, halving);
+        assert!(serde_json::from_str::<TransformFn>(r#"{"Registered":"unknown"}"#).is_err());
+    }
+}
@@ -0,0 +1,731 @@
+//! Spectral quantities and their conversion to CIE XYZ.
+//!
+//! With the `cie-cmf-tables` feature, the CIE 1931 2° and CIE 1964 10°
+//! standard observers use the color matching functions tabulated by the CIE
+//! in 5nm steps, interpolated linearly in between. Without it, they are
+//! approximated with the multi-lobe and single-lobe fits of Wyman, Sloan and
+//! Shirley, "Simple Analytic Approximations to the CIE XYZ Color Matching
+//! Functions" (JCGT 2013), which avoid bundling tabulated data. Spectra, like
+//! reflectances and illuminants, are sampled in 5nm steps as a [`Spectrum`].
+use super::{
+    color::{Color, ColorSpace, RgbPrimaries, WhitePoint},
+    diagram::DiagramType,
+    transform::xyz_to_cie_lab,
+};
+use crate::{Float, Vec3};
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+use num_traits::Float as _;
+
+/// The range of wavelengths in nanometers which is integrated over when
+/// converting a spectrum to CIE XYZ.
+const WAVELENGTH_RANGE: core::ops::RangeInclusive<u16> = 360..=830;
+
+/// First radiation constant for spectral radiance, `2hc²`, in
+/// W·µm⁴·m⁻²·sr⁻¹.
+const PLANCK_C1: Float = 1.191_042_972e8;
+/// Second radiation constant, `hc/k`, in µm·K.
+const PLANCK_C2: Float = 1.438_776_877e4;
+
+/// Piecewise gaussian with different widths left and right of the mean.
+#[cfg(not(feature = "cie-cmf-tables"))]
+fn lobe(wavelength: Float, mean: Float, left_width: Float, right_width: Float) -> Float {
+    let width = if wavelength < mean {
+        left_width
+    } else {
+        right_width
+    };
+    let t = (wavelength - mean) / width;
+    (-0.5 * t * t).exp()
+}
+
+/// The tabulated CIE 1931 2° color matching functions in 5nm steps from 360nm
+/// to 830nm, as published by the CIE.
+#[cfg(feature = "cie-cmf-tables")]
+#[rustfmt::skip]
+const CIE_1931_CMF_TABLE: [[Float; 3]; SPECTRUM_SAMPLES] = [
+    [0.0001299, 3.917e-06, 0.0006061],
+    [0.0002321, 6.965e-06, 0.001086],
+    [0.0004149, 1.239e-05, 0.001946],
+    [0.0007416, 2.202e-05, 0.003486],
+    [0.001368, 3.9e-05, 0.006450001],
+    [0.002236, 6.4e-05, 0.01054999],
+    [0.004243, 0.00012, 0.02005001],
+    [0.00765, 0.000217, 0.03621],
+    [0.01431, 0.000396, 0.06785001],
+    [0.02319, 0.00064, 0.1102],
+    [0.04351, 0.00121, 0.2074],
+    [0.07763, 0.00218, 0.3713],
+    [0.13438, 0.004, 0.6456],
+    [0.21477, 0.0073, 1.0390501],
+    [0.2839, 0.0116, 1.3856],
+    [0.3285, 0.01684, 1.62296],
+    [0.34828, 0.023, 1.74706],
+    [0.34806, 0.0298, 1.7826],
+    [0.3362, 0.038, 1.77211],
+    [0.3187, 0.048, 1.7441],
+    [0.2908, 0.06, 1.6692],
+    [0.2511, 0.0739, 1.5281],
+    [0.19536, 0.09098, 1.28764],
+    [0.1421, 0.1126, 1.0419],
+    [0.09564, 0.13902, 0.8129501],
+    [0.05795001, 0.1693, 0.6162],
+    [0.03201, 0.20802, 0.46518],
+    [0.0147, 0.2586, 0.3533],
+    [0.0049, 0.323, 0.272],
+    [0.0024, 0.4073, 0.2123],
+    [0.0093, 0.503, 0.1582],
+    [0.0291, 0.6082, 0.1117],
+    [0.06327, 0.71, 0.07824999],
+    [0.1096, 0.7932, 0.05725001],
+    [0.1655, 0.862, 0.04216],
+    [0.2257499, 0.9148501, 0.02984],
+    [0.2904, 0.954, 0.0203],
+    [0.3597, 0.9803, 0.0134],
+    [0.4334499, 0.9949501, 0.008749999],
+    [0.5120501, 1.0, 0.005749999],
+    [0.5945, 0.995, 0.0039],
+    [0.6784, 0.9786, 0.002749999],
+    [0.7621, 0.952, 0.0021],
+    [0.8425, 0.9154, 0.0018],
+    [0.9163, 0.87, 0.001650001],
+    [0.9786, 0.8163, 0.0014],
+    [1.0263, 0.757, 0.0011],
+    [1.0567, 0.6949, 0.001],
+    [1.0622, 0.631, 0.0008],
+    [1.0456, 0.5668, 0.0006],
+    [1.0026, 0.503, 0.00034],
+    [0.9384, 0.4412, 0.00024],
+    [0.8544499, 0.381, 0.00019],
+    [0.7514, 0.321, 0.0001],
+    [0.6424, 0.265, 4.999999e-05],
+    [0.5419, 0.217, 3e-05],
+    [0.4479, 0.175, 2e-05],
+    [0.3608, 0.1382, 1e-05],
+    [0.2835, 0.107, 0.0],
+    [0.2187, 0.0816, 0.0],
+    [0.1649, 0.061, 0.0],
+    [0.1212, 0.04458, 0.0],
+    [0.0874, 0.032, 0.0],
+    [0.0636, 0.0232, 0.0],
+    [0.04677, 0.017, 0.0],
+    [0.0329, 0.01192, 0.0],
+    [0.0227, 0.00821, 0.0],
+    [0.01584, 0.005723, 0.0],
+    [0.01135916, 0.004102, 0.0],
+    [0.008110916, 0.002929, 0.0],
+    [0.005790346, 0.002091, 0.0],
+    [0.004109457, 0.001484, 0.0],
+    [0.002899327, 0.001047, 0.0],
+    [0.00204919, 0.00074, 0.0],
+    [0.001439971, 0.00052, 0.0],
+    [0.0009999493, 0.0003611, 0.0],
+    [0.0006900786, 0.0002492, 0.0],
+    [0.0004760213, 0.0001719, 0.0],
+    [0.0003323011, 0.00012, 0.0],
+    [0.0002348261, 8.48e-05, 0.0],
+    [0.0001661505, 6e-05, 0.0],
+    [0.000117413, 4.24e-05, 0.0],
+    [8.307527e-05, 3e-05, 0.0],
+    [5.870652e-05, 2.12e-05, 0.0],
+    [4.150994e-05, 1.499e-05, 0.0],
+    [2.935326e-05, 1.06e-05, 0.0],
+    [2.067383e-05, 7.4657e-06, 0.0],
+    [1.455977e-05, 5.2578e-06, 0.0],
+    [1.025398e-05, 3.7029e-06, 0.0],
+    [7.221456e-06, 2.6078e-06, 0.0],
+    [5.085868e-06, 1.8366e-06, 0.0],
+    [3.581652e-06, 1.2934e-06, 0.0],
+    [2.522525e-06, 9.1093e-07, 0.0],
+    [1.776509e-06, 6.4153e-07, 0.0],
+    [1.251141e-06, 4.5181e-07, 0.0],
+];
+
+/// The tabulated CIE 1964 10° color matching functions in 5nm steps from 360nm
+/// to 830nm, as published by the CIE.
+#[cfg(feature = "cie-cmf-tables")]
+#[rustfmt::skip]
+const CIE_1964_CMF_TABLE: [[Float; 3]; SPECTRUM_SAMPLES] = [
+    [1.222e-07, 1.3398e-08, 5.35027e-07],
+    [9.1927e-07, 1.0065e-07, 4.0283e-06],
+    [5.9586e-06, 6.511e-07, 2.61437e-05],
+    [3.3266e-05, 3.625e-06, 0.00014622],
+    [0.000159952, 1.7364e-05, 0.000704776],
+    [0.00066244, 7.156e-05, 0.0029278],
+    [0.0023616, 0.0002534, 0.0104822],
+    [0.0072423, 0.0007685, 0.032344],
+    [0.0191097, 0.0020044, 0.0860109],
+    [0.0434, 0.004509, 0.19712],
+    [0.084736, 0.008756, 0.389366],
+    [0.140638, 0.014456, 0.65676],
+    [0.204492, 0.021391, 0.972542],
+    [0.264737, 0.029497, 1.2825],
+    [0.314679, 0.038676, 1.55348],
+    [0.357719, 0.049602, 1.7985],
+    [0.383734, 0.062077, 1.96728],
+    [0.386726, 0.074704, 2.0273],
+    [0.370702, 0.089456, 1.9948],
+    [0.342957, 0.106256, 1.9007],
+    [0.302273, 0.128201, 1.74537],
+    [0.254085, 0.152761, 1.5549],
+    [0.195618, 0.18519, 1.31756],
+    [0.132349, 0.21994, 1.0302],
+    [0.080507, 0.253589, 0.772125],
+    [0.041072, 0.297665, 0.57006],
+    [0.016172, 0.339133, 0.415254],
+    [0.005132, 0.395379, 0.302356],
+    [0.003816, 0.460777, 0.218502],
+    [0.015444, 0.53136, 0.159249],
+    [0.037465, 0.606741, 0.112044],
+    [0.071358, 0.68566, 0.082248],
+    [0.117749, 0.761757, 0.060709],
+    [0.172953, 0.82333, 0.04305],
+    [0.236491, 0.875211, 0.030451],
+    [0.304213, 0.92381, 0.020584],
+    [0.376772, 0.961988, 0.013676],
+    [0.451584, 0.9822, 0.007918],
+    [0.529826, 0.991761, 0.003988],
+    [0.616053, 0.99911, 0.001091],
+    [0.705224, 0.99734, 0.0],
+    [0.793832, 0.98238, 0.0],
+    [0.878655, 0.955552, 0.0],
+    [0.951162, 0.915175, 0.0],
+    [1.01416, 0.868934, 0.0],
+    [1.0743, 0.825623, 0.0],
+    [1.11852, 0.777405, 0.0],
+    [1.1343, 0.720353, 0.0],
+    [1.12399, 0.658341, 0.0],
+    [1.0891, 0.593878, 0.0],
+    [1.03048, 0.527963, 0.0],
+    [0.95074, 0.461834, 0.0],
+    [0.856297, 0.398057, 0.0],
+    [0.75493, 0.339554, 0.0],
+    [0.647467, 0.283493, 0.0],
+    [0.53511, 0.228254, 0.0],
+    [0.431567, 0.179828, 0.0],
+    [0.34369, 0.140211, 0.0],
+    [0.268329, 0.107633, 0.0],
+    [0.2043, 0.081187, 0.0],
+    [0.152568, 0.060281, 0.0],
+    [0.11221, 0.044096, 0.0],
+    [0.0812606, 0.0318004, 0.0],
+    [0.05793, 0.0226017, 0.0],
+    [0.0408508, 0.0159051, 0.0],
+    [0.028623, 0.0111303, 0.0],
+    [0.0199413, 0.0077488, 0.0],
+    [0.013842, 0.0053751, 0.0],
+    [0.00957688, 0.00371774, 0.0],
+    [0.0066052, 0.00256456, 0.0],
+    [0.00455263, 0.00176847, 0.0],
+    [0.0031447, 0.00122239, 0.0],
+    [0.00217496, 0.00084619, 0.0],
+    [0.0015057, 0.00058644, 0.0],
+    [0.00104476, 0.00040741, 0.0],
+    [0.00072745, 0.000284041, 0.0],
+    [0.000508258, 0.00019873, 0.0],
+    [0.00035638, 0.00013955, 0.0],
+    [0.000250969, 9.8428e-05, 0.0],
+    [0.00017773, 6.9819e-05, 0.0],
+    [0.00012639, 4.9737e-05, 0.0],
+    [9.0151e-05, 3.55405e-05, 0.0],
+    [6.45258e-05, 2.5486e-05, 0.0],
+    [4.6339e-05, 1.83384e-05, 0.0],
+    [3.34117e-05, 1.3249e-05, 0.0],
+    [2.4209e-05, 9.6196e-06, 0.0],
+    [1.76115e-05, 7.0128e-06, 0.0],
+    [1.2855e-05, 5.1298e-06, 0.0],
+    [9.41363e-06, 3.76473e-06, 0.0],
+    [6.913e-06, 2.77081e-06, 0.0],
+    [5.09347e-06, 2.04613e-06, 0.0],
+    [3.7671e-06, 1.51677e-06, 0.0],
+    [2.79531e-06, 1.12809e-06, 0.0],
+    [2.082e-06, 8.4216e-07, 0.0],
+    [1.55314e-06, 6.297e-07, 0.0],
+];
+
+/// Interpolates `table`, sampled like a [`Spectrum`], linearly at
+/// `wavelength` in nanometers. Outside of the table, it is 0.
+#[cfg(feature = "cie-cmf-tables")]
+fn interpolate_cmf(table: &[[Float; 3]; SPECTRUM_SAMPLES], wavelength: Float) -> Vec3 {
+    let position = (wavelength - SPECTRUM_FIRST_WAVELENGTH) / SPECTRUM_WAVELENGTH_STEP;
+    if !(0.0..=(SPECTRUM_SAMPLES - 1) as Float).contains(&position) {
+        return Vec3::splat(0.0);
+    }
+    let index = (position as usize).min(SPECTRUM_SAMPLES - 2);
+    let t = position - index as Float;
+    let (a, b) = (Vec3::from(table[index]), Vec3::from(table[index + 1]));
+    a + (b - a) * t
+}
+
+/// Returns the CIE 1931 2° color matching functions `(x̄, ȳ, z̄)` at
+/// `wavelength` in nanometers.
+///
+/// With the `cie-cmf-tables` feature, this interpolates the tabulated CIE data
+/// and is 0 outside of 360nm to 830nm. Otherwise it is an analytic fit.
+#[cfg(feature = "cie-cmf-tables")]
+pub fn cie_1931_cmf(wavelength: Float) -> Vec3 {
+    interpolate_cmf(&CIE_1931_CMF_TABLE, wavelength)
+}
+
+/// Returns the CIE 1931 2° color matching functions `(x̄, ȳ, z̄)` at
+/// `wavelength` in nanometers.
+///
+/// This is an analytic fit, enable the `cie-cmf-tables` feature to use the
+/// tabulated CIE data instead.
+#[cfg(not(feature = "cie-cmf-tables"))]
+pub fn cie_1931_cmf(wavelength: Float) -> Vec3 {
+    Vec3::new(
+        1.056 * lobe(wavelength, 599.8, 37.9, 31.0) + 0.362 * lobe(wavelength, 442.0, 16.0, 26.7)
+            - 0.065 * lobe(wavelength, 501.1, 20.4, 26.2),
+        0.821 * lobe(wavelength, 568.8, 46.9, 40.5) + 0.286 * lobe(wavelength, 530.9, 16.3, 31.1),
+        1.217 * lobe(wavelength, 437.0, 11.8, 36.0) + 0.681 * lobe(wavelength, 459.0, 26.0, 13.8),
+    )
+}
+
+/// Returns the CIE 1964 10° color matching functions `(x̄₁₀, ȳ₁₀, z̄₁₀)` at
+/// `wavelength` in nanometers.
+///
+/// With the `cie-cmf-tables` feature, this interpolates the tabulated CIE data
+/// and is 0 outside of 360nm to 830nm. Otherwise it is an analytic fit.
+#[cfg(feature = "cie-cmf-tables")]
+pub fn cie_1964_cmf(wavelength: Float) -> Vec3 {
+    interpolate_cmf(&CIE_1964_CMF_TABLE, wavelength)
+}
+
+/// Returns the CIE 1964 10° color matching functions `(x̄₁₀, ȳ₁₀, z̄₁₀)` at
+/// `wavelength` in nanometers.
+///
+/// This is an analytic fit and less accurate than the tabulated CIE data of
+/// the `cie-cmf-tables` feature, especially in the tails. The fit is only
+/// defined between 265.8nm and 1338nm, which covers the visible range of
+/// 360nm to 830nm. Outside of it, the components which the fit doesn't define
+/// are 0.
+#[cfg(not(feature = "cie-cmf-tables"))]
+pub fn cie_1964_cmf(wavelength: Float) -> Vec3 {
+    let log_lobe = |scale: Float, width: Float, ratio: Float| {
+        if ratio <= 0.0 {
+            return 0.0;
+        }
+        scale * (-width * ratio.ln().powi(2)).exp()
+    };
+    let t = (wavelength - 556.1) / 46.14;
+    Vec3::new(
+        log_lobe(0.398, 1250.0, (wavelength + 570.1) / 1014.0)
+            + log_lobe(1.132, 234.0, (1338.0 - wavelength) / 743.5),
+        1.011 * (-0.5 * t * t).exp(),
+        log_lobe(2.060, 32.0, (wavelength - 265.8) / 180.4),
+    )
+}
+
+/// A CIE standard observer, i.e. a set of color matching functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Observer {
+    /// The CIE 1931 2° observer, which CIE XYZ and all color spaces in
+    /// `kolor` are defined with.
+    #[default]
+    Cie1931TwoDegree,
+    /// The CIE 1964 10° observer, for stimuli covering a larger field of
+    /// view.
+    Cie1964TenDegree,
+}
+
+impl Observer {
+    /// Returns the color matching functions of the observer at `wavelength`
+    /// in nanometers, see [`cie_1931_cmf()`] and [`cie_1964_cmf()`].
+    pub fn cmf(self, wavelength: Float) -> Vec3 {
+        match self {
+            Observer::Cie1931TwoDegree => cie_1931_cmf(wavelength),
+            Observer::Cie1964TenDegree => cie_1964_cmf(wavelength),
+        }
+    }
+}
+
+/// Returns the spectral radiance of a blackbody at temperature `kelvin` and
+/// `wavelength` in nanometers per Planck's law, in W·sr⁻¹·m⁻²·nm⁻¹.
+pub fn planck_radiance(wavelength: Float, kelvin: Float) -> Float {
+    let micrometers = wavelength * 1e-3;
+    let radiance =
+        PLANCK_C1 / (micrometers.powi(5) * ((PLANCK_C2 / (micrometers * kelvin)).exp() - 1.0));
+    // Per µm to per nm.
+    radiance * 1e-3
+}
+
+/// Integrates the spectral radiance of a blackbody at temperature `kelvin`
+/// against the CIE 1931 color matching functions in 1 nm steps.
+pub fn blackbody_xyz(kelvin: Float) -> Vec3 {
+    WAVELENGTH_RANGE.fold(Vec3::splat(0.0), |xyz, wavelength| {
+        let wavelength = wavelength as Float;
+        xyz + cie_1931_cmf(wavelength) * planck_radiance(wavelength, kelvin)
+    })
+}
+
+/// The number of samples of a [`Spectrum`].
+pub const SPECTRUM_SAMPLES: usize = 95;
+/// The wavelength in nanometers of the first sample of a [`Spectrum`].
+pub const SPECTRUM_FIRST_WAVELENGTH: Float = 360.0;
+/// The wavelength step in nanometers between the samples of a [`Spectrum`].
+pub const SPECTRUM_WAVELENGTH_STEP: Float = 5.0;
+
+/// A spectral distribution, e.g. the reflectance of a surface or the relative
+/// power of an illuminant, sampled in 5nm steps from 360nm to 830nm.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Spectrum(pub [Float; SPECTRUM_SAMPLES]);
+
+impl Spectrum {
+    /// Returns a spectrum with the same `value` at every wavelength, e.g. the
+    /// equal energy illuminant E or a perfect reflector.
+    pub const fn constant(value: Float) -> Self {
+        Self([value; SPECTRUM_SAMPLES])
+    }
+
+    /// Samples `f`, which is given the wavelength in nanometers.
+    pub fn from_fn(f: impl Fn(Float) -> Float) -> Self {
+        let mut values = [0.0; SPECTRUM_SAMPLES];
+        for (index, value) in values.iter_mut().enumerate() {
+            *value = f(Self::wavelength(index));
+        }
+        Self(values)
+    }
+
+    /// The relative spectral power of the CIE standard illuminant D65,
+    /// normalized to 100 at 560nm.
+    ///
+    /// The tabulated values from 380nm to 780nm are used, the power is zero
+    /// outside of them, where the color matching functions are negligible.
+    #[rustfmt::skip]
+    pub const D65: Spectrum = Spectrum([
+        0.0, 0.0, 0.0, 0.0, 49.9755, 52.3118, 54.6482, 68.7015,
+        82.7549, 87.1204, 91.486, 92.4589, 93.4318, 90.057, 86.6823, 95.7736,
+        104.865, 110.936, 117.008, 117.41, 117.812, 116.336, 114.861, 115.392,
+        115.923, 112.367, 108.811, 109.082, 109.354, 108.578, 107.802, 106.296,
+        104.79, 106.239, 107.689, 106.047, 104.405, 104.225, 104.046, 102.023,
+        100.0, 98.1671, 96.3342, 96.0611, 95.788, 92.2368, 88.6856, 89.3459,
+        90.0062, 89.8026, 89.5991, 88.6489, 87.6987, 85.4936, 83.2886, 83.4939,
+        83.6992, 81.863, 80.0268, 80.1207, 80.2146, 81.2462, 82.2778, 80.281,
+        78.2842, 74.0027, 69.7213, 70.6652, 71.6091, 72.979, 74.349, 67.9765,
+        61.604, 65.7448, 69.8856, 72.4863, 75.087, 69.3398, 63.5927, 55.0054,
+        46.4182, 56.6118, 66.8054, 65.0941, 63.3828, 0.0, 0.0, 0.0,
+        0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+    ]);
+
+    /// Returns the spectral radiance of a blackbody at temperature `kelvin`,
+    /// see [`planck_radiance()`]. At 2856K, this is the CIE standard
+    /// illuminant A.
+    pub fn blackbody(kelvin: Float) -> Self {
+        Self::from_fn(|wavelength| planck_radiance(wavelength, kelvin))
+    }
+
+    /// Returns the wavelength in nanometers of the sample at `index`.
+    pub fn wavelength(index: usize) -> Float {
+        SPECTRUM_FIRST_WAVELENGTH + index as Float * SPECTRUM_WAVELENGTH_STEP
+    }
+
+    /// Returns the CIE 1931 color matching functions weighted by `self` as an
+    /// illuminant, normalized so that a perfect reflector has a CIE Y of 1.
+    ///
+    /// These are the columns of the matrix mapping a reflectance to CIE XYZ
+    /// under the illuminant.
+    pub fn weighted_cmfs(&self) -> [Vec3; SPECTRUM_SAMPLES] {
+        let mut weighted = [Vec3::splat(0.0); SPECTRUM_SAMPLES];
+        let mut luminance = 0.0;
+        for (index, (cmf, power)) in weighted.iter_mut().zip(self.0).enumerate() {
+            *cmf = cie_1931_cmf(Self::wavelength(index)) * power;
+            luminance += cmf.y;
+        }
+        for cmf in &mut weighted {
+            *cmf *= luminance.recip();
+        }
+        weighted
+    }
+
+    /// Returns the CIE XYZ of `self` as a reflectance lit by `illuminant`,
+    /// relative to a perfect reflector having a CIE Y of 1.
+    pub fn reflected_xyz(&self, illuminant: &Spectrum) -> Vec3 {
+        illuminant
+            .weighted_cmfs()
+            .iter()
+            .zip(self.0)
+            .fold(Vec3::splat(0.0), |xyz, (cmf, reflectance)| {
+                xyz + *cmf * reflectance
+            })
+    }
+}
+
+/// Returns the CIELAB of `reflectance` lit by `illuminant`, relative to the
+/// CIE XYZ of a perfect reflector lit by it.
+fn reflected_lab(reflectance: &Spectrum, illuminant: &Spectrum) -> Vec3 {
+    let white = Spectrum::constant(1.0).reflected_xyz(illuminant);
+    xyz_to_cie_lab(
+        reflectance.reflected_xyz(illuminant),
+        WhitePoint::Custom([white.x, white.y, white.z]),
+    )
+}
+
+/// Returns the metamerism index of the reflectances `sample` and `reference`,
+/// the CIELAB ΔE*ab between them lit by `test_illuminant`, when they match
+/// when lit by `reference_illuminant`.
+///
+/// If the pair doesn't match exactly under the reference illuminant, the
+/// difference is corrected additively in CIELAB, as recommended by CIE 15.
+///
+/// As the colors of both reflectances are computed from the spectra, the
+/// result doesn't depend on a chromatic adaptation transform: CIELAB
+/// normalizes each pair by the white of its illuminant, which amounts to a
+/// von Kries adaptation in CIE XYZ. A colorimetric approximation, adapting the
+/// CIE XYZ of a pair from one illuminant to another, can't tell metamers
+/// apart, as they have the same CIE XYZ under the reference illuminant.
+pub fn metameric_index(
+    sample: &Spectrum,
+    reference: &Spectrum,
+    reference_illuminant: &Spectrum,
+    test_illuminant: &Spectrum,
+) -> Float {
+    let correction = reflected_lab(reference, reference_illuminant)
+        - reflected_lab(sample, reference_illuminant);
+    let sample = reflected_lab(sample, test_illuminant) + correction;
+    let difference = sample - reflected_lab(reference, test_illuminant);
+    difference.dot(difference).sqrt()
+}
+
+/// How the color of a blackbody emitter returned by [`Color::blackbody_in()`]
+/// is scaled.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlackbodyNormalization {
+    /// Scaled so the CIE Y (luminance) is 1.
+    UnitLuminance,
+    /// Scaled so the largest channel of the linear destination space is 1.
+    UnitMaxChannel,
+    /// Not scaled, i.e. CIE XYZ is the spectral radiance integrated against
+    /// the color matching functions, in W·sr⁻¹·m⁻².
+    AbsoluteRadiance,
+}
+
+impl Color {
+    /// Returns the color of a blackbody emitter at temperature `kelvin` in
+    /// `space`, scaled according to `normalization`.
+    ///
+    /// The emitted light is mapped into `space` without chromatic adaptation,
+    /// i.e. the result is the color of the emitter as seen by an observer
+    /// adapted to the white point of `space`.
+    pub fn blackbody_in(
+        kelvin: Float,
+        space: ColorSpace,
+        normalization: BlackbodyNormalization,
+    ) -> Color {
+        let mut xyz = blackbody_xyz(kelvin);
+        if normalization == BlackbodyNormalization::UnitLuminance {
+            xyz = xyz / xyz.y;
+        }
+        let linear_space = space.as_linear();
+        let mut linear = Color {
+            value: xyz,
+            space: ColorSpace::linear(RgbPrimaries::CieXyz, space.white_point()),
+        }
+        .to(linear_space);
+        if normalization == BlackbodyNormalization::UnitMaxChannel {
+            let max = linear.value.x.max(linear.value.y).max(linear.value.z);
+            linear.value *= max.recip();
+        }
+        linear.to(space)
+    }
+}
+
+/// Largest distance in CIE 1960 uv from the Planckian locus for which
+/// [`WhitePoint::cct()`] estimates a correlated color temperature.
+const CCT_MAX_LOCUS_DISTANCE: Float = 0.05;
+
+impl WhitePoint {
+    /// Creates a [`Custom`](WhitePoint::Custom) white point with Y=1 of the
+    /// given correlated color temperature, e.g. to simulate tungsten light or
+    /// daylight.
+    ///
+    /// Temperatures from 4000K to 25000K lie on the CIE daylight locus, like
+    /// the D series of illuminants, lower and higher temperatures on the
+    /// Planckian locus of blackbody emitters. Note that D65 has a temperature
+    /// of about 6504K, as the constants of Planck's law were revised after it
+    /// was defined.
+    pub fn from_cct(kelvin: Float) -> WhitePoint {
+        if (4000.0..=25000.0).contains(&kelvin) {
+            let t = kelvin;
+            let x = if t <= 7000.0 {
+                -4.6070e9 / (t * t * t) + 2.9678e6 / (t * t) + 0.09911e3 / t + 0.244063
+            } else {
+                -2.0064e9 / (t * t * t) + 1.9018e6 / (t * t) + 0.24748e3 / t + 0.237040
+            };
+            let y = -3.0 * x * x + 2.870 * x - 0.275;
+            return WhitePoint::Custom([x / y, 1.0, (1.0 - x - y) / y]);
+        }
+        let xyz = blackbody_xyz(kelvin);
+        let xyz = xyz / xyz.y;
+        WhitePoint::Custom([xyz.x, xyz.y, xyz.z])
+    }
+
+    /// Estimates the correlated color temperature of the white point in
+    /// kelvin with McCamy's approximation.
+    ///
+    /// Returns `None` for white points too far from the Planckian locus for a
+    /// correlated color temperature to be meaningful, i.e. more than 0.05
+    /// away in CIE 1960 uv.
+    pub fn cct(&self) -> Option<Float> {
+        let [x, y] = self.xy();
+        let n = (x - 0.3320) / (0.1858 - y);
+        let kelvin = 449.0 * n * n * n + 3525.0 * n * n + 6823.3 * n + 5520.33;
+        if !(kelvin.is_finite() && kelvin > 0.0) {
+            return None;
+        }
+        let [u, v] = DiagramType::Cie1960Uv.coords(self.xyz())?;
+        let [locus_u, locus_v] = DiagramType::Cie1960Uv.coords(blackbody_xyz(kelvin))?;
+        let distance = ((u - locus_u).powi(2) + (v - locus_v).powi(2)).sqrt();
+        (distance <= CCT_MAX_LOCUS_DISTANCE).then_some(kelvin)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::spaces;
+
+    #[test]
+    fn cie_1964_cmf_outside_of_the_fit() {
+        for wavelength in [200.0, 265.8, 1338.0, 1500.0] {
+            let cmf = cie_1964_cmf(wavelength);
+            assert!(cmf.is_finite(), "{wavelength}: {cmf:?}");
+        }
+        assert_eq!(cie_1964_cmf(200.0).z, 0.0);
+        assert!(cie_1964_cmf(1500.0).x < 1e-6);
+    }
+
+    #[test]
+    #[cfg(feature = "cie-cmf-tables")]
+    fn tabulated_cmfs() {
+        assert_eq!(cie_1931_cmf(555.0), Vec3::new(0.5120501, 1.0, 0.005749999));
+        assert_eq!(
+            cie_1964_cmf(360.0),
+            Vec3::new(1.222e-7, 1.3398e-8, 5.35027e-7)
+        );
+        let between = cie_1931_cmf(557.5);
+        let expected = (cie_1931_cmf(555.0) + cie_1931_cmf(560.0)) * 0.5;
+        assert!(between.abs_diff_eq(expected, 1e-6), "{between:?}");
+        assert_eq!(cie_1931_cmf(359.0), Vec3::splat(0.0));
+        assert_eq!(cie_1964_cmf(831.0), Vec3::splat(0.0));
+    }
+
+    #[test]
+    fn correlated_color_temperature() {
+        let d65 = WhitePoint::D65.cct().unwrap();
+        assert!((d65 - 6504.0).abs() < 5.0, "{d65}");
+        let d50 = WhitePoint::D50.cct().unwrap();
+        assert!((d50 - 5003.0).abs() < 5.0, "{d50}");
+        let a = WhitePoint::A.cct().unwrap();
+        assert!((a - 2856.0).abs() < 10.0, "{a}");
+
+        let daylight = WhitePoint::from_cct(6504.0);
+        assert!(matches!(daylight, WhitePoint::Custom(_)));
+        let ([x, y], [d65_x, d65_y]) = (daylight.xy(), WhitePoint::D65.xy());
+        assert!(
+            (x - d65_x).abs() < 5e-4 && (y - d65_y).abs() < 5e-4,
+            "{x} {y}"
+        );
+        assert_eq!(daylight.values()[1], 1.0);
+        let [x, y] = WhitePoint::from_cct(2856.0).xy();
+        let [a_x, a_y] = WhitePoint::A.xy();
+        assert!((x - a_x).abs() < 2e-3 && (y - a_y).abs() < 2e-3, "{x} {y}");
+
+        for kelvin in [2000.0, 3200.0, 5600.0, 9000.0] {
+            let estimate = WhitePoint::from_cct(kelvin).cct().unwrap();
+            assert!(
+                (estimate - kelvin).abs() < 0.01 * kelvin,
+                "{kelvin}: {estimate}"
+            );
+        }
+        let green = WhitePoint::from_xy(0.3, 0.6);
+        assert_eq!(green.cct(), None);
+    }
+
+    #[test]
+    fn blackbody_chromaticity() {
+        let tolerance = if cfg!(feature = "cie-cmf-tables") {
+            1e-4
+        } else {
+            2e-3
+        };
+        // Planckian locus reference chromaticities.
+        for (kelvin, x, y) in [(3000.0, 0.4369, 0.4041), (6500.0, 0.3135, 0.3237)] {
+            let xyz = blackbody_xyz(kelvin);
+            let sum = xyz.x + xyz.y + xyz.z;
+            assert!((xyz.x / sum - x).abs() < tolerance, "{kelvin}: {xyz:?}");
+            assert!((xyz.y / sum - y).abs() < tolerance, "{kelvin}: {xyz:?}");
+        }
+    }
+
+    #[test]
+    fn metamers_under_d65_mismatch_under_a() {
+        let reference = Spectrum::from_fn(|wavelength| 0.4 + 0.2 * (wavelength / 50.0).sin());
+        let black = crate::details::xyz::metameric_black(
+            &Spectrum::from_fn(|wavelength| 0.5 + 0.5 * (wavelength / 25.0).cos()),
+            &Spectrum::D65,
+        );
+        let mut sample = reference;
+        for (value, black) in sample.0.iter_mut().zip(black.0) {
+            *value += 0.3 * black;
+        }
+        let matched = reference.reflected_xyz(&Spectrum::D65);
+        assert!(
+            sample
+                .reflected_xyz(&Spectrum::D65)
+                .abs_diff_eq(matched, 1e-4)
+        );
+
+        let illuminant_a = Spectrum::blackbody(2856.0);
+        let index = metameric_index(&sample, &reference, &Spectrum::D65, &illuminant_a);
+        assert!(index > 0.5, "{index}");
+        let same = metameric_index(&sample, &reference, &Spectrum::D65, &Spectrum::D65);
+        assert!(same < 1e-2, "{same}");
+    }
+
+    #[test]
+    fn d65_white_point() {
+        let white = Spectrum::constant(1.0).reflected_xyz(&Spectrum::D65);
+        let [x, y] = WhitePoint::D65.xy();
+        let sum = white.x + white.y + white.z;
+        let tolerance = if cfg!(feature = "cie-cmf-tables") {
+            1e-4
+        } else {
+            2e-3
+        };
+        assert!(
+            (white.x / sum - x).abs() < tolerance && (white.y / sum - y).abs() < tolerance,
+            "{white:?}"
+        );
+    }
+
+    #[test]
+    fn warm_emitter_in_aces_cg() {
+        let emitter = Color::blackbody_in(
+            3000.0,
+            spaces::ACES_CG,
+            BlackbodyNormalization::UnitLuminance,
+        );
+        assert_eq!(emitter.space, spaces::ACES_CG);
+        let rgb = emitter.value;
+        assert!(rgb.z > 0.0, "{rgb:?}");
+        assert!(rgb.x > rgb.y && rgb.y > rgb.z, "{rgb:?}");
+
+        let xyz = emitter.to(spaces::CIE_XYZ.with_whitepoint(spaces::ACES_CG.white_point()));
+        assert!((xyz.value.y - 1.0).abs() < 1e-4, "{xyz:?}");
+
+        let max = Color::blackbody_in(
+            3000.0,
+            spaces::ACES_CG,
+            BlackbodyNormalization::UnitMaxChannel,
+        );
+        assert!((max.value.x - 1.0).abs() < 1e-5, "{max:?}");
+
+        let absolute = Color::blackbody_in(
+            3000.0,
+            spaces::ACES_CG,
+            BlackbodyNormalization::AbsoluteRadiance,
+        );
+        let ratio = absolute.value / absolute.value.x;
+        assert!(ratio.abs_diff_eq(max.value, 1e-5), "{ratio:?} != {max:?}");
+    }
+}
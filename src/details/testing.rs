@@ -0,0 +1,65 @@
+//! Assertions for testing code built on `kolor`, see
+//! [`assert_color_eq!`](crate::assert_color_eq) and
+//! [`assert_in_gamut!`](crate::assert_in_gamut).
+use super::{
+    color::{Color, ColorSpace},
+    gamut::{in_unit_cube, rgb_gamut_space},
+};
+use crate::Float;
+
+pub use super::gamut::IN_GAMUT_EPSILON;
+
+/// Implements [`assert_color_eq!`].
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_color_eq(a: &Color, b: &Color, epsilon: Float) {
+    let converted = b.to(a.space);
+    if !a.value.abs_diff_eq(converted.value, epsilon) {
+        panic!(
+            "colors differ by more than {epsilon}\n  left: {:?}\n right: {:?} in {:?}\n   ΔE2000: {}",
+            a.value,
+            converted.value,
+            a.space,
+            a.delta_e_2000(b)
+        );
+    }
+}
+
+/// Implements [`assert_in_gamut!`].
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_in_gamut(color: &Color, space: ColorSpace) {
+    let gamut_space = rgb_gamut_space(space);
+    let rgb = color.to(gamut_space).value;
+    if !in_unit_cube(rgb, IN_GAMUT_EPSILON) {
+        let (_, distance) = color.to_oklch_with_gamut_info(space);
+        panic!(
+            "color {:?} in {:?} is outside of the gamut of {:?}\n linear RGB: {rgb:?}\n Oklch chroma beyond the boundary: {distance}",
+            color.value, color.space, gamut_space
+        );
+    }
+}
+
+/// Asserts that two [`Color`]s are equal within an epsilon, after converting
+/// the right hand side into the space of the left hand side.
+///
+/// The failure message shows both colors in that space and their CIEDE2000
+/// difference.
+#[macro_export]
+macro_rules! assert_color_eq {
+    ($left:expr, $right:expr, $epsilon:expr $(,)?) => {
+        $crate::details::testing::assert_color_eq(&$left, &$right, $epsilon)
+    };
+}
+
+/// Asserts that a [`Color`] is inside the gamut of a [`ColorSpace`] (linear
+/// sRGB for spaces without RGB primaries), within [`IN_GAMUT_EPSILON`].
+///
+/// The failure message shows the linear RGB components of the color in the
+/// gamut and how far it is outside of it.
+#[macro_export]
+macro_rules! assert_in_gamut {
+    ($color:expr, $space:expr $(,)?) => {
+        $crate::details::testing::assert_in_gamut(&$color, $space)
+    };
+}
@@ -1,30 +1,47 @@
 use super::{
+    appearance::{
+        Cam16Parameters, Cam16ViewingConditions, cam16_ucs_to_xyz_with, xyz_to_cam16_ucs_with,
+    },
     color::{TransformFn, WhitePoint},
     math::prelude::*,
 };
 use crate::{Float, Mat3, PI, TAU, Vec3};
 #[cfg(all(not(feature = "std"), feature = "libm"))]
-use num_traits::Float;
+use num_traits::Float as _;
 
 /// Represents a reference to a function that can apply a [`TransformFn`] or
 /// its inverse.
 #[derive(Copy, Clone)]
 pub struct ColorTransform {
-    first: for<'r> fn(Vec3, WhitePoint) -> Vec3,
-    second: Option<for<'r> fn(Vec3, WhitePoint) -> Vec3>,
+    first: TransformStep,
+    second: Option<TransformStep>,
 }
 
 impl ColorTransform {
+    /// Creates a transform applying the inverse of `src_transform`, then
+    /// `dst_transform`, prepared for the [D65](WhitePoint::D65) white point,
+    /// see [`with_white_point()`](Self::with_white_point).
     #[inline]
     pub fn new(
         src_transform: Option<TransformFn>,
         dst_transform: Option<TransformFn>,
     ) -> Option<Self> {
-        use super::transform::*;
+        Self::with_white_point(src_transform, dst_transform, WhitePoint::D65)
+    }
 
-        let from_transform = src_transform.map(|src| TRANSFORMS_INVERSE[src as usize]);
+    /// Like [`new()`](Self::new), but computes the values of transform
+    /// functions which depend on the white point, e.g. of CAM16-UCS, up front
+    /// for `white_point`. Applying the transform with another white point
+    /// still gives the correct result, but computes them on every call.
+    #[inline]
+    pub fn with_white_point(
+        src_transform: Option<TransformFn>,
+        dst_transform: Option<TransformFn>,
+        white_point: WhitePoint,
+    ) -> Option<Self> {
+        let from_transform = src_transform.map(|src| transform_steps(src, white_point).1);
 
-        let to_transform = dst_transform.map(|dst| TRANSFORMS[dst as usize]);
+        let to_transform = dst_transform.map(|dst| transform_steps(dst, white_point).0);
 
         if let Some(from_transform) = from_transform {
             Some(Self {
@@ -41,89 +58,229 @@ impl ColorTransform {
 
     #[inline(always)]
     pub fn apply(&self, color: Vec3, white_point: WhitePoint) -> Vec3 {
-        let mut color = (self.first)(color, white_point);
+        let mut color = self.first.apply(color, white_point);
         if let Some(second) = self.second {
-            color = second(color, white_point);
+            color = second.apply(color, white_point);
         }
         color
     }
 }
 
-// Keep in sync with TransformFn
-const TRANSFORMS: [fn(Vec3, WhitePoint) -> Vec3; 17] = [
-    // sRGB,
-    srgb_oetf,
-    // Oklab,
-    xyz_to_ok_lab,
-    // Oklch,
-    xyz_to_ok_lch,
-    // CIE_xyY,
-    xyz_to_xyy,
-    // CIE LAB,
-    xyz_to_cie_lab,
-    // CIE LCh,
-    xyz_to_cie_lch,
-    // CIE 1960 UCS,
-    xyz_to_cie_1960_ucs,
-    // CIE 1960 UCS_uvV,
-    xyz_to_cie_1960_ucs_uvv,
-    // CIE 1964 UVW,
-    xyz_to_cie_1964_uvw,
-    // CIE 1976 Luv,
-    xyz_to_cie_1976_luv,
-    // HSL,
-    hsx::rgb_to_hsl,
-    // HSV,
-    hsx::rgb_to_hsv,
-    // HSI,
-    hsx::rgb_to_hsi,
-    // ICtCp PQ,
-    ict_cp::rgb_to_ict_cp_pq,
-    // ICtCp HLG,
-    ict_cp::rgb_to_ict_cp_hlg,
-    // BT 601,
-    bt601_oetf,
-    // PQ,
-    st_2084_pq_eotf_inverse,
-];
-
-// Keep in sync with TransformFn
-const TRANSFORMS_INVERSE: [fn(Vec3, WhitePoint) -> Vec3; 17] = [
-    // sRGB,
-    srgb_eotf,
-    // Oklab,
-    ok_lab_to_xyz,
-    // Oklch,
-    ok_lch_to_xyz,
-    //CIE_xyY,
-    xyy_to_xyz,
-    //CIELAB,
-    cie_lab_to_xyz,
-    //CIELCh,
-    cie_lch_to_xyz,
-    //CIE_1960_UCS,
-    cie_1960_ucs_to_xyz,
-    //CIE_1960_UCS_uvV,
-    cie_1960_ucs_uvv_to_xyz,
-    //CIE_1964_UVW,
-    cie_1964_uvw_to_xyz,
-    //CIE_1976_Luv,
-    cie_1976_luv_to_xyz,
-    //HSL,
-    hsx::hsl_to_rgb,
-    //HSV,
-    hsx::hsv_to_rgb,
-    //HSI,
-    hsx::hsi_to_rgb,
-    //ICtCp_PQ,
-    ict_cp::ict_cp_pq_to_rgb,
-    //ICtCp_HLG,
-    ict_cp::ict_cp_hlg_to_rgb,
-    //BT_601,
-    bt601_oetf_inverse,
-    //PQ,
-    st_2084_pq_eotf,
-];
+/// A direction of a [`TransformFn`], as applied by a [`ColorTransform`].
+#[derive(Copy, Clone)]
+enum TransformStep {
+    Fn(fn(Vec3, WhitePoint) -> Vec3),
+    Cam16Ucs(Cam16Step),
+    InverseCam16Ucs(Cam16Step),
+    YCbCr(LumaCoefficients),
+    InverseYCbCr(LumaCoefficients),
+    GammaEncode(GammaExponent),
+    GammaDecode(GammaExponent),
+}
+
+impl TransformStep {
+    #[inline(always)]
+    fn apply(&self, color: Vec3, white_point: WhitePoint) -> Vec3 {
+        match self {
+            Self::Fn(transform) => transform(color, white_point),
+            Self::Cam16Ucs(step) => xyz_to_cam16_ucs_with(color, &step.parameters(white_point)),
+            Self::InverseCam16Ucs(step) => {
+                cam16_ucs_to_xyz_with(color, &step.parameters(white_point))
+            }
+            Self::YCbCr(coefficients) => rgb_to_ycbcr(color, *coefficients),
+            Self::InverseYCbCr(coefficients) => ycbcr_to_rgb(color, *coefficients),
+            Self::GammaEncode(gamma) => gamma_encode(color, *gamma),
+            Self::GammaDecode(gamma) => gamma_decode(color, *gamma),
+        }
+    }
+}
+
+/// CAM16-UCS under some viewing conditions, with the parameters for the white
+/// point the step is expected to be applied with.
+#[derive(Copy, Clone)]
+struct Cam16Step {
+    conditions: Cam16ViewingConditions,
+    white_point: WhitePoint,
+    parameters: Cam16Parameters,
+}
+
+impl Cam16Step {
+    fn new(conditions: Cam16ViewingConditions, white_point: WhitePoint) -> Self {
+        Self {
+            conditions,
+            white_point,
+            parameters: Cam16Parameters::new(&conditions, white_point),
+        }
+    }
+
+    #[inline(always)]
+    fn parameters(&self, white_point: WhitePoint) -> Cam16Parameters {
+        if white_point == self.white_point {
+            self.parameters
+        } else {
+            Cam16Parameters::new(&self.conditions, white_point)
+        }
+    }
+}
+
+/// Returns the steps applying `transform_fn` and its inverse, prepared for
+/// `white_point`.
+fn transform_steps(
+    transform_fn: TransformFn,
+    white_point: WhitePoint,
+) -> (TransformStep, TransformStep) {
+    match transform_fn {
+        TransformFn::Cam16Ucs(conditions) => {
+            let step = Cam16Step::new(conditions, white_point);
+            (
+                TransformStep::Cam16Ucs(step),
+                TransformStep::InverseCam16Ucs(step),
+            )
+        }
+        TransformFn::YCbCr(coefficients) => (
+            TransformStep::YCbCr(coefficients),
+            TransformStep::InverseYCbCr(coefficients),
+        ),
+        TransformFn::Gamma(gamma) => (
+            TransformStep::GammaEncode(gamma),
+            TransformStep::GammaDecode(gamma),
+        ),
+        _ => {
+            let (forward, inverse) = transform_fns(transform_fn);
+            (TransformStep::Fn(forward), TransformStep::Fn(inverse))
+        }
+    }
+}
+
+/// A transform function and its inverse.
+pub type TransformFns = (fn(Vec3, WhitePoint) -> Vec3, fn(Vec3, WhitePoint) -> Vec3);
+
+/// A transform function registered at runtime, see
+/// [`TransformFn::Registered`].
+///
+/// Handles can only be obtained from
+/// [`register_transform()`](super::registry::register_transform) or by name,
+/// so they always refer to registered functions. They compare and hash by
+/// identity and serialize as the name the functions were registered under.
+#[derive(Copy, Clone)]
+pub struct RegisteredTransform(&'static RegisteredTransformFns);
+
+/// The functions and name behind a [`RegisteredTransform`], which live for the
+/// rest of the program once registered.
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+pub(crate) struct RegisteredTransformFns {
+    pub(crate) name: &'static str,
+    pub(crate) fns: TransformFns,
+}
+
+impl RegisteredTransform {
+    #[cfg(feature = "std")]
+    pub(crate) fn new(fns: &'static RegisteredTransformFns) -> Self {
+        Self(fns)
+    }
+
+    /// The name the transform function was registered under.
+    pub fn name(&self) -> &'static str {
+        self.0.name
+    }
+}
+
+impl PartialEq for RegisteredTransform {
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self.0, other.0)
+    }
+}
+
+impl Eq for RegisteredTransform {}
+
+impl core::hash::Hash for RegisteredTransform {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        core::ptr::hash(self.0, state);
+    }
+}
+
+impl core::fmt::Debug for RegisteredTransform {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("RegisteredTransform")
+            .field(&self.0.name)
+            .finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for RegisteredTransform {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RegisteredTransform {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Name;
+        impl serde::de::Visitor<'_> for Name {
+            type Value = RegisteredTransform;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str("the name of a registered transform function")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, name: &str) -> Result<Self::Value, E> {
+                #[cfg(feature = "std")]
+                if let Some(TransformFn::Registered(transform)) =
+                    super::registry::registered_transform(name)
+                {
+                    return Ok(transform);
+                }
+                Err(E::custom(format_args!(
+                    "transform function {name:?} is not registered"
+                )))
+            }
+        }
+        deserializer.deserialize_str(Name)
+    }
+}
+
+/// Returns the functions applying `transform_fn` and its inverse.
+fn transform_fns(transform_fn: TransformFn) -> TransformFns {
+    match transform_fn {
+        TransformFn::Srgb => (srgb_oetf, srgb_eotf),
+        TransformFn::OkLab => (xyz_to_ok_lab, ok_lab_to_xyz),
+        TransformFn::OkLch => (xyz_to_ok_lch, ok_lch_to_xyz),
+        TransformFn::CieXyY => (xyz_to_xyy, xyy_to_xyz),
+        TransformFn::CieLab => (xyz_to_cie_lab, cie_lab_to_xyz),
+        TransformFn::CieLch => (xyz_to_cie_lch, cie_lch_to_xyz),
+        TransformFn::Cie1960Ucs => (xyz_to_cie_1960_ucs, cie_1960_ucs_to_xyz),
+        TransformFn::Cie1960UcsUvV => (xyz_to_cie_1960_ucs_uvv, cie_1960_ucs_uvv_to_xyz),
+        TransformFn::Cie1964Uvw => (xyz_to_cie_1964_uvw, cie_1964_uvw_to_xyz),
+        TransformFn::Cie1976Luv => (xyz_to_cie_1976_luv, cie_1976_luv_to_xyz),
+        TransformFn::Hsl => (hsx::rgb_to_hsl, hsx::hsl_to_rgb),
+        TransformFn::Hsv => (hsx::rgb_to_hsv, hsx::hsv_to_rgb),
+        TransformFn::Hsi => (hsx::rgb_to_hsi, hsx::hsi_to_rgb),
+        TransformFn::IctCpPq => (ict_cp::rgb_to_ict_cp_pq, ict_cp::ict_cp_pq_to_rgb),
+        TransformFn::IctCpHlg => (ict_cp::rgb_to_ict_cp_hlg, ict_cp::ict_cp_hlg_to_rgb),
+        TransformFn::Bt601 => (bt601_oetf, bt601_oetf_inverse),
+        TransformFn::Pq => (st_2084_pq_eotf_inverse, st_2084_pq_eotf),
+        TransformFn::Hlg => (bt2100_hlg_oetf, bt2100_hlg_oetf_inverse),
+        TransformFn::DciP3 => (dci_p3_eotf_inverse, dci_p3_eotf),
+        TransformFn::AcesCc => (aces::linear_to_aces_cc, aces::aces_cc_to_linear),
+        TransformFn::AcesCct => (aces::linear_to_aces_cct, aces::aces_cct_to_linear),
+        TransformFn::Bt2020_10Bit => (bt2020_10_bit_oetf, bt2020_10_bit_oetf_inverse),
+        TransformFn::Bt2020_12Bit => (bt2020_12_bit_oetf, bt2020_12_bit_oetf_inverse),
+        TransformFn::SLog3 => (s_log3_oetf, s_log3_oetf_inverse),
+        TransformFn::Hwb => (hsx::rgb_to_hwb, hsx::hwb_to_rgb),
+        TransformFn::Cam16Ucs(_) => {
+            unreachable!("CAM16-UCS depends on its viewing conditions, see `transform_steps()`")
+        }
+        TransformFn::YCbCr(_) => {
+            unreachable!("Y'CbCr depends on its luma coefficients, see `transform_steps()`")
+        }
+        TransformFn::Gamma(_) => {
+            unreachable!("gamma depends on its exponent, see `transform_steps()`")
+        }
+        TransformFn::Registered(transform) => transform.0.fns,
+    }
+}
 
 /// Applies the sRGB OETF (opto-eletronic transfer function), sometimes called
 /// 'gamma compensation'.
@@ -167,6 +324,187 @@ pub fn bt601_oetf_inverse(color: Vec3, _wp: WhitePoint) -> Vec3 {
     Vec3::select(cutoff, lower, higher)
 }
 
+/// Exponent of the DCI-P3 EOTF.
+const DCI_P3_GAMMA: Float = 2.6;
+
+/// Raises the magnitude of `value` to `exponent`, keeping its sign.
+fn signed_powf(value: Float, exponent: Float) -> Float {
+    value.signum() * value.abs().powf(exponent)
+}
+
+/// The exponent of a pure power transfer function, see
+/// [`TransformFn::Gamma`].
+///
+/// Compares and hashes by the bits of the exponent, like
+/// [`LumaCoefficients`].
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GammaExponent(pub Float);
+
+impl GammaExponent {
+    #[allow(clippy::unnecessary_cast)]
+    fn to_bits(self) -> u64 {
+        self.0.to_bits() as u64
+    }
+}
+
+impl PartialEq for GammaExponent {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bits() == other.to_bits()
+    }
+}
+
+impl Eq for GammaExponent {}
+
+impl core::hash::Hash for GammaExponent {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.to_bits().hash(state);
+    }
+}
+
+/// Encodes linear values with a pure power of 1 / `gamma`. Negative values are
+/// mirrored.
+#[inline]
+pub fn gamma_encode(color: Vec3, gamma: GammaExponent) -> Vec3 {
+    let exponent = 1.0 / gamma.0;
+    Vec3::new(
+        signed_powf(color.x, exponent),
+        signed_powf(color.y, exponent),
+        signed_powf(color.z, exponent),
+    )
+}
+
+/// Decodes values with a pure power of `gamma`, the inverse of
+/// [`gamma_encode()`]. Negative values are mirrored.
+#[inline]
+pub fn gamma_decode(color: Vec3, gamma: GammaExponent) -> Vec3 {
+    Vec3::new(
+        signed_powf(color.x, gamma.0),
+        signed_powf(color.y, gamma.0),
+        signed_powf(color.z, gamma.0),
+    )
+}
+
+/// Applies the inverse of the DCI-P3 EOTF of SMPTE RP 431-2, a pure power of
+/// 1/2.6. Negative values are mirrored.
+#[inline]
+pub fn dci_p3_eotf_inverse(color: Vec3, _wp: WhitePoint) -> Vec3 {
+    gamma_encode(color, GammaExponent(DCI_P3_GAMMA))
+}
+
+/// Applies the DCI-P3 EOTF of SMPTE RP 431-2, a pure power of 2.6. Negative
+/// values are mirrored.
+#[inline]
+pub fn dci_p3_eotf(color: Vec3, _wp: WhitePoint) -> Vec3 {
+    gamma_decode(color, GammaExponent(DCI_P3_GAMMA))
+}
+
+/// α of the 10-bit BT.2020 OETF.
+const BT2020_10_BIT_ALPHA: Float = 1.099;
+/// β of the 10-bit BT.2020 OETF.
+const BT2020_10_BIT_BETA: Float = 0.018;
+// BT.2020 defines α = 1.09929682680944 and β = 0.018053968510807, for which
+// the curve and its slope are continuous, and specifies these values rounded
+// for 10-bit and 12-bit systems.
+/// α of the 12-bit BT.2020 OETF.
+const BT2020_12_BIT_ALPHA: Float = 1.0993;
+/// β of the 12-bit BT.2020 OETF.
+const BT2020_12_BIT_BETA: Float = 0.0181;
+
+#[inline]
+fn bt2020_oetf(color: Vec3, alpha: Float, beta: Float) -> Vec3 {
+    let cutoff = color.cmplt(Vec3::splat(beta));
+    let higher = alpha * color.powf(0.45) - Vec3::splat(alpha - 1.0);
+    let lower = 4.5 * color;
+
+    Vec3::select(cutoff, lower, higher)
+}
+
+#[inline]
+fn bt2020_oetf_inverse(color: Vec3, alpha: Float, beta: Float) -> Vec3 {
+    let cutoff = color.cmplt(Vec3::splat(4.5 * beta));
+    let higher = ((color + Vec3::splat(alpha - 1.0)) / alpha).powf(1.0 / 0.45);
+    let lower = color / 4.5;
+
+    Vec3::select(cutoff, lower, higher)
+}
+
+/// Applies the BT.2020 OETF with the constants for 10-bit systems.
+#[inline]
+pub fn bt2020_10_bit_oetf(color: Vec3, _wp: WhitePoint) -> Vec3 {
+    bt2020_oetf(color, BT2020_10_BIT_ALPHA, BT2020_10_BIT_BETA)
+}
+
+/// Applies the inverse of the BT.2020 OETF with the constants for 10-bit
+/// systems.
+#[inline]
+pub fn bt2020_10_bit_oetf_inverse(color: Vec3, _wp: WhitePoint) -> Vec3 {
+    bt2020_oetf_inverse(color, BT2020_10_BIT_ALPHA, BT2020_10_BIT_BETA)
+}
+
+/// Applies the BT.2020 OETF with the constants for 12-bit systems.
+#[inline]
+pub fn bt2020_12_bit_oetf(color: Vec3, _wp: WhitePoint) -> Vec3 {
+    bt2020_oetf(color, BT2020_12_BIT_ALPHA, BT2020_12_BIT_BETA)
+}
+
+/// Applies the inverse of the BT.2020 OETF with the constants for 12-bit
+/// systems.
+#[inline]
+pub fn bt2020_12_bit_oetf_inverse(color: Vec3, _wp: WhitePoint) -> Vec3 {
+    bt2020_oetf_inverse(color, BT2020_12_BIT_ALPHA, BT2020_12_BIT_BETA)
+}
+
+/// 10-bit code value of the linear reflectance at which S-Log3 switches from
+/// its linear toe to the logarithmic curve.
+const S_LOG3_TOE_CODE: Float = 171.2102946929;
+/// Linear reflectance at which S-Log3 switches from its linear toe to the
+/// logarithmic curve.
+const S_LOG3_TOE_CUTOFF: Float = 0.01125;
+
+/// Encodes a scene linear reflectance with Sony's S-Log3 curve, returning the
+/// full range code value divided by 1023.
+#[inline]
+pub fn s_log3_oetf_float(linear: Float) -> Float {
+    if linear >= S_LOG3_TOE_CUTOFF {
+        (420.0 + ((linear + 0.01) / (0.18 + 0.01)).log10() * 261.5) / 1023.0
+    } else {
+        (linear * (S_LOG3_TOE_CODE - 95.0) / S_LOG3_TOE_CUTOFF + 95.0) / 1023.0
+    }
+}
+
+/// Decodes an S-Log3 code value divided by 1023 to scene linear reflectance.
+#[inline]
+pub fn s_log3_oetf_inverse_float(encoded: Float) -> Float {
+    let code = encoded * 1023.0;
+    if code >= S_LOG3_TOE_CODE {
+        (10.0 as Float).powf((code - 420.0) / 261.5) * (0.18 + 0.01) - 0.01
+    } else {
+        (code - 95.0) * S_LOG3_TOE_CUTOFF / (S_LOG3_TOE_CODE - 95.0)
+    }
+}
+
+/// Applies Sony's S-Log3 camera log encoding, which maps 18% gray to a 10-bit
+/// code value of 420 and 90% white to about 598.
+#[inline]
+pub fn s_log3_oetf(color: Vec3, _wp: WhitePoint) -> Vec3 {
+    Vec3::new(
+        s_log3_oetf_float(color.x),
+        s_log3_oetf_float(color.y),
+        s_log3_oetf_float(color.z),
+    )
+}
+
+/// Applies the inverse of Sony's S-Log3 camera log encoding.
+#[inline]
+pub fn s_log3_oetf_inverse(color: Vec3, _wp: WhitePoint) -> Vec3 {
+    Vec3::new(
+        s_log3_oetf_inverse_float(color.x),
+        s_log3_oetf_inverse_float(color.y),
+        s_log3_oetf_inverse_float(color.z),
+    )
+}
+
 #[rustfmt::skip]
 const OKLAB_M_1: Mat3 =
     Mat3::from_cols_array(&[0.8189330101,0.0329845436,0.0482003018,
@@ -189,6 +527,28 @@ pub fn xyz_to_ok_lab(color: Vec3, _wp: WhitePoint) -> Vec3 {
     OKLAB_M_2 * lms
 }
 
+/// Approximates the cube root of `value` by dividing the exponent of its `f32`
+/// representation by 3, refined by one Halley iteration.
+///
+/// The relative error is below 5e-5.
+#[inline]
+#[allow(clippy::unnecessary_cast)]
+fn fast_cbrt(value: Float) -> Float {
+    let magnitude = (value as f32).abs();
+    let guess = f32::from_bits(magnitude.to_bits() / 3 + 0x2A51_4067);
+    let cube = guess * guess * guess;
+    let root = guess * (cube + 2.0 * magnitude) / (2.0 * cube + magnitude);
+    (root as Float).copysign(value)
+}
+
+/// Like [`xyz_to_ok_lab()`], but with an approximation of the cube root, see
+/// [`Color::to_oklab_fast()`](super::color::Color::to_oklab_fast).
+#[inline]
+pub fn xyz_to_ok_lab_fast(color: Vec3, _wp: WhitePoint) -> Vec3 {
+    let lms = OKLAB_M_1 * color;
+    OKLAB_M_2 * Vec3::new(fast_cbrt(lms.x), fast_cbrt(lms.y), fast_cbrt(lms.z))
+}
+
 #[inline]
 pub fn ok_lab_to_xyz(color: Vec3, _wp: WhitePoint) -> Vec3 {
     let mut lms = OKLAB_M_2.inverse() * color;
@@ -277,13 +637,11 @@ pub fn cie_lab_to_xyz(color: Vec3, wp: WhitePoint) -> Vec3 {
 // CIELCh
 #[inline]
 pub fn xyz_to_cie_lch(color: Vec3, wp: WhitePoint) -> Vec3 {
-    xyz_to_cie_lab(color, wp);
-    cie_lab_to_cie_lch(color)
+    cie_lab_to_cie_lch(xyz_to_cie_lab(color, wp))
 }
 #[inline]
 pub fn cie_lch_to_xyz(color: Vec3, wp: WhitePoint) -> Vec3 {
-    cie_lch_to_cie_lab(color);
-    cie_lab_to_xyz(color, wp)
+    cie_lab_to_xyz(cie_lch_to_cie_lab(color), wp)
 }
 #[inline]
 pub fn cie_lab_to_cie_lch(color: Vec3) -> Vec3 {
@@ -343,7 +701,7 @@ pub fn cie_1960_ucs_to_uvv(color: Vec3, _wp: WhitePoint) -> Vec3 {
 pub fn cie_1960_uvv_to_ucs(color: Vec3, _wp: WhitePoint) -> Vec3 {
     let U = color.z * (color.x / color.y);
     let W = -color.z * (color.x + color.y - 1.0) / color.y;
-    Vec3::new(U, color.y, W)
+    Vec3::new(U, color.z, W)
 }
 
 #[inline]
@@ -485,6 +843,39 @@ pub mod hlg {
             HLG_channel_inverse(color.z),
         )
     }
+
+    /// BT.2100 HLG OETF, mapping scene light in [0, 1] to the HLG signal in
+    /// [0, 1]. This is [`arib_hlg_oetf`] with scene light scaled by 1/12.
+    #[inline]
+    pub fn bt2100_hlg_oetf(color: Vec3, wp: WhitePoint) -> Vec3 {
+        arib_hlg_oetf(color * 12.0, wp)
+    }
+
+    /// Inverse of the BT.2100 HLG OETF.
+    #[inline]
+    pub fn bt2100_hlg_oetf_inverse(color: Vec3, wp: WhitePoint) -> Vec3 {
+        arib_hlg_oetf_inverse(color, wp) / 12.0
+    }
+
+    /// The BT.2100 HLG system gamma for a display with a nominal peak luminance
+    /// of `peak_nits`, 1.2 for a 1000 cd/m² display.
+    #[inline]
+    pub fn hlg_system_gamma(peak_nits: Float) -> Float {
+        1.2 + 0.42 * (peak_nits / 1000.0).log10()
+    }
+
+    /// BT.2100 HLG OOTF, mapping linear BT.2020 scene light in [0, 1] to
+    /// display light in cd/m² on a display with a nominal peak luminance of
+    /// `peak_nits`, applying the [system gamma](hlg_system_gamma) to the scene
+    /// luminance.
+    #[inline]
+    pub fn hlg_ootf(scene: Vec3, peak_nits: Float) -> Vec3 {
+        let luminance = 0.2627 * scene.x + 0.678 * scene.y + 0.0593 * scene.z;
+        if luminance <= 0.0 {
+            return Vec3::splat(0.0);
+        }
+        scene * (peak_nits * luminance.powf(hlg_system_gamma(peak_nits) - 1.0))
+    }
 }
 
 pub use hlg::*;
@@ -542,6 +933,113 @@ pub mod pq {
 
 pub use pq::*;
 
+/// ACEScc and ACEScct logarithmic encodings of ACEScg, as specified in
+/// S-2014-003 and S-2016-001.
+pub mod aces {
+    use super::*;
+
+    /// Largest value representable as a half float, which decoding clamps to.
+    const HALF_MAX: Float = 65504.0;
+    /// Linear value below which ACEScct uses its linear toe.
+    const CCT_TOE_CUTOFF: Float = 0.0078125;
+    const CCT_TOE_SLOPE: Float = 10.5402377416545;
+    const CCT_TOE_OFFSET: Float = 0.0729055341958355;
+
+    fn log_encode(linear: Float) -> Float {
+        (linear.log2() + 9.72) / 17.52
+    }
+
+    fn log_decode(encoded: Float) -> Float {
+        (encoded * 17.52 - 9.72).exp2()
+    }
+
+    /// Encodes a linear ACEScg channel value as ACEScc.
+    #[inline]
+    pub fn linear_to_aces_cc_float(linear: Float) -> Float {
+        if linear <= 0.0 {
+            log_encode(Float::powi(2.0, -16))
+        } else if linear < Float::powi(2.0, -15) {
+            log_encode(Float::powi(2.0, -16) + linear * 0.5)
+        } else {
+            log_encode(linear)
+        }
+    }
+
+    /// Decodes an ACEScc channel value to linear ACEScg.
+    #[inline]
+    pub fn aces_cc_to_linear_float(encoded: Float) -> Float {
+        if encoded < (9.72 - 15.0) / 17.52 {
+            (log_decode(encoded) - Float::powi(2.0, -16)) * 2.0
+        } else if encoded < log_encode(HALF_MAX) {
+            log_decode(encoded)
+        } else {
+            HALF_MAX
+        }
+    }
+
+    /// Encodes a linear ACEScg channel value as ACEScct.
+    #[inline]
+    pub fn linear_to_aces_cct_float(linear: Float) -> Float {
+        if linear <= CCT_TOE_CUTOFF {
+            CCT_TOE_SLOPE * linear + CCT_TOE_OFFSET
+        } else {
+            log_encode(linear)
+        }
+    }
+
+    /// Decodes an ACEScct channel value to linear ACEScg.
+    #[inline]
+    pub fn aces_cct_to_linear_float(encoded: Float) -> Float {
+        if encoded <= linear_to_aces_cct_float(CCT_TOE_CUTOFF) {
+            (encoded - CCT_TOE_OFFSET) / CCT_TOE_SLOPE
+        } else if encoded < log_encode(HALF_MAX) {
+            log_decode(encoded)
+        } else {
+            HALF_MAX
+        }
+    }
+
+    /// Encodes linear ACEScg as ACEScc.
+    #[inline]
+    pub fn linear_to_aces_cc(color: Vec3, _wp: WhitePoint) -> Vec3 {
+        Vec3::new(
+            linear_to_aces_cc_float(color.x),
+            linear_to_aces_cc_float(color.y),
+            linear_to_aces_cc_float(color.z),
+        )
+    }
+
+    /// Decodes ACEScc to linear ACEScg.
+    #[inline]
+    pub fn aces_cc_to_linear(color: Vec3, _wp: WhitePoint) -> Vec3 {
+        Vec3::new(
+            aces_cc_to_linear_float(color.x),
+            aces_cc_to_linear_float(color.y),
+            aces_cc_to_linear_float(color.z),
+        )
+    }
+
+    /// Encodes linear ACEScg as ACEScct.
+    #[inline]
+    pub fn linear_to_aces_cct(color: Vec3, _wp: WhitePoint) -> Vec3 {
+        Vec3::new(
+            linear_to_aces_cct_float(color.x),
+            linear_to_aces_cct_float(color.y),
+            linear_to_aces_cct_float(color.z),
+        )
+    }
+
+    /// Decodes ACEScct to linear ACEScg.
+    #[inline]
+    pub fn aces_cct_to_linear(color: Vec3, _wp: WhitePoint) -> Vec3 {
+        Vec3::new(
+            aces_cct_to_linear_float(color.x),
+            aces_cct_to_linear_float(color.y),
+            aces_cct_to_linear_float(color.z),
+        )
+    }
+}
+
 /// BT.2100 ICtCp
 pub mod ict_cp {
     use super::*;
@@ -715,7 +1213,7 @@ pub mod hsx {
             let hue_prime = color.x / 60.0;
             let largest_component = chroma * (1.0 - (hue_prime % 2.0 - 1.0).abs());
             let lightness_match = color.z - chroma / 2.0;
-            (chroma, hue_prime, largest_component, lightness_match)
+            (hue_prime, chroma, largest_component, lightness_match)
         })
     }
 
@@ -726,10 +1224,33 @@ pub mod hsx {
             let hue_prime = color.x / 60.0;
             let largest_component = chroma * (1.0 - (hue_prime % 2.0 - 1.0).abs());
             let lightness_match = color.z - chroma;
-            (chroma, hue_prime, largest_component, lightness_match)
+            (hue_prime, chroma, largest_component, lightness_match)
         })
     }
 
+    /// Converts RGB to (Hue, Whiteness, Blackness), with the hue in degrees
+    /// like [`rgb_to_hsv()`].
+    #[inline]
+    pub fn rgb_to_hwb(color: Vec3, wp: WhitePoint) -> Vec3 {
+        let hsv = rgb_to_hsv(color, wp);
+        Vec3::new(hsv.x, (1.0 - hsv.y) * hsv.z, 1.0 - hsv.z)
+    }
+
+    /// Converts (Hue, Whiteness, Blackness) to RGB, accepting hues outside of
+    /// [0, 360). Whiteness and blackness
+    /// which sum to 1 or more are normalized to that sum, giving the gray
+    /// `whiteness / (whiteness + blackness)`.
+    #[inline]
+    pub fn hwb_to_rgb(color: Vec3, wp: WhitePoint) -> Vec3 {
+        let (whiteness, blackness) = (color.y, color.z);
+        if whiteness + blackness >= 1.0 {
+            return Vec3::splat(whiteness / (whiteness + blackness));
+        }
+        let hue = color.x - 360.0 * (color.x / 360.0).floor();
+        let value = 1.0 - blackness;
+        hsv_to_rgb(Vec3::new(hue, 1.0 - whiteness / value, value), wp)
+    }
+
     #[inline]
     pub fn hsi_to_rgb(color: Vec3, _wp: WhitePoint) -> Vec3 {
         hsx_to_rgb(color, |color| {
@@ -767,12 +1288,311 @@ pub mod hsx {
 
 pub use hsx::*;
 
+/// Full-range Y'CbCr, with Cb and Cr centered at 0.
+pub mod ycbcr {
+    use super::*;
+    #[cfg(feature = "serde")]
+    use serde::{Deserialize, Serialize};
+
+    /// The luma weights of red and blue of a Y'CbCr matrix standard, green
+    /// being weighted with `1 - kr - kb`.
+    #[derive(Debug, Copy, Clone)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct LumaCoefficients {
+        /// The luma weight of red.
+        pub kr: Float,
+        /// The luma weight of blue.
+        pub kb: Float,
+    }
+
+    impl LumaCoefficients {
+        /// BT.601, used for standard definition video.
+        pub const BT601: Self = Self::new(0.299, 0.114);
+        /// BT.709, used for high definition video.
+        pub const BT709: Self = Self::new(0.2126, 0.0722);
+        /// BT.2020 non-constant luminance, used for ultra high definition video.
+        pub const BT2020: Self = Self::new(0.2627, 0.0593);
+
+        /// Creates luma coefficients from the weights of red and blue, which
+        /// should be positive and sum to less than 1.
+        pub const fn new(kr: Float, kb: Float) -> Self {
+            Self { kr, kb }
+        }
+
+        /// The luma weight of green, `1 - kr - kb`.
+        pub fn kg(&self) -> Float {
+            1.0 - self.kr - self.kb
+        }
+
+        fn to_bits(self) -> (u64, u64) {
+            #[allow(clippy::unnecessary_cast)]
+            let bits = |value: Float| value.to_bits() as u64;
+            (bits(self.kr), bits(self.kb))
+        }
+    }
+
+    impl PartialEq for LumaCoefficients {
+        fn eq(&self, other: &Self) -> bool {
+            self.to_bits() == other.to_bits()
+        }
+    }
+
+    impl Eq for LumaCoefficients {}
+
+    impl core::hash::Hash for LumaCoefficients {
+        fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+            self.to_bits().hash(state);
+        }
+    }
+
+    /// Converts linear RGB to Y'CbCr, encoding it with the [BT.709
+    /// OETF](bt601_oetf) first. Y' is in [0, 1] and Cb and Cr in [-0.5, 0.5]
+    /// for colors in [0, 1].
+    #[inline]
+    pub fn rgb_to_ycbcr(color: Vec3, coefficients: LumaCoefficients) -> Vec3 {
+        let LumaCoefficients { kr, kb } = coefficients;
+        let encoded = bt601_oetf(color, WhitePoint::D65);
+        let luma = kr * encoded.x + coefficients.kg() * encoded.y + kb * encoded.z;
+        Vec3::new(
+            luma,
+            (encoded.z - luma) / (2.0 * (1.0 - kb)),
+            (encoded.x - luma) / (2.0 * (1.0 - kr)),
+        )
+    }
+
+    /// Converts Y'CbCr to linear RGB, the inverse of [`rgb_to_ycbcr()`].
+    #[inline]
+    pub fn ycbcr_to_rgb(color: Vec3, coefficients: LumaCoefficients) -> Vec3 {
+        let LumaCoefficients { kr, kb } = coefficients;
+        let luma = color.x;
+        let red = luma + 2.0 * (1.0 - kr) * color.z;
+        let blue = luma + 2.0 * (1.0 - kb) * color.y;
+        let green = (luma - kr * red - kb * blue) / coefficients.kg();
+        bt601_oetf_inverse(Vec3::new(red, green, blue), WhitePoint::D65)
+    }
+}
+
+pub use ycbcr::*;
+
 #[cfg(test)]
 mod test {
-    use crate::ColorConversion;
+    use crate::details::color::RgbPrimaries;
     use crate::spaces;
+    use crate::{ColorConversion, ColorSpace};
 
     use super::*;
+
+    #[test]
+    fn hsx_round_trips() {
+        for rgb in [
+            Vec3::new(0.8, 0.3, 0.1),
+            Vec3::new(0.2, 0.5, 0.9),
+            Vec3::new(0.1, 0.9, 0.4),
+            Vec3::new(0.6, 0.6, 0.6),
+            Vec3::new(0.0, 0.0, 0.0),
+        ] {
+            for (forward, inverse) in [
+                (
+                    rgb_to_hsl as fn(Vec3, WhitePoint) -> Vec3,
+                    hsl_to_rgb as fn(_, _) -> _,
+                ),
+                (rgb_to_hsv, hsv_to_rgb),
+                (rgb_to_hsi, hsi_to_rgb),
+                (rgb_to_hwb, hwb_to_rgb),
+            ] {
+                let back = inverse(forward(rgb, WhitePoint::D65), WhitePoint::D65);
+                assert!(back.abs_diff_eq(rgb, 1e-5), "{back:?} != {rgb:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn ycbcr() {
+        let white = rgb_to_ycbcr(Vec3::ONE, LumaCoefficients::BT709);
+        assert!(
+            white.abs_diff_eq(Vec3::new(1.0, 0.0, 0.0), 1e-5),
+            "{white:?}"
+        );
+        let red = rgb_to_ycbcr(Vec3::new(1.0, 0.0, 0.0), LumaCoefficients::BT709);
+        assert!(
+            red.abs_diff_eq(Vec3::new(0.2126, -0.114572, 0.5), 1e-5),
+            "{red:?}"
+        );
+        let blue = rgb_to_ycbcr(Vec3::new(0.0, 0.0, 1.0), LumaCoefficients::BT2020);
+        assert!(
+            blue.abs_diff_eq(Vec3::new(0.0593, 0.5, -0.040214), 1e-5),
+            "{blue:?}"
+        );
+        for coefficients in [
+            LumaCoefficients::BT601,
+            LumaCoefficients::BT709,
+            LumaCoefficients::BT2020,
+        ] {
+            for rgb in [
+                Vec3::new(0.8, 0.3, 0.1),
+                Vec3::new(0.01, 0.5, 0.9),
+                Vec3::ZERO,
+            ] {
+                let ycbcr = rgb_to_ycbcr(rgb, coefficients);
+                let back = ycbcr_to_rgb(ycbcr, coefficients);
+                assert!(
+                    back.abs_diff_eq(rgb, 1e-5),
+                    "{coefficients:?}: {back:?} != {rgb:?}"
+                );
+            }
+        }
+
+        let conversion = ColorConversion::new(spaces::YCBCR_BT709, spaces::YCBCR_BT2020);
+        let gray = conversion.convert(Vec3::new(0.5, 0.0, 0.0));
+        assert!(gray.abs_diff_eq(Vec3::new(0.5, 0.0, 0.0), 1e-4), "{gray:?}");
+    }
+
+    #[test]
+    fn hwb() {
+        let hwb = rgb_to_hwb(Vec3::new(0.9, 0.55, 0.2), WhitePoint::D65);
+        assert!(hwb.abs_diff_eq(Vec3::new(30.0, 0.2, 0.1), 1e-5), "{hwb:?}");
+        // Whiteness and blackness summing to 1 or more give a gray.
+        for (whiteness, blackness, gray) in [(0.6, 0.6, 0.5), (0.3, 0.7, 0.3), (1.0, 0.0, 1.0)] {
+            let rgb = hwb_to_rgb(Vec3::new(200.0, whiteness, blackness), WhitePoint::D65);
+            assert!(rgb.abs_diff_eq(Vec3::splat(gray), 1e-6), "{rgb:?}");
+        }
+        let color = crate::Color::new(30.0, 0.2, 0.1, spaces::HWB);
+        let back = color.to(spaces::LINEAR_SRGB).to(spaces::HWB);
+        assert!(back.value.abs_diff_eq(color.value, 1e-4), "{back:?}");
+    }
+
+    #[test]
+    fn bt2020_code_values() {
+        // Narrow range code values per BT.2020: D = round((219 E' + 16) 2^(n - 8)).
+        let code = |encoded: Float, bits: i32| {
+            ((219.0 * encoded + 16.0) * (2.0 as Float).powi(bits - 8)).round()
+        };
+        let linear = Vec3::new(0.01, 0.18, 1.0);
+
+        let encoded = bt2020_10_bit_oetf(linear, WhitePoint::D65);
+        let codes = [
+            code(encoded.x, 10),
+            code(encoded.y, 10),
+            code(encoded.z, 10),
+        ];
+        assert_eq!(codes, [103.0, 422.0, 940.0]);
+        assert!((encoded.y - 0.409_007_7).abs() < 1e-6, "{encoded:?}");
+
+        let encoded = bt2020_12_bit_oetf(linear, WhitePoint::D65);
+        let codes = [
+            code(encoded.x, 12),
+            code(encoded.y, 12),
+            code(encoded.z, 12),
+        ];
+        assert_eq!(codes, [414.0, 1689.0, 3760.0]);
+        assert!((encoded.y - 0.408_846_4).abs() < 1e-6, "{encoded:?}");
+
+        for (oetf, inverse) in [
+            (
+                bt2020_10_bit_oetf as fn(Vec3, WhitePoint) -> Vec3,
+                bt2020_10_bit_oetf_inverse as fn(Vec3, WhitePoint) -> Vec3,
+            ),
+            (bt2020_12_bit_oetf, bt2020_12_bit_oetf_inverse),
+        ] {
+            let round_trip = inverse(oetf(linear, WhitePoint::D65), WhitePoint::D65);
+            assert!(round_trip.abs_diff_eq(linear, 1e-6), "{round_trip:?}");
+        }
+        assert_eq!(
+            crate::spaces::ENCODED_BT_2020.transform_function(),
+            Some(TransformFn::Bt2020_10Bit)
+        );
+        assert_eq!(
+            TransformFn::by_name("BT.2020"),
+            Some(TransformFn::Bt2020_10Bit)
+        );
+    }
+
+    #[test]
+    fn s_log3_code_values() {
+        let encoded = s_log3_oetf(Vec3::new(0.0, 0.18, 0.9), WhitePoint::D65) * 1023.0;
+        assert!(
+            encoded.abs_diff_eq(Vec3::new(95.0, 420.0, 598.0), 0.5),
+            "{encoded:?}"
+        );
+        for linear in [-0.01, 0.0, 0.005, S_LOG3_TOE_CUTOFF, 0.18, 10.0] {
+            let round_trip = s_log3_oetf_inverse_float(s_log3_oetf_float(linear));
+            assert!((round_trip - linear).abs() < 1e-5, "{linear}: {round_trip}");
+        }
+        // The toe joins the curve continuously.
+        let below = s_log3_oetf_float(S_LOG3_TOE_CUTOFF - 1e-6);
+        let above = s_log3_oetf_float(S_LOG3_TOE_CUTOFF);
+        assert!((above - below).abs() < 1e-4, "{below} {above}");
+    }
+
+    #[test]
+    fn pure_gamma() {
+        let gamma = TransformFn::gamma(2.2);
+        let space = ColorSpace::new(RgbPrimaries::Adobe1998, WhitePoint::D65, Some(gamma));
+        let decode = ColorConversion::new(space, space.as_linear());
+        let linear = decode.convert(Vec3::new(0.5, -0.5, 1.0));
+        let expected = (0.5 as Float).powf(2.2);
+        assert!(
+            linear.abs_diff_eq(Vec3::new(expected, -expected, 1.0), 1e-6),
+            "{linear:?}"
+        );
+        let encoded = decode.inverse().convert(linear);
+        assert!(
+            encoded.abs_diff_eq(Vec3::new(0.5, -0.5, 1.0), 1e-5),
+            "{encoded:?}"
+        );
+
+        let value = Vec3::new(0.1, 0.4, 0.9);
+        assert_eq!(
+            dci_p3_eotf(value, WhitePoint::D65),
+            gamma_decode(value, GammaExponent(2.6))
+        );
+        assert_eq!(TransformFn::by_name("Gamma 2.2"), Some(gamma));
+        assert_ne!(gamma, TransformFn::gamma(2.4));
+    }
+
+    #[test]
+    fn pq_and_hlg_anchor_points() {
+        for (nits, signal) in [
+            (0.0, 0.0),
+            (100.0, 0.508_078),
+            (1000.0, 0.751_827),
+            (10000.0, 1.0),
+        ] {
+            let encoded = st_2084_pq_eotf_inverse_float(nits);
+            assert!((encoded - signal).abs() < 1e-5, "{nits}: {encoded}");
+            let decoded = st_2084_pq_eotf_float(signal);
+            assert!(
+                (decoded - nits).abs() < nits * 1e-4 + 1e-6,
+                "{signal}: {decoded}"
+            );
+        }
+
+        let scene = Vec3::new(1.0 / 12.0, 1.0, 0.0);
+        let encoded = bt2100_hlg_oetf(scene, WhitePoint::D65);
+        assert!(
+            encoded.abs_diff_eq(Vec3::new(0.5, 1.0, 0.0), 1e-5),
+            "{encoded:?}"
+        );
+        let round_trip = bt2100_hlg_oetf_inverse(Vec3::new(0.25, 0.5, 0.75), WhitePoint::D65);
+        let round_trip = bt2100_hlg_oetf(round_trip, WhitePoint::D65);
+        assert!(
+            round_trip.abs_diff_eq(Vec3::new(0.25, 0.5, 0.75), 1e-5),
+            "{round_trip:?}"
+        );
+        assert_eq!(
+            crate::spaces::ENCODED_BT_2100_HLG.transform_function(),
+            Some(TransformFn::Hlg)
+        );
+
+        assert!((hlg_system_gamma(1000.0) - 1.2).abs() < 1e-6);
+        assert!((hlg_system_gamma(2000.0) - 1.326_43).abs() < 1e-5);
+        let white = hlg_ootf(Vec3::ONE, 1000.0);
+        assert!(white.abs_diff_eq(Vec3::splat(1000.0), 1e-2), "{white:?}");
+        let gray = hlg_ootf(Vec3::splat(0.5), 1000.0);
+        let expected = 1000.0 * (0.5 as Float).powf(1.2);
+        assert!(gray.abs_diff_eq(Vec3::splat(expected), 1e-2), "{gray:?}");
+    }
+
     #[test]
     fn ictcp_pq_inverse() {
         let to_conv = ColorConversion::new(spaces::LINEAR_SRGB, spaces::ICT_CP_PQ);
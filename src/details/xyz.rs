@@ -1,3 +1,4 @@
+use super::spectral::{Observer, SPECTRUM_SAMPLES, Spectrum};
 use crate::{Float, Mat3, Vec3};
 
 pub fn xyz_to_rgb(primaries: &[[Float; 2]; 3], white_point: &[Float; 3]) -> Mat3 {
@@ -32,3 +33,171 @@ pub fn rgb_to_xyz(primaries: &[[Float;2]; 3], white_point: &[Float;3]) -> Mat3 {
 
     base_matrix
 }
+
+/// Integrates the spectral samples, given as `(wavelength, value)` pairs with
+/// wavelengths in nanometers, against the color matching functions of
+/// `observer` in 1nm steps from 360nm to 830nm.
+///
+/// The samples may be spaced irregularly, values in between them are
+/// interpolated linearly and the spectrum is 0 outside of them. The result is
+/// not normalized, e.g. a constant emission of 1 has a Y of about 107 for the
+/// CIE 1931 observer. For a reflectance, multiply it with the illuminant, or
+/// use [`Spectrum::reflected_xyz()`] for regularly sampled data.
+///
+/// # Panics
+///
+/// If the wavelengths are not in ascending order.
+pub fn from_spectrum(samples: &[(Float, Float)], observer: Observer) -> Vec3 {
+    assert!(
+        samples.windows(2).all(|pair| pair[0].0 <= pair[1].0),
+        "spectral sample wavelengths must be in ascending order"
+    );
+    let mut xyz = Vec3::splat(0.0);
+    let mut segments = samples.windows(2).peekable();
+    for wavelength in 360..=830u16 {
+        let wavelength = wavelength as Float;
+        while segments.next_if(|pair| pair[1].0 < wavelength).is_some() {}
+        let Some(&&[(start, start_value), (end, end_value)]) = segments.peek() else {
+            break;
+        };
+        if wavelength < start {
+            continue;
+        }
+        let t = if end > start {
+            (wavelength - start) / (end - start)
+        } else {
+            0.0
+        };
+        xyz += observer.cmf(wavelength) * (start_value + (end_value - start_value) * t);
+    }
+    xyz
+}
+
+/// Returns the fundamental metamer of `xyz` lit by `illuminant`, per Cohen and
+/// Kappauf's decomposition of spectra with the matrix R.
+///
+/// With `A` the matrix of the [weighted color matching
+/// functions](Spectrum::weighted_cmfs), the fundamental metamer is
+/// `A (AᵀA)⁻¹ xyz`: the one reflectance with the given CIE XYZ which lies in
+/// the space spanned by the color matching functions. It is smooth, but not
+/// necessarily within [0, 1]. Every reflectance with the same CIE XYZ under
+/// `illuminant` is the fundamental metamer plus a [metameric
+/// black](metameric_black).
+pub fn fundamental_metamer(xyz: Vec3, illuminant: &Spectrum) -> Spectrum {
+    let cmfs = illuminant.weighted_cmfs();
+    let mut gram = [0.0; 9];
+    for cmf in &cmfs {
+        let [x, y, z] = [cmf.x, cmf.y, cmf.z];
+        for (entry, product) in gram.iter_mut().zip([
+            x * x,
+            y * x,
+            z * x,
+            x * y,
+            y * y,
+            z * y,
+            x * z,
+            y * z,
+            z * z,
+        ]) {
+            *entry += product;
+        }
+    }
+    let weights = Mat3::from_cols_array(&gram).inverse() * xyz;
+    let mut values = [0.0; SPECTRUM_SAMPLES];
+    for (value, cmf) in values.iter_mut().zip(cmfs) {
+        *value = cmf.dot(weights);
+    }
+    Spectrum(values)
+}
+
+/// Returns the metameric black of `reflectance` lit by `illuminant`, i.e.
+/// `reflectance` minus its fundamental metamer.
+///
+/// A metameric black has a CIE XYZ of zero under `illuminant`, so adding it to
+/// a reflectance gives a metamer of that reflectance.
+pub fn metameric_black(reflectance: &Spectrum, illuminant: &Spectrum) -> Spectrum {
+    let fundamental = fundamental_metamer(reflectance.reflected_xyz(illuminant), illuminant);
+    let mut values = reflectance.0;
+    for (value, fundamental) in values.iter_mut().zip(fundamental.0) {
+        *value -= fundamental;
+    }
+    Spectrum(values)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::details::spectral::planck_radiance;
+
+    #[test]
+    fn fundamental_metamer_reproduces_xyz() {
+        let illuminant = Spectrum::from_fn(|wavelength| planck_radiance(wavelength, 3000.0));
+        let xyz = Vec3::new(0.3, 0.25, 0.2);
+        let metamer = fundamental_metamer(xyz, &illuminant);
+        let reproduced = metamer.reflected_xyz(&illuminant);
+        assert!(
+            reproduced.abs_diff_eq(xyz, 1e-4),
+            "{reproduced:?} != {xyz:?}"
+        );
+    }
+
+    #[test]
+    fn metameric_black_is_invisible() {
+        let illuminant = Spectrum::constant(1.0);
+        let reflectance = Spectrum::from_fn(|wavelength| 0.5 + 0.4 * (wavelength / 40.0).sin());
+        let black = metameric_black(&reflectance, &illuminant);
+        let xyz = black.reflected_xyz(&illuminant);
+        assert!(xyz.abs_diff_eq(Vec3::splat(0.0), 1e-4), "{xyz:?}");
+        assert!(black.0.iter().any(|value| value.abs() > 0.1));
+    }
+
+    #[test]
+    fn spectrum_samples_to_xyz() {
+        // An equal energy spectrum has the chromaticity of illuminant E for
+        // both observers.
+        let tolerance = if cfg!(feature = "cie-cmf-tables") {
+            1e-4
+        } else {
+            5e-3
+        };
+        for observer in [Observer::Cie1931TwoDegree, Observer::Cie1964TenDegree] {
+            let xyz = from_spectrum(&[(360.0, 1.0), (830.0, 1.0)], observer);
+            let sum = xyz.x + xyz.y + xyz.z;
+            let (x, y) = (xyz.x / sum, xyz.y / sum);
+            assert!(
+                (x - 1.0 / 3.0).abs() < tolerance && (y - 1.0 / 3.0).abs() < tolerance,
+                "{observer:?}: {x} {y}"
+            );
+        }
+
+        // Irregular samples are interpolated linearly, like sampling the same
+        // piecewise linear spectrum densely.
+        let ramp = |wavelength: Float| {
+            if wavelength < 450.0 {
+                0.2 + 0.6 * (wavelength - 400.0) / 50.0
+            } else {
+                0.8 - 0.7 * (wavelength - 450.0) / 250.0
+            }
+        };
+        let irregular = from_spectrum(
+            &[(400.0, 0.2), (450.0, 0.8), (700.0, 0.1)],
+            Observer::Cie1931TwoDegree,
+        );
+        let dense: [(Float, Float); 301] = core::array::from_fn(|index| {
+            let wavelength = 400.0 + index as Float;
+            (wavelength, ramp(wavelength))
+        });
+        let dense = from_spectrum(&dense, Observer::Cie1931TwoDegree);
+        assert!(
+            irregular.abs_diff_eq(dense, 1e-3),
+            "{irregular:?} != {dense:?}"
+        );
+
+        assert_eq!(
+            from_spectrum(&[], Observer::Cie1931TwoDegree),
+            Vec3::splat(0.0)
+        );
+        let narrow = from_spectrum(&[(550.0, 1.0), (550.0, 1.0)], Observer::Cie1931TwoDegree);
+        assert!(narrow.abs_diff_eq(Observer::Cie1931TwoDegree.cmf(550.0), 1e-6));
+    }
+}
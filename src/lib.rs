@@ -10,13 +10,17 @@
 //! notable color spaces and models:
 //!
 //! * sRGB/linear sRGB/BT.709
-//! * BT.2020
+//! * BT.2020/BT.2100 PQ/BT.2100 HLG
 //! * ACEScg
 //! * ACES2065-1
+//! * Display P3/DCI-P3
 //! * Oklab
+//! * CAM16-UCS
 //! * CIE LAB/Lch/Luv/xyY/uvV
-//! * HSL/HSV/HSI
+//! * HSL/HSV/HSI/HWB
 //! * ICtCp
+//! * Y'CbCr (BT.709/BT.2020)
+//! * Sony S-Gamut3/S-Gamut3.Cine with S-Log3
 //!
 //! You can also construct custom [`ColorSpace`]s from a combination of
 //! primaries, whitepoint and transform function.
@@ -65,9 +69,12 @@
 //! to convert between popular spaces, but for GPU contexts, these
 //! implementations clearly can't be used directly. To implement data-driven
 //! conversions, you can read the required operations for transforming between
-//! spaces from a `ColorConversion` value and run these as appropriate.
+//! spaces from a `ColorConversion` value with
+//! [`operations()`](ColorConversion::operations) and run these as appropriate.
 //! Feel free to port the implementations in the
-//! [`transform`](`details::transform`) module to your shaders or other code.
+//! [`transform`](`details::transform`) module to your shaders or other code,
+//! or bake conversions into lookup tables with the `lut` module, which
+//! requires the `std` feature.
 //!
 //! ### Gamut-Agnostic Transforms
 //!
@@ -99,6 +106,12 @@
 //! on-demand, meaning the creation will be a bit slower to create than if there
 //! is a constant matrix available.
 //!
+//! ### Allocations
+//!
+//! Converting colors with [`Color::to()`] or a [`ColorConversion`] never
+//! allocates, including when the conversion matrix is calculated on-demand,
+//! so conversions are safe to use in real-time code.
+//!
 //! ### Chromatic Adaptation Transformation (CAT)
 //!
 //! `kolor` implements CAT in the [`cat`](details::cat) module and supports the
@@ -108,7 +121,9 @@
 //! another.
 //!
 //! Use [`ColorSpace::with_whitepoint()`] to change the `WhitePoint` for a color
-//! space.
+//! space. Conversions adapt in the [`Sharp`][details::cat::LmsConeSpace::Sharp]
+//! cone space by default; [`ColorConversion::with_cat()`] selects another
+//! method, e.g. Bradford or plain XYZ scaling.
 //!
 //! ### XYZ-RGB Conversions
 //!
@@ -152,19 +167,90 @@ macro_rules! const_mat3 {
     };
 }
 
+/// Expands to the bundled `Mat3` converting from the source to the
+/// destination [`RgbPrimaries`][details::color::RgbPrimaries] and
+/// [`WhitePoint`][details::color::WhitePoint], evaluated at compile time.
+///
+/// Fails to compile if there is no bundled matrix for the combination, i.e.
+/// if [`LinearColorConversion`][details::conversion::LinearColorConversion]
+/// would calculate it on demand, e.g. for `Custom` primaries. Identical
+/// primaries and white points expand to the identity.
+///
+/// ```
+/// use kolor::details::color::{RgbPrimaries, WhitePoint};
+///
+/// const SRGB_TO_ACES_CG: kolor::Mat3 = kolor::const_conversion_matrix!(
+///     RgbPrimaries::Bt709,
+///     WhitePoint::D65,
+///     RgbPrimaries::AcesAp1,
+///     WhitePoint::D60
+/// );
+/// ```
+///
+/// ```compile_fail
+/// use kolor::details::color::{RgbPrimaries, WhitePoint};
+///
+/// const CUSTOM: kolor::Mat3 = kolor::const_conversion_matrix!(
+///     RgbPrimaries::Bt709,
+///     WhitePoint::D65,
+///     RgbPrimaries::Custom([[0.7, 0.3], [0.2, 0.7], [0.1, 0.1]]),
+///     WhitePoint::D65
+/// );
+/// ```
+#[cfg(feature = "color-matrices")]
+#[macro_export]
+macro_rules! const_conversion_matrix {
+    ($src_primaries:expr, $src_wp:expr, $dst_primaries:expr, $dst_wp:expr $(,)?) => {
+        const {
+            match $crate::details::generated_matrices::const_conversion_matrix(
+                $src_primaries,
+                $src_wp,
+                $dst_primaries,
+                $dst_wp,
+            ) {
+                Some(mat) => mat,
+                None => panic!("no bundled conversion matrix between these spaces"),
+            }
+        }
+    };
+}
+
 #[cfg(not(feature = "f64"))]
 pub(crate) use core::f32::consts::{PI, TAU};
 #[cfg(feature = "f64")]
 pub(crate) use core::f64::consts::{PI, TAU};
 
 pub mod details {
+    pub mod adjust;
+    pub mod appearance;
     pub mod cat;
+    pub mod cmyk;
     pub mod color;
+    pub mod contrast;
     pub mod conversion;
+    pub mod css;
+    pub mod diagram;
+    pub mod difference;
+    pub mod gamut;
     #[allow(clippy::excessive_precision)]
     #[cfg(feature = "color-matrices")]
     pub mod generated_matrices;
+    pub mod gradient;
+    pub mod icc;
+    #[cfg(feature = "std")]
+    pub mod lut;
     pub mod math;
+    pub mod mipmap;
+    pub mod packed;
+    pub mod palette;
+    #[cfg(any(test, feature = "reference-data"))]
+    pub mod reference;
+    #[cfg(feature = "std")]
+    pub mod registry;
+    #[allow(clippy::excessive_precision)]
+    pub mod spectral;
+    #[cfg(feature = "test-utils")]
+    pub mod testing;
     #[allow(clippy::excessive_precision)]
     #[allow(clippy::many_single_char_names)]
     #[allow(non_snake_case)]
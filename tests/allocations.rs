@@ -0,0 +1,79 @@
+//! Verifies that conversions never allocate, with an allocator counting the
+//! allocations of the current thread.
+use kolor::{
+    Color, ColorConversion, ColorSpace,
+    details::color::{RgbPrimaries, WhitePoint},
+    spaces,
+};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the number of allocations made by `f` on the current thread.
+fn count_allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn allocations_are_counted() {
+    assert_eq!(
+        count_allocations(|| drop(std::hint::black_box(vec![1u8]))),
+        1
+    );
+}
+
+#[test]
+fn conversions_between_built_in_spaces_do_not_allocate() {
+    let allocations = count_allocations(|| {
+        for &src in spaces::ALL_COLOR_SPACES.iter() {
+            for &dst in spaces::ALL_COLOR_SPACES.iter() {
+                let color = Color::new(0.25, 0.5, 0.75, src);
+                std::hint::black_box(color.to(dst));
+                let conversion = ColorConversion::new(src, dst);
+                std::hint::black_box(conversion.convert(color.value));
+                let mut values = [color.value; 4];
+                conversion.convert_slice(&mut values);
+                std::hint::black_box(values);
+            }
+        }
+    });
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn on_demand_conversions_do_not_allocate() {
+    let custom = ColorSpace::new(
+        RgbPrimaries::Custom([[0.7, 0.3], [0.2, 0.7], [0.1, 0.1]]),
+        WhitePoint::Custom([0.97, 1.0, 1.1]),
+        None,
+    );
+    let allocations = count_allocations(|| {
+        let color = Color::srgb(0.25, 0.5, 0.75);
+        std::hint::black_box(color.to(custom));
+        std::hint::black_box(ColorConversion::new(custom, spaces::OK_LCH).convert(color.value));
+    });
+    assert_eq!(allocations, 0);
+}
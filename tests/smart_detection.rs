@@ -103,3 +103,27 @@ fn aces_ap1_detection() {
     let primaries = RgbPrimaries::from_rgb_xy([0.713, 0.293], [0.165, 0.830], [0.128, 0.044]);
     assert!(matches!(primaries, RgbPrimaries::AcesAp1));
 }
+
+#[test]
+fn detect_s_gamut3_variants() {
+    let s_gamut3 = RgbPrimaries::from_rgb_xy([0.730, 0.280], [0.140, 0.855], [0.100, -0.050]);
+    assert!(matches!(s_gamut3, RgbPrimaries::SGamut3));
+
+    let cine = RgbPrimaries::from_rgb_xy([0.766, 0.275], [0.225, 0.800], [0.089, -0.087]);
+    assert!(matches!(cine, RgbPrimaries::SGamut3Cine));
+
+    let venice = RgbPrimaries::from_rgb_xy(
+        [0.740_464_3, 0.279_364_4],
+        [0.089_241_1, 0.893_809_5],
+        [0.110_488_2, -0.052_579_3],
+    );
+    assert!(matches!(venice, RgbPrimaries::VeniceSGamut3));
+
+    let mut venice_cine = RgbPrimaries::Custom([
+        [0.775_901_9, 0.274_502_4],
+        [0.188_682_9, 0.828_684_9],
+        [0.101_337_4, -0.089_187_5],
+    ]);
+    assert!(venice_cine.canonicalize().is_ok());
+    assert!(matches!(venice_cine, RgbPrimaries::VeniceSGamut3Cine));
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "test-utils")]
+
+use kolor::{Color, assert_color_eq, assert_in_gamut, spaces};
+
+fn panic_message(f: impl FnOnce() + std::panic::UnwindSafe) -> String {
+    let payload = std::panic::catch_unwind(f).unwrap_err();
+    payload
+        .downcast_ref::<String>()
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[test]
+fn assert_color_eq_converts_into_common_space() {
+    let color = Color::srgb(0.25, 0.5, 0.75);
+    assert_color_eq!(color, color.to(spaces::OK_LAB), 1e-5);
+
+    let message = panic_message(|| assert_color_eq!(color, Color::srgb(0.25, 0.5, 0.8), 1e-3));
+    assert!(message.contains("ΔE2000"), "{message}");
+    assert!(message.contains("0.8"), "{message}");
+}
+
+#[test]
+fn assert_in_gamut_reports_linear_rgb() {
+    assert_in_gamut!(Color::srgb(1.0, 0.0, 0.5), spaces::ENCODED_SRGB);
+
+    let p3_green = Color::new(0.0, 1.0, 0.0, spaces::ENCODED_DISPLAY_P3);
+    assert_in_gamut!(p3_green, spaces::DISPLAY_P3);
+    let message = panic_message(|| assert_in_gamut!(p3_green, spaces::ENCODED_SRGB));
+    assert!(message.contains("outside of the gamut"), "{message}");
+    assert!(message.contains("linear RGB"), "{message}");
+}